@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::diagnostic::Diagnostic;
+use crate::walk::{absolute_path, relative_path};
+
+const BASELINE_VERSION: u32 = 1;
+
+/// Identifies a finding independently of the line it is on.
+///
+/// Instead of a line number the fingerprint hashes the text of the line the
+/// finding is on along with its message, so code being added or removed
+/// above a finding doesn't make it look new. Paths in the message are made
+/// relative to the baseline first, so it doesn't matter how the paths being
+/// checked were spelled.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+struct Fingerprint {
+    project: String,
+    file: String,
+    rule: String,
+    hash: String,
+}
+
+/// The baseline file, with a finding for each recorded occurrence.
+#[derive(Serialize, Deserialize)]
+struct BaselineFile {
+    version: u32,
+    findings: Vec<Fingerprint>,
+}
+
+/// Findings recorded by `--write-baseline`, which `--baseline` then stops
+/// reporting so only new findings fail a build.
+///
+/// Paths are stored relative to the directory the baseline file is in, so
+/// the baseline can be committed and used from any checkout.
+pub struct Baseline {
+    root: PathBuf,
+    /// How many times each fingerprint was recorded. Identical findings on
+    /// identical lines of a file are matched one for one.
+    fingerprints: HashMap<Fingerprint, usize>,
+    source_lines: HashMap<PathBuf, Vec<String>>,
+}
+
+impl Baseline {
+    /// Creates an empty baseline to be saved at `path`.
+    pub fn new(path: &Path) -> Self {
+        let root = absolute_path(path)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        Baseline {
+            root,
+            fingerprints: HashMap::new(),
+            source_lines: HashMap::new(),
+        }
+    }
+
+    /// Reads a baseline previously written with [`Baseline::save`].
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Unable to read baseline '{}': {}", path.display(), e))?;
+
+        let invalid =
+            |reason: &str| anyhow!("Baseline '{}' is not valid: {}", path.display(), reason);
+
+        let document: BaselineFile =
+            serde_json::from_str(&contents).map_err(|e| invalid(&e.to_string()))?;
+
+        if document.version != BASELINE_VERSION {
+            return Err(invalid("unsupported version"));
+        }
+
+        let mut baseline = Baseline::new(path);
+        for fingerprint in document.findings {
+            *baseline.fingerprints.entry(fingerprint).or_default() += 1;
+        }
+
+        Ok(baseline)
+    }
+
+    /// Records `diagnostic`, found while checking `project_path`.
+    pub fn insert(&mut self, project_path: &Path, diagnostic: &Diagnostic) {
+        let fingerprint = self.fingerprint(project_path, diagnostic);
+        *self.fingerprints.entry(fingerprint).or_default() += 1;
+    }
+
+    /// Checks if `diagnostic` is in the baseline, using up one of the
+    /// recorded occurrences of it if so.
+    pub fn contains(&mut self, project_path: &Path, diagnostic: &Diagnostic) -> bool {
+        let fingerprint = self.fingerprint(project_path, diagnostic);
+
+        match self.fingerprints.get_mut(&fingerprint) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The number of recorded findings.
+    pub fn len(&self) -> usize {
+        self.fingerprints.values().sum()
+    }
+
+    /// Writes the baseline to `path` as JSON, sorted so it diffs cleanly.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut fingerprints: Vec<_> = self.fingerprints.iter().collect();
+        fingerprints.sort();
+
+        let document = BaselineFile {
+            version: BASELINE_VERSION,
+            findings: fingerprints
+                .into_iter()
+                .flat_map(|(fingerprint, &count)| std::iter::repeat_n(fingerprint.clone(), count))
+                .collect(),
+        };
+
+        let contents = serde_json::to_string_pretty(&document)?;
+        std::fs::write(path, contents + "\n")
+            .map_err(|e| anyhow!("Unable to write baseline '{}': {}", path.display(), e))
+    }
+
+    fn fingerprint(&mut self, project_path: &Path, diagnostic: &Diagnostic) -> Fingerprint {
+        let line = diagnostic
+            .region
+            .and_then(|region| {
+                self.source_lines(&diagnostic.path)
+                    .get(region.line.checked_sub(1)?)
+            })
+            .map(|line| line.trim().to_owned())
+            .unwrap_or_default();
+
+        let content = format!("{}\n{}", line, self.normalize_paths(&diagnostic.message));
+
+        Fingerprint {
+            project: relative_path(&self.root, &absolute_path(project_path)),
+            file: relative_path(&self.root, &absolute_path(&diagnostic.path)),
+            rule: diagnostic.kind.id().to_owned(),
+            hash: format!("{:016x}", fnv1a(content.as_bytes())),
+        }
+    }
+
+    /// Rewrites each word of `message` that looks like a path relative to
+    /// the baseline's directory, with `/` separators.
+    fn normalize_paths(&self, message: &str) -> String {
+        message
+            .split(' ')
+            .map(|word| {
+                let path = word.trim_matches(['\'', '"', '(', ')', ',', ';']);
+                let path = path.strip_suffix(':').unwrap_or(path);
+
+                if !is_path_like(path) {
+                    return word.to_owned();
+                }
+
+                let relative = relative_path(&self.root, &absolute_path(Path::new(path)));
+                word.replacen(path, &relative, 1)
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn source_lines(&mut self, path: &Path) -> &[String] {
+        self.source_lines
+            .entry(path.to_path_buf())
+            .or_insert_with(|| {
+                std::fs::read(path)
+                    .map(|contents| {
+                        String::from_utf8_lossy(&contents)
+                            .lines()
+                            .map(str::to_owned)
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            })
+    }
+}
+
+/// Checks if a word of a message names a file or directory, either by its
+/// separators or by the extension of a VB6 file or build output.
+fn is_path_like(word: &str) -> bool {
+    const EXTENSIONS: [&str; 10] = [
+        "vbp", "vbg", "bas", "cls", "frm", "ctl", "dob", "dll", "ocx", "exe",
+    ];
+
+    let has_extension = Path::new(word).extension().is_some_and(|extension| {
+        EXTENSIONS
+            .iter()
+            .any(|known| extension.eq_ignore_ascii_case(known))
+    });
+
+    word.contains(['/', '\\']) || has_extension
+}
+
+/// A 64 bit FNV-1a hash, used since the standard library's hashers don't
+/// promise to be stable between releases.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::{DiagnosticKind, Region};
+
+    #[test]
+    fn findings_survive_line_shifts() {
+        let directory = std::env::temp_dir().join(format!("aspen-baseline-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let baseline_path = directory.join("aspen-baseline.json");
+        let project_path = directory.join("P.vbp");
+        let module_path = directory.join("M.bas");
+
+        let finding = |line| {
+            Diagnostic::new(
+                DiagnosticKind::ImplicitVariant,
+                &module_path,
+                "Variable 'X' is implicitly Variant",
+            )
+            .with_region(Some(Region { line, column: 1 }))
+        };
+
+        std::fs::write(&module_path, "Attribute VB_Name = \"M\"\r\nPublic X\r\n").unwrap();
+
+        let mut baseline = Baseline::new(&baseline_path);
+        baseline.insert(&project_path, &finding(2));
+        baseline.save(&baseline_path).unwrap();
+
+        std::fs::write(
+            &module_path,
+            "Attribute VB_Name = \"M\"\r\nPublic Y\r\nPublic X\r\n",
+        )
+        .unwrap();
+
+        let mut baseline = Baseline::load(&baseline_path).unwrap();
+        assert_eq!(baseline.len(), 1);
+        assert!(!baseline.contains(&project_path, &finding(2)));
+        assert!(baseline.contains(&project_path, &finding(3)));
+        assert!(!baseline.contains(&project_path, &finding(3)));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn findings_survive_path_spellings() {
+        use crate::check::{join_parent_project_path, project_directory};
+
+        let directory =
+            std::env::temp_dir().join(format!("aspen-baseline-spellings-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let directory = directory.canonicalize().unwrap();
+        let baseline_path = directory.join("aspen-baseline.json");
+
+        // The same project, once by its absolute path and once relative to
+        // the current directory, as `../../tmp/.../R.vbp`.
+        let absolute_project = directory.join("R.vbp");
+        let current_dir = std::env::current_dir().unwrap();
+        let relative_project = PathBuf::from("../".repeat(current_dir.components().count() - 1))
+            .join(absolute_project.strip_prefix("/").unwrap());
+
+        let finding = |project_path: &Path| {
+            let module_path = join_parent_project_path(project_directory(project_path), "M.bas");
+            Diagnostic::new(
+                DiagnosticKind::MissingFile,
+                project_path,
+                format!("Module not found: {}", module_path.display()),
+            )
+        };
+
+        let mut baseline = Baseline::new(&baseline_path);
+        baseline.insert(&absolute_project, &finding(&absolute_project));
+        baseline.save(&baseline_path).unwrap();
+
+        let mut baseline = Baseline::load(&baseline_path).unwrap();
+        assert!(baseline.contains(&relative_project, &finding(&relative_project)));
+
+        let mut baseline = Baseline::new(&baseline_path);
+        baseline.insert(&relative_project, &finding(&relative_project));
+        assert!(baseline.contains(&absolute_project, &finding(&absolute_project)));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
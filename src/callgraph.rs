@@ -0,0 +1,797 @@
+//! A procedure level call graph of a project, for `aspen callgraph`.
+//!
+//! Calls are found by name in the code of each procedure, so calls made
+//! through `CallByName`, or through `Object` and `Variant` variables, can only
+//! be matched up by the name of the member they call.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::builder::PossibleValue;
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::check::SourceFileKind;
+use crate::code::{Procedure, ProcedureKind, Visibility};
+use crate::sources::{is_event_handler, SourceFile, Sources};
+
+/// How `aspen callgraph` writes the graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GraphFormat {
+    /// A Graphviz DOT graph.
+    #[default]
+    Dot,
+    /// A JSON document for scripts.
+    Json,
+}
+
+impl ValueEnum for GraphFormat {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[GraphFormat::Dot, GraphFormat::Json]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            GraphFormat::Dot => PossibleValue::new("dot").help("a Graphviz DOT graph"),
+            GraphFormat::Json => PossibleValue::new("json").help("a JSON document for scripts"),
+        })
+    }
+}
+
+/// A procedure in the graph. The `Get`, `Let` and `Set` procedures of a
+/// property are one node.
+pub struct Node<'a> {
+    pub file: &'a SourceFile,
+    pub procedure: &'a Procedure,
+    /// Whether VB6 calls the procedure when an event is raised.
+    pub is_event_handler: bool,
+    /// Whether the procedure runs when the program starts: `Sub Main`, or the
+    /// startup form's own event handlers.
+    pub is_entry_point: bool,
+}
+
+impl Node<'_> {
+    /// The name the procedure is qualified with in the graph, such as
+    /// `Module1.Main`.
+    pub fn id(&self) -> String {
+        format!("{}.{}", self.file.name, self.procedure.name)
+    }
+}
+
+/// A call from one procedure to another.
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    /// The line of the first call.
+    pub line: usize,
+    /// Whether the call is made on an object of unknown type, so it is
+    /// assumed to reach every class and form member of that name.
+    pub is_late_bound: bool,
+}
+
+/// The procedures of a project and the calls between them.
+pub struct CallGraph<'a> {
+    pub nodes: Vec<Node<'a>>,
+    pub edges: Vec<Edge>,
+}
+
+impl<'a> CallGraph<'a> {
+    pub fn build(sources: &'a Sources) -> CallGraph<'a> {
+        let mut nodes: Vec<Node<'a>> = Vec::new();
+        // The nodes of each file by lower case procedure name.
+        let mut file_nodes: Vec<HashMap<String, usize>> = Vec::new();
+
+        for file in &sources.files {
+            let event_sources = file.event_sources();
+            let mut by_name = HashMap::new();
+
+            for procedure in &file.code.procedures {
+                let key = procedure.name.to_ascii_lowercase();
+                if by_name.contains_key(&key) {
+                    continue;
+                }
+
+                let is_event_handler = file.kind != SourceFileKind::Module
+                    && is_event_handler(&procedure.name, &event_sources);
+
+                by_name.insert(key, nodes.len());
+                nodes.push(Node {
+                    file,
+                    procedure,
+                    is_event_handler,
+                    is_entry_point: is_entry_point(sources, file, procedure, is_event_handler),
+                });
+            }
+
+            file_nodes.push(by_name);
+        }
+
+        let files_by_name: HashMap<String, usize> = sources
+            .files
+            .iter()
+            .enumerate()
+            .map(|(index, file)| (file.name.to_ascii_lowercase(), index))
+            .collect();
+
+        let resolver = Resolver {
+            sources,
+            nodes: &nodes,
+            file_nodes: &file_nodes,
+            files_by_name: &files_by_name,
+        };
+
+        // Keyed by the ends of the edge, so each call is listed once. The
+        // calls of every procedure of a property belong to its node.
+        let mut edges: BTreeMap<(usize, usize), Edge> = BTreeMap::new();
+        for (file_index, file) in sources.files.iter().enumerate() {
+            for procedure in &file.code.procedures {
+                let from = file_nodes[file_index][&procedure.name.to_ascii_lowercase()];
+
+                for (to, line, is_late_bound) in resolver.calls(file_index, procedure) {
+                    edges.entry((from, to)).or_insert(Edge {
+                        from,
+                        to,
+                        line,
+                        is_late_bound,
+                    });
+                }
+            }
+        }
+
+        CallGraph {
+            nodes,
+            edges: edges.into_values().collect(),
+        }
+    }
+}
+
+impl CallGraph<'_> {
+    /// Which nodes can be reached by following calls from `roots`.
+    pub fn reachable_from(&self, roots: impl IntoIterator<Item = usize>) -> Vec<bool> {
+        let mut reachable = vec![false; self.nodes.len()];
+        let mut pending: Vec<usize> = roots.into_iter().collect();
+
+        while let Some(node) = pending.pop() {
+            if reachable[node] {
+                continue;
+            }
+            reachable[node] = true;
+
+            pending.extend(
+                self.edges
+                    .iter()
+                    .filter(|edge| edge.from == node)
+                    .map(|edge| edge.to),
+            );
+        }
+
+        reachable
+    }
+}
+
+/// Checks if a procedure runs when the program starts.
+fn is_entry_point(
+    sources: &Sources,
+    file: &SourceFile,
+    procedure: &Procedure,
+    is_event_handler: bool,
+) -> bool {
+    match (&sources.startup, file.kind) {
+        // Files given on their own may come from any kind of project.
+        (None, SourceFileKind::Module) => procedure.name.eq_ignore_ascii_case("Main"),
+        (Some(startup), SourceFileKind::Module) => {
+            startup.eq_ignore_ascii_case("Sub Main") && procedure.name.eq_ignore_ascii_case("Main")
+        }
+        (Some(startup), SourceFileKind::Form) => {
+            let is_form_event = procedure.name.split_once('_').is_some_and(|(source, _)| {
+                source.eq_ignore_ascii_case("Form") || source.eq_ignore_ascii_case("MDIForm")
+            });
+
+            is_event_handler && is_form_event && startup.eq_ignore_ascii_case(&file.name)
+        }
+        _ => false,
+    }
+}
+
+/// Types whose members are only looked up when they are called.
+const LATE_BOUND_TYPES: [&str; 4] = ["object", "variant", "control", "form"];
+
+/// What the object a member is called on is.
+enum Target {
+    /// A class, form, or module of the project.
+    File(usize),
+    /// An object from outside the project, such as a control or an
+    /// `ADODB.Recordset`.
+    External,
+    /// An object of unknown type, such as a `Variant` or the object of a
+    /// `With` block.
+    Unknown,
+}
+
+/// Finds the procedures a call by name can reach.
+struct Resolver<'a, 'b> {
+    sources: &'a Sources,
+    nodes: &'b [Node<'a>],
+    file_nodes: &'b [HashMap<String, usize>],
+    files_by_name: &'b HashMap<String, usize>,
+}
+
+impl Resolver<'_, '_> {
+    /// The procedures `procedure` in the file at `file_index` calls, with the
+    /// line of each call and whether it is late bound.
+    fn calls(&self, file_index: usize, procedure: &Procedure) -> Vec<(usize, usize, bool)> {
+        let file = &self.sources.files[file_index];
+
+        // The lower case type of each variable in scope, local ones first.
+        let local_variables = procedure.local_variables();
+        let mut variable_types: HashMap<String, Option<String>> = HashMap::new();
+        let variables = local_variables
+            .iter()
+            .map(|variable| (variable.name.clone(), variable.type_name.clone()))
+            .chain(
+                procedure
+                    .parameters
+                    .iter()
+                    .map(|parameter| (parameter.name.clone(), parameter.type_name.clone())),
+            )
+            .chain(
+                file.code
+                    .variables
+                    .iter()
+                    .map(|variable| (variable.name.clone(), variable.type_name.clone())),
+            );
+        for (name, type_name) in variables {
+            variable_types
+                .entry(name.to_ascii_lowercase())
+                .or_insert_with(|| type_name.map(|type_name| class_name(&type_name)));
+        }
+        let locals: Vec<String> = local_variables
+            .iter()
+            .map(|variable| variable.name.to_ascii_lowercase())
+            .chain(
+                procedure
+                    .parameters
+                    .iter()
+                    .map(|parameter| parameter.name.to_ascii_lowercase()),
+            )
+            .collect();
+
+        let mut calls = Vec::new();
+
+        for statement in &procedure.body {
+            let tokens = &statement.tokens;
+
+            for (position, token) in tokens.iter().enumerate() {
+                if !token.starts_with(|character: char| character.is_alphabetic()) {
+                    continue;
+                }
+                let key = token.to_ascii_lowercase();
+
+                let is_member = position > 0 && tokens[position - 1] == ".";
+                if !is_member {
+                    // A function's name on the left of an assignment sets its
+                    // return value.
+                    let is_assigned = match position {
+                        0 => true,
+                        1 => ["Set", "Let"]
+                            .iter()
+                            .any(|keyword| tokens[0].eq_ignore_ascii_case(keyword)),
+                        _ => false,
+                    };
+                    let is_return_value = is_assigned
+                        && tokens.get(position + 1).map(String::as_str) == Some("=")
+                        && key == procedure.name.to_ascii_lowercase();
+                    if is_return_value || locals.contains(&key) {
+                        continue;
+                    }
+
+                    let targets = self.unqualified(file_index, &key);
+                    calls.extend(targets.into_iter().map(|to| (to, statement.line, false)));
+                    continue;
+                }
+
+                // The members of `With` blocks are called on an object of
+                // unknown type.
+                let qualifier = position
+                    .checked_sub(2)
+                    .map(|position| tokens[position].to_ascii_lowercase())
+                    .filter(|qualifier| {
+                        qualifier.starts_with(|character: char| character.is_alphabetic())
+                    });
+
+                let target = match qualifier.as_deref() {
+                    None => Target::Unknown,
+                    Some("me") => Target::File(file_index),
+                    // Variables hide files of the same name.
+                    Some(qualifier) => match variable_types.get(qualifier) {
+                        Some(Some(type_name))
+                            if !LATE_BOUND_TYPES.contains(&type_name.as_str()) =>
+                        {
+                            match self.files_by_name.get(type_name) {
+                                Some(&target_file) => Target::File(target_file),
+                                None => Target::External,
+                            }
+                        }
+                        Some(_) => Target::Unknown,
+                        // Controls, and objects such as `App` or `VBA`.
+                        None => match self.files_by_name.get(qualifier) {
+                            Some(&target_file) => Target::File(target_file),
+                            None => Target::External,
+                        },
+                    },
+                };
+
+                match target {
+                    Target::File(target_file) => {
+                        if let Some(to) = self.member(target_file, &key, target_file == file_index)
+                        {
+                            calls.push((to, statement.line, false));
+                        }
+                    }
+                    Target::External => {}
+                    Target::Unknown => calls.extend(
+                        self.late_bound(&key)
+                            .into_iter()
+                            .map(|to| (to, statement.line, true)),
+                    ),
+                }
+            }
+        }
+
+        calls
+    }
+
+    /// The procedures an unqualified name can call: those of its own file,
+    /// otherwise the public ones of modules.
+    fn unqualified(&self, file_index: usize, key: &str) -> Vec<usize> {
+        if let Some(&to) = self.file_nodes[file_index].get(key) {
+            return vec![to];
+        }
+
+        self.sources
+            .files
+            .iter()
+            .enumerate()
+            .filter(|(_, file)| file.kind == SourceFileKind::Module)
+            .filter_map(|(index, _)| self.member(index, key, false))
+            .collect()
+    }
+
+    /// The procedure of a file called `key`, if it can be called from another
+    /// file or `is_own_file`.
+    fn member(&self, file_index: usize, key: &str, is_own_file: bool) -> Option<usize> {
+        let &to = self.file_nodes[file_index].get(key)?;
+        let is_visible = is_own_file || self.nodes[to].procedure.visibility != Visibility::Private;
+
+        is_visible.then_some(to)
+    }
+
+    /// The public procedures of classes and forms a late bound call could
+    /// reach.
+    fn late_bound(&self, key: &str) -> Vec<usize> {
+        self.sources
+            .files
+            .iter()
+            .enumerate()
+            .filter(|(_, file)| file.kind != SourceFileKind::Module)
+            .filter_map(|(index, _)| self.member(index, key, false))
+            .collect()
+    }
+}
+
+/// The lower case name of the class a type names, without the project or
+/// library it's qualified with.
+fn class_name(type_name: &str) -> String {
+    type_name
+        .rsplit('.')
+        .next()
+        .unwrap_or(type_name)
+        .to_ascii_lowercase()
+}
+
+pub fn callgraph_subcommand(paths: &[PathBuf], format: GraphFormat) -> Result<bool> {
+    let (all_sources, errors) = Sources::load(paths);
+    for error in &errors {
+        eprintln!("{}", error);
+    }
+
+    let graphs: Vec<(&Sources, CallGraph)> = all_sources
+        .iter()
+        .map(|sources| (sources, CallGraph::build(sources)))
+        .collect();
+
+    match format {
+        GraphFormat::Dot => {
+            for (sources, graph) in &graphs {
+                print!("{}", graph_dot(sources, graph));
+            }
+        }
+        GraphFormat::Json => {
+            let json: Vec<GraphJson> = graphs
+                .iter()
+                .map(|(sources, graph)| graph_json(sources, graph))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+    }
+
+    Ok(errors.is_empty())
+}
+
+/// Writes a graph with a cluster for each file. Entry points are drawn with
+/// a double border and event handlers as ellipses.
+fn graph_dot(sources: &Sources, graph: &CallGraph) -> String {
+    let mut dot = String::new();
+    let name = sources
+        .display_project()
+        .unwrap_or_else(|| "callgraph".to_owned());
+
+    let _ = writeln!(dot, "digraph {} {{", quote(&name));
+    let _ = writeln!(dot, "    rankdir=LR;");
+    let _ = writeln!(dot, "    node [shape=box];");
+
+    for file in &sources.files {
+        let nodes: Vec<&Node> = graph
+            .nodes
+            .iter()
+            .filter(|node| std::ptr::eq(node.file, file))
+            .collect();
+        if nodes.is_empty() {
+            continue;
+        }
+
+        let _ = writeln!(
+            dot,
+            "    subgraph {} {{",
+            quote(&format!("cluster_{}", file.name))
+        );
+        let _ = writeln!(dot, "        label={};", quote(&file.name));
+        for node in nodes {
+            let mut attributes = vec![format!("label={}", quote(&node.procedure.name))];
+            if node.is_event_handler {
+                attributes.push("shape=ellipse".to_owned());
+            }
+            if node.is_entry_point {
+                attributes.push("peripheries=2".to_owned());
+            }
+            let _ = writeln!(
+                dot,
+                "        {} [{}];",
+                quote(&node.id()),
+                attributes.join(", ")
+            );
+        }
+        let _ = writeln!(dot, "    }}");
+    }
+
+    for edge in &graph.edges {
+        let style = if edge.is_late_bound {
+            " [style=dashed]"
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            dot,
+            "    {} -> {}{};",
+            quote(&graph.nodes[edge.from].id()),
+            quote(&graph.nodes[edge.to].id()),
+            style
+        );
+    }
+
+    let _ = writeln!(dot, "}}");
+    dot
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[derive(Serialize)]
+struct GraphJson {
+    project: Option<String>,
+    nodes: Vec<NodeJson>,
+    edges: Vec<EdgeJson>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NodeJson {
+    id: String,
+    module: String,
+    name: String,
+    kind: &'static str,
+    path: String,
+    line: usize,
+    event_handler: bool,
+    entry_point: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EdgeJson {
+    from: String,
+    to: String,
+    line: usize,
+    late_bound: bool,
+}
+
+fn graph_json(sources: &Sources, graph: &CallGraph) -> GraphJson {
+    let nodes = graph
+        .nodes
+        .iter()
+        .map(|node| NodeJson {
+            id: node.id(),
+            module: node.file.name.clone(),
+            name: node.procedure.name.clone(),
+            kind: procedure_kind(node.procedure.kind),
+            path: node.file.path.display().to_string(),
+            line: node.procedure.line,
+            event_handler: node.is_event_handler,
+            entry_point: node.is_entry_point,
+        })
+        .collect();
+
+    let edges = graph
+        .edges
+        .iter()
+        .map(|edge| EdgeJson {
+            from: graph.nodes[edge.from].id(),
+            to: graph.nodes[edge.to].id(),
+            line: edge.line,
+            late_bound: edge.is_late_bound,
+        })
+        .collect();
+
+    GraphJson {
+        project: sources.display_project(),
+        nodes,
+        edges,
+    }
+}
+
+fn procedure_kind(kind: ProcedureKind) -> &'static str {
+    match kind {
+        ProcedureKind::Sub => "sub",
+        ProcedureKind::Function => "function",
+        ProcedureKind::PropertyGet | ProcedureKind::PropertyLet | ProcedureKind::PropertySet => {
+            "property"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::testing::sources;
+
+    /// Each edge as `from -> to:line`, marking late bound calls.
+    fn edges(graph: &CallGraph) -> Vec<String> {
+        graph
+            .edges
+            .iter()
+            .map(|edge| {
+                format!(
+                    "{} -> {}:{}{}",
+                    graph.nodes[edge.from].id(),
+                    graph.nodes[edge.to].id(),
+                    edge.line,
+                    if edge.is_late_bound {
+                        " late bound"
+                    } else {
+                        ""
+                    }
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn calls_are_resolved_across_files() {
+        let files = [
+            (
+                SourceFileKind::Module,
+                "Module1.bas",
+                concat!(
+                    "Attribute VB_Name = \"Module1\"\r\n",
+                    "Public Sub Main()\r\n",
+                    "End Sub\r\n",
+                    "Public Function Twice(ByVal Value As Long) As Long\r\n",
+                    "  Twice = Value * 2\r\n",
+                    "  Log \"Twice\"\r\n",
+                    "End Function\r\n",
+                    "Private Sub Log(Text As String)\r\n",
+                    "End Sub\r\n",
+                ),
+            ),
+            (
+                SourceFileKind::Class,
+                "Widget.cls",
+                concat!(
+                    "Attribute VB_Name = \"Widget\"\r\n",
+                    "Private Sub Class_Initialize()\r\n",
+                    "End Sub\r\n",
+                    "Public Sub Refresh()\r\n",
+                    "End Sub\r\n",
+                    "Public Sub Clear()\r\n",
+                    "End Sub\r\n",
+                ),
+            ),
+            (
+                SourceFileKind::Form,
+                "frmMain.frm",
+                concat!(
+                    "VERSION 5.00\r\n",
+                    "Begin VB.Form frmMain \r\n",
+                    "   Begin VB.CommandButton cmdOK \r\n",
+                    "   End\r\n",
+                    "End\r\n",
+                    "Attribute VB_Name = \"frmMain\"\r\n",
+                    "Private m_Widget As Widget\r\n",
+                    "Private Sub Form_Load()\r\n",
+                    "  Set m_Widget = New Widget\r\n",
+                    "End Sub\r\n",
+                    "Private Sub cmdOK_Click()\r\n",
+                    "  Dim Other As Object\r\n",
+                    "  m_Widget.Refresh\r\n",
+                    "  Other.Clear\r\n",
+                    "  cmdOK.Refresh\r\n",
+                    "  Caption = Module1.Twice(2) & Twice(3)\r\n",
+                    "End Sub\r\n",
+                ),
+            ),
+        ];
+        let sources = Sources {
+            startup: Some("frmMain".to_owned()),
+            ..sources(&files)
+        };
+
+        let graph = CallGraph::build(&sources);
+
+        let roots: Vec<String> = graph
+            .nodes
+            .iter()
+            .filter(|node| node.is_event_handler || node.is_entry_point)
+            .map(|node| {
+                format!(
+                    "{} {} {}",
+                    node.id(),
+                    node.is_event_handler,
+                    node.is_entry_point
+                )
+            })
+            .collect();
+        assert_eq!(
+            roots,
+            [
+                "Widget.Class_Initialize true false",
+                "frmMain.Form_Load true true",
+                "frmMain.cmdOK_Click true false",
+            ]
+        );
+
+        assert_eq!(
+            edges(&graph),
+            [
+                "Module1.Twice -> Module1.Log:6",
+                "frmMain.cmdOK_Click -> Module1.Twice:16",
+                "frmMain.cmdOK_Click -> Widget.Refresh:13",
+                "frmMain.cmdOK_Click -> Widget.Clear:14 late bound",
+            ]
+        );
+    }
+
+    #[test]
+    fn with_blocks_and_untyped_objects_are_late_bound() {
+        let graph_sources = sources(&[
+            (
+                SourceFileKind::Module,
+                "Module1.bas",
+                concat!(
+                    "Attribute VB_Name = \"Module1\"\r\n",
+                    "Public Sub Main()\r\n",
+                    "  Dim Item As Widget\r\n",
+                    "  Dim Thing\r\n",
+                    "  With Item\r\n",
+                    "    .Refresh\r\n",
+                    "  End With\r\n",
+                    "  Thing.Clear\r\n",
+                    "  CallByName Item, \"Hidden\", VbMethod\r\n",
+                    "  Item.Refresh\r\n",
+                    "End Sub\r\n",
+                ),
+            ),
+            (
+                SourceFileKind::Class,
+                "Widget.cls",
+                concat!(
+                    "Attribute VB_Name = \"Widget\"\r\n",
+                    "Public Sub Refresh()\r\n",
+                    "End Sub\r\n",
+                    "Public Sub Clear()\r\n",
+                    "End Sub\r\n",
+                    "Private Sub Hidden()\r\n",
+                    "End Sub\r\n",
+                ),
+            ),
+            (
+                SourceFileKind::Form,
+                "frmList.frm",
+                concat!(
+                    "VERSION 5.00\r\n",
+                    "Begin VB.Form frmList \r\n",
+                    "End\r\n",
+                    "Attribute VB_Name = \"frmList\"\r\n",
+                    "Public Sub Clear()\r\n",
+                    "End Sub\r\n",
+                ),
+            ),
+        ]);
+
+        let graph = CallGraph::build(&graph_sources);
+
+        // Members called by name in a string can't be found at all.
+        assert_eq!(
+            edges(&graph),
+            [
+                "Module1.Main -> Widget.Refresh:6 late bound",
+                "Module1.Main -> Widget.Clear:8 late bound",
+                "Module1.Main -> frmList.Clear:8 late bound",
+            ]
+        );
+    }
+
+    #[test]
+    fn property_procedures_are_one_node() {
+        let graph_sources = sources(&[(
+            SourceFileKind::Class,
+            "Widget.cls",
+            concat!(
+                "Attribute VB_Name = \"Widget\"\r\n",
+                "Private m_Owner As Object\r\n",
+                "Public Property Get Owner() As Object\r\n",
+                "  Set Owner = m_Owner\r\n",
+                "End Property\r\n",
+                "Public Property Let Owner(ByVal Value As Object)\r\n",
+                "  Validate\r\n",
+                "End Property\r\n",
+                "Public Property Set Owner(ByVal Value As Object)\r\n",
+                "  Set m_Owner = Value\r\n",
+                "  Notify\r\n",
+                "End Property\r\n",
+                "Private Sub Validate()\r\n",
+                "  Notify\r\n",
+                "End Sub\r\n",
+                "Private Sub Notify()\r\n",
+                "End Sub\r\n",
+            ),
+        )]);
+
+        let graph = CallGraph::build(&graph_sources);
+
+        let nodes: Vec<(String, ProcedureKind)> = graph
+            .nodes
+            .iter()
+            .map(|node| (node.id(), node.procedure.kind))
+            .collect();
+        assert_eq!(
+            nodes,
+            [
+                ("Widget.Owner".to_owned(), ProcedureKind::PropertyGet),
+                ("Widget.Validate".to_owned(), ProcedureKind::Sub),
+                ("Widget.Notify".to_owned(), ProcedureKind::Sub),
+            ]
+        );
+
+        assert_eq!(
+            edges(&graph),
+            [
+                "Widget.Owner -> Widget.Validate:7",
+                "Widget.Owner -> Widget.Notify:11",
+                "Widget.Validate -> Widget.Notify:14",
+            ]
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::walk::absolute_path;
+
+/// The set of files git reports as changed relative to a revision.
+///
+/// Paths are stored normalized and lowercased so they can be compared with
+/// the (often inconsistently cased) member paths listed in `.vbp` files.
+#[derive(Debug, Clone, Default)]
+pub struct ChangedFiles {
+    pub revision: String,
+    paths: HashSet<PathBuf>,
+}
+
+impl ChangedFiles {
+    /// Asks the git repository containing each of `check_paths` for every
+    /// file that differs from `revision`, including uncommitted and untracked
+    /// files. Only the local repositories are consulted.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if git can't be run, any of `check_paths` is not
+    /// in a git repository, or `revision` can't be resolved in one of them.
+    pub fn since(check_paths: &[PathBuf], revision: &str) -> Result<Self> {
+        let mut top_levels = Vec::new();
+        for check_path in check_paths {
+            let top_level = repository_root(check_path).with_context(|| {
+                format!(
+                    "Unable to find changes since '{}' for '{}'",
+                    revision,
+                    check_path.display()
+                )
+            })?;
+
+            if !top_levels.contains(&top_level) {
+                top_levels.push(top_level);
+            }
+        }
+
+        let mut paths = HashSet::new();
+        for top_level in &top_levels {
+            let changed = run_git(top_level, &["diff", "--name-only", "-z", revision, "--"])?;
+            let untracked = run_git(
+                top_level,
+                &[
+                    "ls-files",
+                    "--others",
+                    "--exclude-standard",
+                    "-z",
+                    "--full-name",
+                ],
+            )?;
+
+            paths.extend(
+                changed
+                    .split('\0')
+                    .chain(untracked.split('\0'))
+                    .filter(|path| !path.is_empty())
+                    .map(|path| comparable_path(&top_level.join(path))),
+            );
+        }
+
+        Ok(ChangedFiles {
+            revision: revision.to_owned(),
+            paths,
+        })
+    }
+
+    /// Checks if `path` is one of the changed files.
+    pub fn contains(&self, path: &Path) -> bool {
+        self.paths.contains(&comparable_path(path))
+    }
+}
+
+/// Asks git for the root of the repository containing `path`, which need
+/// not exist yet. A glob pattern is looked up from its deepest existing
+/// directory.
+///
+/// # Errors
+///
+/// An error is returned if git can't be run or `path` is not in a git
+/// repository.
+pub fn repository_root(path: &Path) -> Result<PathBuf> {
+    let path = absolute_path(path);
+    let directory = path
+        .ancestors()
+        .find(|directory| directory.is_dir())
+        .unwrap_or(&path);
+
+    let top_level = run_git(directory, &["rev-parse", "--show-toplevel"])?;
+    Ok(PathBuf::from(top_level.trim_end_matches(['\r', '\n'])))
+}
+
+fn run_git(directory: &Path, arguments: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(arguments)
+        .output()
+        .context("Unable to run git")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git {} failed: {}",
+            arguments.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Resolves `path` to an absolute, lowercased path with any `.` and `..`
+/// components and symlinks in its existing ancestors resolved.
+fn comparable_path(path: &Path) -> PathBuf {
+    let normalized = absolute_path(path);
+
+    // Deleted files can't be canonicalized, but their directory usually can.
+    let resolved = match (normalized.parent(), normalized.file_name()) {
+        (Some(parent), Some(file_name)) => match parent.canonicalize() {
+            Ok(parent) => parent.join(file_name),
+            Err(_) => normalized,
+        },
+        _ => normalized,
+    };
+
+    PathBuf::from(resolved.to_string_lossy().to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a repository in a scratch directory with `files` committed.
+    fn repository(test_name: &str, files: &[&str]) -> PathBuf {
+        let directory = std::env::temp_dir()
+            .join(format!("aspen-changes-{}", std::process::id()))
+            .join(test_name);
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        let directory = directory.canonicalize().unwrap();
+
+        for file in files {
+            std::fs::write(directory.join(file), "Type=Exe\r\n").unwrap();
+        }
+
+        run_git(&directory, &["init", "--quiet"]).unwrap();
+        run_git(&directory, &["add", "--all"]).unwrap();
+        run_git(
+            &directory,
+            &[
+                "-c",
+                "user.name=aspen",
+                "-c",
+                "user.email=aspen@example.com",
+                "commit",
+                "--quiet",
+                "--message",
+                "Initial",
+            ],
+        )
+        .unwrap();
+
+        directory
+    }
+
+    #[test]
+    fn changes_come_from_the_repository_of_each_check_path() {
+        let first = repository("first", &["A.vbp", "B.vbp"]);
+        let second = repository("second", &["C.vbp"]);
+
+        std::fs::write(first.join("A.vbp"), "Type=OleDll\r\n").unwrap();
+        std::fs::write(first.join("New.bas"), "").unwrap();
+        std::fs::write(second.join("C.vbp"), "Type=OleDll\r\n").unwrap();
+
+        let changed_files = ChangedFiles::since(&[first.join("B.vbp")], "HEAD").unwrap();
+        assert!(changed_files.contains(&first.join("A.vbp")));
+        assert!(changed_files.contains(&first.join("new.BAS")));
+        assert!(!changed_files.contains(&first.join("B.vbp")));
+        assert!(!changed_files.contains(&second.join("C.vbp")));
+
+        let changed_files =
+            ChangedFiles::since(&[first.join("*.vbp"), second.clone()], "HEAD").unwrap();
+        assert!(changed_files.contains(&first.join("A.vbp")));
+        assert!(changed_files.contains(&second.join("C.vbp")));
+    }
+
+    #[test]
+    fn paths_outside_a_repository_are_an_error() {
+        let first = repository("outside", &["A.vbp"]);
+        let outside = first.parent().unwrap().join("not-a-repository");
+        std::fs::create_dir_all(&outside).unwrap();
+
+        assert_eq!(repository_root(&first.join("A.vbp")).unwrap(), first);
+        assert!(repository_root(&outside).is_err());
+        assert!(ChangedFiles::since(&[first, outside], "HEAD").is_err());
+    }
+}
@@ -1,252 +1,736 @@
-use anyhow::Error;
+use anyhow::anyhow;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use rayon::prelude::*;
+use serde::Serialize;
 use vb6parse::parsers::VB6ProjectReference;
 
-use walkdir::WalkDir;
+use crate::baseline::Baseline;
+use crate::changes::repository_root;
+use crate::changes::ChangedFiles;
+use crate::control_arrays::lint_control_arrays;
+use crate::dead_code::find_dead_code;
+use crate::diagnostic::{Diagnostic, DiagnosticKind, OutputFormat, Region};
+use crate::error_handling::lint_error_handling;
+use crate::event_handlers::lint_event_handlers;
+use crate::form_layout::lint_form_layout;
+use crate::glob::{expand_glob, is_glob_pattern};
+use crate::implicit_variants::lint_implicit_variants;
+use crate::junit::junit_report;
+use crate::member_access::find_unknown_members;
+use crate::project_settings::validate_project;
+use crate::reachability::find_unreachable_code;
+use crate::sarif::sarif_log;
+use crate::sources::Sources;
+use crate::suppression::{strip_project_comments, Suppressions};
+use crate::typelib::TypeLibraries;
+use crate::undeclared::find_undeclared_identifiers;
+use crate::walk::{absolute_path, WalkFilter};
 
 use vb6parse::parsers::{VB6ClassFile, VB6FormFile, VB6ModuleFile, VB6Project};
+use vb6parse::VB6Token;
 
+#[derive(Clone)]
 pub struct CheckSettings {
-    pub project_path: PathBuf,
     pub check_forms: bool,
     pub check_modules: bool,
     pub check_classes: bool,
     pub check_references: bool,
+    pub walk_filter: WalkFilter,
+    /// When set, only projects and files touched by these changes are checked.
+    pub changed_files: Option<ChangedFiles>,
+    pub output_format: OutputFormat,
+    /// Findings recorded in this baseline file are not reported.
+    pub baseline: Option<PathBuf>,
+    /// Every finding is recorded in this baseline file.
+    pub write_baseline: Option<PathBuf>,
+    /// Descriptions of the COM libraries projects reference.
+    pub type_libraries: TypeLibraries,
+    /// The root of the git repository the current check path is in, found
+    /// once for every project beneath it rather than once per project.
+    pub repository_root: Option<PathBuf>,
 }
 
 pub struct CheckResults {
     pub project_path: String,
-    pub parsing_errors: Vec<Error>,
-    pub non_english_files: Vec<String>,
-    pub missing_files: Vec<String>,
+    /// Every file that was checked, starting with the project itself.
+    pub checked_files: Vec<PathBuf>,
+    pub parsing_errors: Vec<Diagnostic>,
+    pub non_english_files: Vec<Diagnostic>,
+    pub missing_files: Vec<Diagnostic>,
+    pub lint_findings: Vec<Diagnostic>,
+    /// Things worth knowing that don't fail the check, such as references
+    /// to libraries aspen can't look into.
+    pub notes: Vec<Diagnostic>,
 }
 
-pub fn check_subcommand(check_settings: CheckSettings) -> Result<()> {
-    if !check_settings.project_path.exists() {
+impl CheckResults {
+    fn new(project_path: &Path) -> Self {
+        CheckResults {
+            project_path: project_path.display().to_string(),
+            checked_files: Vec::new(),
+            parsing_errors: Vec::new(),
+            non_english_files: Vec::new(),
+            missing_files: Vec::new(),
+            lint_findings: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    fn from_diagnostic(project_path: &Path, diagnostic: Diagnostic) -> Self {
+        let mut check_results = CheckResults::new(project_path);
+        check_results.push(diagnostic);
+        check_results
+    }
+
+    /// Records `diagnostic` alongside the others of its kind.
+    fn push(&mut self, diagnostic: Diagnostic) {
+        match diagnostic.kind {
+            DiagnosticKind::MissingFile | DiagnosticKind::MissingSubProjectReference => {
+                self.missing_files.push(diagnostic)
+            }
+            DiagnosticKind::NonEnglishEncoding => self.non_english_files.push(diagnostic),
+            DiagnosticKind::UnreadableFile
+            | DiagnosticKind::ParseError
+            | DiagnosticKind::UnsupportedFile => self.parsing_errors.push(diagnostic),
+            DiagnosticKind::UndescribedReference => self.notes.push(diagnostic),
+            DiagnosticKind::UnusedSuppression
+            | DiagnosticKind::InvalidStartup
+            | DiagnosticKind::InvalidOutputPath
+            | DiagnosticKind::ProjectTypeMismatch
+            | DiagnosticKind::InvalidVersion
+            | DiagnosticKind::MissingCompatibleBinary
+            | DiagnosticKind::BrokenCompatibility
+            | DiagnosticKind::ControlOutsideContainer
+            | DiagnosticKind::OverlappingControls
+            | DiagnosticKind::InvalidTabOrder
+            | DiagnosticKind::DuplicateAccessKey
+            | DiagnosticKind::ZeroSizeControl
+            | DiagnosticKind::OrphanedEventHandler
+            | DiagnosticKind::EventHandlerMismatch
+            | DiagnosticKind::InvalidControlArray
+            | DiagnosticKind::ControlArrayGap
+            | DiagnosticKind::UnusedProcedure
+            | DiagnosticKind::UnusedVariable
+            | DiagnosticKind::UnusedConstant
+            | DiagnosticKind::UnusedDeclare
+            | DiagnosticKind::UnreachableProcedure
+            | DiagnosticKind::ImplicitVariant
+            | DiagnosticKind::UndeclaredIdentifier
+            | DiagnosticKind::UnknownMember
+            | DiagnosticKind::MissingErrorLabel
+            | DiagnosticKind::ErrorHandlerFallThrough
+            | DiagnosticKind::SwallowedErrors
+            | DiagnosticKind::UnhandledEntryPoint => self.lint_findings.push(diagnostic),
+        }
+    }
+
+    /// Drops every diagnostic `suppressions` applies to.
+    fn remove_suppressed(&mut self, suppressions: &mut Suppressions) {
+        self.retain(|diagnostic| !suppressions.suppresses(diagnostic));
+    }
+
+    /// Drops every diagnostic recorded in `baseline`, returning how many
+    /// were dropped.
+    fn remove_baselined(&mut self, baseline: &mut Baseline) -> usize {
+        let project_path = PathBuf::from(&self.project_path);
+        let mut baselined_count = 0;
+
+        self.retain(|diagnostic| {
+            let is_baselined = baseline.contains(&project_path, diagnostic);
+            baselined_count += usize::from(is_baselined);
+            !is_baselined
+        });
+
+        baselined_count
+    }
+
+    fn retain(&mut self, mut keep: impl FnMut(&Diagnostic) -> bool) {
+        for diagnostics in [
+            &mut self.missing_files,
+            &mut self.parsing_errors,
+            &mut self.non_english_files,
+            &mut self.lint_findings,
+            &mut self.notes,
+        ] {
+            diagnostics.retain(&mut keep);
+        }
+    }
+
+    /// Iterates over every diagnostic that fails the check, regardless of
+    /// kind.
+    pub fn diagnostics(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.missing_files
+            .iter()
+            .chain(self.parsing_errors.iter())
+            .chain(self.non_english_files.iter())
+            .chain(self.lint_findings.iter())
+    }
+
+    fn is_clean(&self) -> bool {
+        self.parsing_errors.is_empty()
+            && self.non_english_files.is_empty()
+            && self.missing_files.is_empty()
+            && self.lint_findings.is_empty()
+    }
+}
+
+/// The kinds of VB6 source file that can be checked on their own, without
+/// the project that lists them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceFileKind {
+    Class,
+    Module,
+    Form,
+}
+
+impl SourceFileKind {
+    /// Determines the kind of source file from the path's extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_string_lossy().to_ascii_lowercase();
+
+        match extension.as_str() {
+            "cls" => Some(SourceFileKind::Class),
+            "bas" => Some(SourceFileKind::Module),
+            "frm" => Some(SourceFileKind::Form),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SourceFileKind::Class => "Class",
+            SourceFileKind::Module => "Module",
+            SourceFileKind::Form => "Form",
+        }
+    }
+}
+
+/// Checks each of `check_paths`, which may be `.vbp` projects, directories to
+/// search for projects, individual `.bas`, `.cls` and `.frm` files, or globs
+/// matching any of these.
+///
+/// Returns `true` when no problems were found.
+pub fn check_subcommand(check_settings: CheckSettings, check_paths: Vec<PathBuf>) -> Result<bool> {
+    let mut check_summary = Vec::new();
+    let is_text_output = check_settings.output_format == OutputFormat::Text;
+
+    if let (true, Some(changed_files)) = (is_text_output, &check_settings.changed_files) {
         println!(
-            "No project file found at '{:?}'",
-            check_settings.project_path
+            "Only checking projects and files changed since '{}'.",
+            changed_files.revision
         );
-        return Ok(());
     }
 
-    let mut check_summary = Vec::new();
+    for check_path in check_paths {
+        let check_settings = CheckSettings {
+            repository_root: repository_root(&check_path).ok(),
+            ..check_settings.clone()
+        };
 
-    if check_settings.project_path.is_dir() {
-        let search_path = check_settings.project_path.to_str().unwrap();
-        let walker = WalkDir::new(search_path).into_iter();
+        if check_path.is_dir() {
+            if is_text_output {
+                println!(
+                    "Searching '{}' for .vbp project files.",
+                    check_path.display()
+                );
+            }
 
-        println!("Searching '{}' for .vbp project files.", search_path);
+            check_summary.extend(check_directory(&check_settings, &check_path));
+            continue;
+        }
 
-        let found_projects: Vec<_> = walker
-            .into_iter()
-            .filter(|entry| is_project_file(entry))
-            .collect();
+        if check_path.exists() {
+            if is_affected_by_changes(&check_settings, &check_path) {
+                check_summary.push(check_path_by_kind(&check_settings, &check_path));
+            }
+            continue;
+        }
 
-        found_projects
+        let pattern = check_path.to_string_lossy();
+        if !is_glob_pattern(&pattern) {
+            check_summary.push(CheckResults::from_diagnostic(
+                &check_path,
+                Diagnostic::new(
+                    DiagnosticKind::MissingFile,
+                    &check_path,
+                    format!(
+                        "No project or source file found at '{}'",
+                        check_path.display()
+                    ),
+                ),
+            ));
+            continue;
+        }
+
+        let matched_paths = expand_glob(&pattern)?;
+        if matched_paths.is_empty() {
+            check_summary.push(CheckResults::from_diagnostic(
+                &check_path,
+                Diagnostic::new(
+                    DiagnosticKind::MissingFile,
+                    &check_path,
+                    format!("No files match '{}'", pattern),
+                ),
+            ));
+            continue;
+        }
+
+        let matched_results: Vec<_> = checkable_glob_matches(matched_paths)
             .par_iter()
-            .map(|project_path| {
-                if project_path.is_err() {
-                    let check_result = CheckResults {
-                        project_path: project_path
-                            .as_ref()
-                            .unwrap()
-                            .path()
-                            .to_str()
-                            .unwrap()
-                            .to_string(),
-                        parsing_errors: Vec::new(),
-                        non_english_files: Vec::new(),
-                        missing_files: vec![format!(
-                            "Failed to load {}",
-                            project_path.as_ref().err().unwrap()
-                        )],
-                    };
-
-                    return check_result;
+            .filter(|path| is_affected_by_changes(&check_settings, path))
+            .map(|path| check_path_by_kind(&check_settings, path))
+            .collect();
+        check_summary.extend(matched_results);
+    }
+
+    remove_dead_code_used_elsewhere(&mut check_summary);
+
+    let baseline = match (&check_settings.write_baseline, &check_settings.baseline) {
+        (Some(baseline_path), _) => {
+            let mut baseline = Baseline::new(baseline_path);
+            for check_results in &check_summary {
+                let project_path = Path::new(&check_results.project_path);
+                for diagnostic in check_results.diagnostics() {
+                    baseline.insert(project_path, diagnostic);
                 }
+            }
 
-                let check_settings = CheckSettings {
-                    project_path: project_path.as_ref().unwrap().path().to_path_buf(),
-                    check_forms: check_settings.check_forms,
-                    check_modules: check_settings.check_modules,
-                    check_classes: check_settings.check_classes,
-                    check_references: check_settings.check_references,
-                };
-
-                let check_result = match check_project(&check_settings) {
-                    Ok(result) => result,
-                    Err(e) => {
-                        let check_result = CheckResults {
-                            project_path: check_settings.project_path.to_str().unwrap().to_string(),
-                            parsing_errors: vec![e],
-                            non_english_files: Vec::new(),
-                            missing_files: Vec::new(),
-                        };
-
-                        return check_result;
-                    }
-                };
-                return check_result;
-            })
-            .collect_into_vec(&mut check_summary);
-    } else {
-        let check_result = match check_project(&check_settings) {
-            Ok(result) => result,
+            baseline.save(baseline_path)?;
+
+            if is_text_output {
+                println!(
+                    "Wrote {} findings to the baseline '{}'.",
+                    baseline.len(),
+                    baseline_path.display()
+                );
+            }
+
+            Some(baseline)
+        }
+        (None, Some(baseline_path)) => Some(Baseline::load(baseline_path)?),
+        (None, None) => None,
+    };
+
+    if let Some(mut baseline) = baseline {
+        let baselined_count: usize = check_summary
+            .iter_mut()
+            .map(|check_results| check_results.remove_baselined(&mut baseline))
+            .sum();
+
+        if is_text_output && baselined_count > 0 {
+            println!(
+                "{} findings are in the baseline and were not reported.",
+                baselined_count
+            );
+        }
+    }
+
+    let is_clean = check_summary.iter().all(CheckResults::is_clean);
+
+    match check_settings.output_format {
+        OutputFormat::Text => {
+            for check_result in &check_summary {
+                report_check(check_result);
+            }
+
+            report_check_summary(check_summary);
+        }
+        OutputFormat::Junit => {
+            println!("{}", junit_report(&check_summary));
+        }
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&findings_json(&check_summary))?
+            );
+        }
+        OutputFormat::Sarif => {
+            let current_dir = std::env::current_dir()?;
+            let repository_root = repository_root(&current_dir).unwrap_or(current_dir);
+
+            let diagnostics = check_summary
+                .iter()
+                .flat_map(|check_results| check_results.diagnostics().chain(&check_results.notes));
+
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&sarif_log(diagnostics, &repository_root))?
+            );
+        }
+    }
+
+    Ok(is_clean)
+}
+
+/// Keeps dead code findings in a file shared by several checked projects
+/// only when every one of them finds the code dead, as a procedure that one
+/// project never calls may be called by another.
+fn remove_dead_code_used_elsewhere(check_summary: &mut [CheckResults]) {
+    let canonical = |path: &Path| path.canonicalize().unwrap_or_else(|_| absolute_path(path));
+
+    // How many projects checked each file, and found dead code at each
+    // location in it.
+    let mut member_counts: HashMap<PathBuf, usize> = HashMap::new();
+    let mut dead_code_counts: HashMap<(PathBuf, Option<Region>), usize> = HashMap::new();
+
+    for check_results in check_summary
+        .iter()
+        .filter(|check_results| is_project_path(Path::new(&check_results.project_path)))
+    {
+        let members: HashSet<PathBuf> = check_results
+            .checked_files
+            .iter()
+            .skip(1)
+            .map(|path| canonical(path))
+            .collect();
+        for member in members {
+            *member_counts.entry(member).or_default() += 1;
+        }
+
+        let dead_code: HashSet<(PathBuf, Option<Region>)> = check_results
+            .lint_findings
+            .iter()
+            .filter(|diagnostic| is_dead_code(diagnostic.kind))
+            .map(|diagnostic| (canonical(&diagnostic.path), diagnostic.region))
+            .collect();
+        for location in dead_code {
+            *dead_code_counts.entry(location).or_default() += 1;
+        }
+    }
+
+    for check_results in check_summary.iter_mut() {
+        check_results.retain(|diagnostic| {
+            if !is_dead_code(diagnostic.kind) {
+                return true;
+            }
+
+            let path = canonical(&diagnostic.path);
+            let member_count = member_counts.get(&path).copied().unwrap_or(1);
+            let dead_code_count = dead_code_counts
+                .get(&(path, diagnostic.region))
+                .copied()
+                .unwrap_or(1);
+
+            dead_code_count >= member_count
+        });
+    }
+}
+
+/// Whether `kind` reports code that nothing in the project uses.
+fn is_dead_code(kind: DiagnosticKind) -> bool {
+    matches!(
+        kind,
+        DiagnosticKind::UnusedProcedure
+            | DiagnosticKind::UnusedVariable
+            | DiagnosticKind::UnusedConstant
+            | DiagnosticKind::UnusedDeclare
+            | DiagnosticKind::UnreachableProcedure
+    )
+}
+
+/// The findings of a checked project, for `--output json`.
+#[derive(Serialize)]
+struct ProjectFindings<'a> {
+    project: &'a str,
+    findings: Vec<Finding>,
+}
+
+#[derive(Serialize)]
+struct Finding {
+    rule: &'static str,
+    message: String,
+    path: String,
+    line: Option<usize>,
+    column: Option<usize>,
+}
+
+/// The findings of each checked project, with their rule and location.
+fn findings_json(check_summary: &[CheckResults]) -> Vec<ProjectFindings<'_>> {
+    check_summary
+        .iter()
+        .map(|check_results| ProjectFindings {
+            project: &check_results.project_path,
+            findings: check_results
+                .diagnostics()
+                .map(|diagnostic| Finding {
+                    rule: diagnostic.kind.id(),
+                    message: diagnostic.to_string(),
+                    path: diagnostic.path.display().to_string(),
+                    line: diagnostic.region.map(|region| region.line),
+                    column: diagnostic.region.map(|region| region.column),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Checks every `.vbp` project found beneath `search_path`.
+///
+/// Entries the walk could not read are reported as results of their own so a
+/// single unreadable directory or project never hides the rest of the tree.
+fn check_directory(check_settings: &CheckSettings, search_path: &Path) -> Vec<CheckResults> {
+    let walk_filter = &check_settings.walk_filter;
+
+    let found_projects: Vec<_> = walk_filter
+        .walk(search_path)
+        .filter(is_project_file)
+        .filter(|entry| match entry {
+            Ok(entry) => walk_filter.is_included(search_path, entry.path()),
+            Err(_) => true,
+        })
+        .filter(|entry| match entry {
+            Ok(entry) => is_affected_by_changes(check_settings, entry.path()),
+            Err(_) => true,
+        })
+        .collect();
+
+    found_projects
+        .par_iter()
+        .map(|entry| match entry {
+            Ok(entry) => check_single_project(check_settings, entry.path()),
             Err(e) => {
-                let check_result = CheckResults {
-                    project_path: check_settings.project_path.to_str().unwrap().to_string(),
-                    parsing_errors: vec![e],
-                    non_english_files: Vec::new(),
-                    missing_files: Vec::new(),
-                };
-
-                check_result
+                let path = e.path().unwrap_or(search_path);
+
+                CheckResults::from_diagnostic(
+                    path,
+                    Diagnostic::new(
+                        DiagnosticKind::UnreadableFile,
+                        path,
+                        format!("Failed to load '{}': {}", path.display(), e),
+                    ),
+                )
             }
-        };
-        check_summary.push(check_result);
+        })
+        .collect()
+}
+
+/// Checks a single existing file as either a project or a source file,
+/// depending on its extension.
+fn check_path_by_kind(check_settings: &CheckSettings, path: &Path) -> CheckResults {
+    match SourceFileKind::from_path(path) {
+        Some(kind) => {
+            let mut check_results = CheckResults::new(path);
+            check_results.checked_files.push(path.to_path_buf());
+            check_source_file(kind, path, Vec::new(), &mut check_results);
+            check_results
+        }
+        None if is_project_path(path) => check_single_project(check_settings, path),
+        None => CheckResults::from_diagnostic(
+            path,
+            Diagnostic::new(
+                DiagnosticKind::UnsupportedFile,
+                path,
+                format!(
+                    "'{}' is not a .vbp project, or a .bas, .cls or .frm file",
+                    path.display()
+                ),
+            ),
+        ),
     }
+}
 
-    for check_result in &check_summary {
-        report_check(check_result);
+/// Narrows the files a glob matched to the ones aspen checks: projects, and
+/// source files that aren't already checked as part of a matched project.
+fn checkable_glob_matches(matched_paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    let project_members: HashSet<PathBuf> = matched_paths
+        .iter()
+        .filter(|path| is_project_path(path))
+        .filter_map(|project_path| {
+            let project_contents = strip_project_comments(&std::fs::read(project_path).ok()?);
+            let project =
+                VB6Project::parse(display_file_name(project_path), &project_contents).ok()?;
+
+            Some(project_member_paths(
+                &project,
+                project_directory(project_path),
+            ))
+        })
+        .flatten()
+        .map(|member_path| absolute_path(&member_path))
+        .collect();
+
+    matched_paths
+        .into_iter()
+        .filter(|path| match SourceFileKind::from_path(path) {
+            Some(_) => !project_members.contains(&absolute_path(path)),
+            None => is_project_path(path),
+        })
+        .collect()
+}
+
+fn is_project_path(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("vbp"))
+}
+
+/// Checks if `path` needs checking given the settings' changed files.
+///
+/// A source file is affected when it changed itself. A project is affected
+/// when the `.vbp` or any file it references changed. Projects that can't be
+/// read or parsed are always affected so their errors aren't hidden.
+fn is_affected_by_changes(check_settings: &CheckSettings, path: &Path) -> bool {
+    let Some(changed_files) = &check_settings.changed_files else {
+        return true;
+    };
+
+    if changed_files.contains(path) {
+        return true;
     }
 
-    report_check_summary(check_summary);
+    if SourceFileKind::from_path(path).is_some() {
+        return false;
+    }
+
+    let Ok(project_contents) = std::fs::read(path) else {
+        return true;
+    };
+    let project_contents = strip_project_comments(&project_contents);
+
+    let Ok(project) = VB6Project::parse(display_file_name(path), project_contents.as_slice())
+    else {
+        return true;
+    };
 
-    Ok(())
+    project_member_paths(&project, project_directory(path))
+        .iter()
+        .any(|member_path| changed_files.contains(member_path))
+}
+
+/// Resolves the paths of every sub-project, class, module, and form that
+/// `project` references.
+fn project_member_paths(project: &VB6Project, project_directory: &Path) -> Vec<PathBuf> {
+    let sub_projects = project
+        .get_subproject_references()
+        .into_iter()
+        .filter_map(|reference| match reference {
+            VB6ProjectReference::SubProject { path } => Some(path.to_string()),
+            _ => None,
+        });
+
+    let classes = project.classes.iter().map(|class| class.path.to_string());
+    let modules = project.modules.iter().map(|module| module.path.to_string());
+    let forms = project.forms.iter().map(|form| form.to_string());
+
+    sub_projects
+        .chain(classes)
+        .chain(modules)
+        .chain(forms)
+        .map(|member_path| join_parent_project_path(project_directory, &member_path))
+        .collect()
+}
+
+fn check_single_project(check_settings: &CheckSettings, project_path: &Path) -> CheckResults {
+    match check_project(check_settings, project_path) {
+        Ok(result) => result,
+        Err(e) => CheckResults::from_diagnostic(
+            project_path,
+            Diagnostic::from_error(DiagnosticKind::UnreadableFile, project_path, e),
+        ),
+    }
 }
 
 fn report_check(check_results: &CheckResults) {
-    if check_results.parsing_errors.len() == 0
-        && check_results.non_english_files.len() == 0
-        && check_results.missing_files.len() == 0
-    {
+    if !check_results.notes.is_empty() {
+        println!("Notes for '{}':", check_results.project_path);
+        for note in &check_results.notes {
+            println!("  {}", note);
+        }
+    }
+
+    if check_results.is_clean() {
         return;
     }
 
     println!("Errors found in '{}':", check_results.project_path);
-    if check_results.missing_files.len() != 0 {
+    if !check_results.missing_files.is_empty() {
         println!("Missing Files:");
         for missing_file in &check_results.missing_files {
             println!("  {}", missing_file);
         }
     }
-    if check_results.parsing_errors.len() != 0 {
+    if !check_results.parsing_errors.is_empty() {
         println!("Parsing Errors:");
         for error in &check_results.parsing_errors {
             println!("  {}", error);
         }
     }
-    if check_results.non_english_files.len() != 0 {
+    if !check_results.non_english_files.is_empty() {
         println!("Non-English Files:");
         for non_english_file in &check_results.non_english_files {
             println!("  {}", non_english_file);
         }
     }
+    if !check_results.lint_findings.is_empty() {
+        println!("Lint Findings:");
+        for lint_finding in &check_results.lint_findings {
+            match lint_finding.region {
+                Some(region) => println!(
+                    "  {}:{}:{}: {} [{}]",
+                    lint_finding.path.display(),
+                    region.line,
+                    region.column,
+                    lint_finding,
+                    lint_finding.kind.id()
+                ),
+                None => println!(
+                    "  {}: {} [{}]",
+                    lint_finding.path.display(),
+                    lint_finding,
+                    lint_finding.kind.id()
+                ),
+            }
+        }
+    }
+}
+
+/// Describes the non-zero counts, e.g. "2 missing files, 1 error".
+fn describe_counts(
+    missing_file_count: usize,
+    error_count: usize,
+    non_english_file_count: usize,
+    lint_finding_count: usize,
+) -> String {
+    let counts = [
+        (missing_file_count, "missing file", "missing files"),
+        (error_count, "error", "errors"),
+        (
+            non_english_file_count,
+            "unprocessed non-English file",
+            "unprocessed non-English files",
+        ),
+        (lint_finding_count, "lint finding", "lint findings"),
+    ];
+
+    counts
+        .iter()
+        .filter(|(count, _, _)| *count != 0)
+        .map(|(count, singular, plural)| match count {
+            1 => format!("1 {}", singular),
+            count => format!("{} {}", count, plural),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 fn report_single_check_summary(summary: &CheckResults) {
-    // 0, 0, 0
-    if summary.parsing_errors.len() == 0
-        && summary.non_english_files.len() == 0
-        && summary.missing_files.len() == 0
-    {
+    if summary.is_clean() {
         println!("No errors found in {}.", summary.project_path);
         return;
     }
 
-    // 0, 0, 1
-    if summary.parsing_errors.len() == 0
-        && summary.non_english_files.len() == 0
-        && summary.missing_files.len() != 0
-    {
-        println!(
-            "{} missing files in {}.",
-            summary.missing_files.len(),
-            summary.project_path
-        );
-        return;
-    }
-
-    // 0, 1, 0
-    if summary.parsing_errors.len() == 0
-        && summary.non_english_files.len() != 0
-        && summary.missing_files.len() == 0
-    {
-        println!(
-            "{} unprocessed non-English files found in the project.",
-            summary.non_english_files.len()
-        );
-        return;
-    }
-
-    // 0, 1, 1
-    if summary.parsing_errors.len() == 0
-        && summary.non_english_files.len() != 0
-        && summary.missing_files.len() != 0
-    {
-        println!(
-            "{} missing files, {} unprocessed non-English files found in the project.",
-            summary.missing_files.len(),
-            summary.non_english_files.len()
-        );
-        return;
-    }
-
-    // 1, 0, 0
-    if summary.parsing_errors.len() != 0
-        && summary.non_english_files.len() == 0
-        && summary.missing_files.len() == 0
-    {
-        println!(
-            "{} errors found in the project.",
-            summary.parsing_errors.len()
-        );
-        return;
-    }
-
-    // 1, 0, 1
-    if summary.parsing_errors.len() != 0
-        && summary.non_english_files.len() == 0
-        && summary.missing_files.len() != 0
-    {
-        println!(
-            "{} missing files, {} errors found in the project.",
-            summary.missing_files.len(),
-            summary.parsing_errors.len()
-        );
-        return;
-    }
-
-    // 1, 1, 0
-    if summary.parsing_errors.len() != 0
-        && summary.non_english_files.len() != 0
-        && summary.missing_files.len() == 0
-    {
-        println!(
-            "{} errors found in project with {} unprocessed non-English files found in the project.",
-            summary.parsing_errors.len(),
-            summary.non_english_files.len()
-        );
-        return;
-    }
-
-    // 1, 1, 1
-    if summary.parsing_errors.len() != 0
-        && summary.non_english_files.len() != 0
-        && summary.missing_files.len() != 0
-    {
-        println!(
-            "{} missing files, {} errors found in project with {} unprocessed non-English files found in the project.",
+    println!(
+        "{} found in {}.",
+        describe_counts(
             summary.missing_files.len(),
             summary.parsing_errors.len(),
-            summary.non_english_files.len()
-        );
-        return;
-    }
+            summary.non_english_files.len(),
+            summary.lint_findings.len()
+        ),
+        summary.project_path
+    );
 }
 
 fn report_check_summary(summary: Vec<CheckResults>) {
@@ -267,133 +751,260 @@ fn report_check_summary(summary: Vec<CheckResults>) {
         .iter()
         .fold(0, |acc, x| acc + x.non_english_files.len());
 
-    // 0, 0, 0
-    if total_error_count == 0 && total_non_english_file_count == 0 && total_missed_file_count == 0 {
+    let total_lint_finding_count = summary.iter().fold(0, |acc, x| acc + x.lint_findings.len());
+
+    if summary.iter().all(CheckResults::is_clean) {
         println!("No errors found in {} projects.", project_count);
         return;
     }
 
-    // 0, 0, 1
-    if total_error_count == 0 && total_non_english_file_count == 0 && total_missed_file_count != 0 {
-        println!(
-            "{} missing files in {} projects",
-            total_non_english_file_count, project_count
-        );
-        return;
+    println!(
+        "{} found in {} projects.",
+        describe_counts(
+            total_missed_file_count,
+            total_error_count,
+            total_non_english_file_count,
+            total_lint_finding_count
+        ),
+        project_count
+    );
+}
+
+/// Keeps `.vbp` files and any walk errors, the latter so they can be reported.
+fn is_project_file(entry: &walkdir::Result<walkdir::DirEntry>) -> bool {
+    match entry {
+        Ok(entry) => entry.path().extension() == Some("vbp".as_ref()),
+        Err(_) => true,
     }
+}
 
-    // 0, 1, 0
-    if total_error_count == 0 && total_non_english_file_count != 0 && total_missed_file_count == 0 {
-        println!(
-            "{} unprocessed non-English files found in {} projects",
-            total_non_english_file_count, project_count
-        );
-        return;
+/// Returns the directory member paths of a project are relative to.
+///
+/// A bare file name (or a path without any parent, such as the filesystem
+/// root) resolves to the current directory rather than panicking.
+pub fn project_directory(project_path: &Path) -> &Path {
+    project_path.parent().unwrap_or(Path::new(""))
+}
+
+/// Returns the file name of `path` for use in diagnostics, falling back to the
+/// whole path when there is no file name component. Non-UTF-8 bytes are
+/// replaced rather than treated as an error.
+fn display_file_name(path: &Path) -> String {
+    match path.file_name() {
+        Some(file_name) => file_name.to_string_lossy().into_owned(),
+        None => path.display().to_string(),
     }
+}
 
-    // 0, 1, 1
-    if total_error_count == 0 && total_non_english_file_count != 0 && total_missed_file_count != 0 {
-        println!(
-            "{} missing files, {} unprocessed non-English files found in {} projects",
-            total_missed_file_count, total_non_english_file_count, project_count
-        );
-        return;
+fn read_file(kind: &str, path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path).map_err(|e| anyhow!("Unable to read {} '{}': {}", kind, path.display(), e))
+}
+
+pub fn join_parent_project_path(parent_project_path: &Path, file_path: &str) -> PathBuf {
+    let path = PathBuf::from(parent_project_path);
+
+    if cfg!(target_os = "windows") {
+        path.join(file_path)
+    } else {
+        path.join(file_path.replace("\\", "/"))
     }
+}
 
-    // 1, 0, 0
-    if total_error_count != 0 && total_non_english_file_count == 0 && total_missed_file_count == 0 {
-        println!(
-            "{} errors found in {} projects.",
-            total_error_count, project_count
-        );
-        return;
+/// Checks that a class, module, or form can be read and parses, recording
+/// any problems in `check_results`.
+///
+/// `project_findings` are the file's findings from checks of its whole
+/// project, which are reported with the file's own so its `aspen:ignore`
+/// comments apply to them.
+fn check_source_file(
+    kind: SourceFileKind,
+    path: &Path,
+    project_findings: Vec<Diagnostic>,
+    check_results: &mut CheckResults,
+) {
+    let file_name = display_file_name(path);
+    let contents = match read_file(&kind.label().to_lowercase(), path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            check_results.push(Diagnostic::from_error(
+                DiagnosticKind::UnreadableFile,
+                path,
+                e,
+            ));
+
+            return;
+        }
+    };
+
+    let parse_result = match kind {
+        SourceFileKind::Class => VB6ClassFile::parse(file_name.clone(), &mut contents.as_slice())
+            .map(|class| class.tokens),
+        SourceFileKind::Module => {
+            VB6ModuleFile::parse(file_name.clone(), &contents).map(|module| module.tokens)
+        }
+        SourceFileKind::Form => {
+            VB6FormFile::parse(file_name.clone(), contents.as_slice()).map(|form| form.tokens)
+        }
+    };
+
+    let err = match parse_result {
+        Ok(tokens) => {
+            lint_source_file(
+                kind,
+                path,
+                &contents,
+                &tokens,
+                project_findings,
+                check_results,
+            );
+            return;
+        }
+        Err(err) => err,
+    };
+
+    if err.kind == vb6parse::errors::VB6ErrorKind::LikelyNonEnglishCharacterSet {
+        check_results.push(Diagnostic::new(
+            DiagnosticKind::NonEnglishEncoding,
+            path,
+            format!(
+                "{} is likely not in an English character set: {}",
+                kind.label(),
+                file_name
+            ),
+        ));
+    } else {
+        check_results.push(Diagnostic::from_error(
+            DiagnosticKind::ParseError,
+            path,
+            err.into(),
+        ));
     }
+}
 
-    // 1, 0, 1
-    if total_error_count != 0 && total_non_english_file_count == 0 && total_missed_file_count != 0 {
-        println!(
-            "{} missing files, {} errors found in {} projects.",
-            total_missed_file_count, total_error_count, project_count
-        );
-        return;
+/// Runs the lints over a parsed source file, honouring the file's
+/// `aspen:ignore` comments and reporting any that go unused.
+fn lint_source_file(
+    kind: SourceFileKind,
+    path: &Path,
+    contents: &[u8],
+    tokens: &[VB6Token],
+    project_findings: Vec<Diagnostic>,
+    check_results: &mut CheckResults,
+) {
+    let mut lint_results = CheckResults::new(path);
+
+    for diagnostic in project_findings {
+        lint_results.push(diagnostic);
     }
 
-    // 1, 1, 0
-    if total_error_count != 0 && total_non_english_file_count != 0 && total_missed_file_count == 0 {
-        println!(
-            "{} errors, {} unprocessed non-English files found in {} projects.",
-            total_error_count, total_non_english_file_count, project_count
-        );
-        return;
+    for diagnostic in lint_implicit_variants(path, contents)
+        .into_iter()
+        .chain(lint_error_handling(kind, path, contents))
+    {
+        lint_results.push(diagnostic);
     }
 
-    // 1, 1, 1
-    if total_error_count != 0 && total_non_english_file_count != 0 && total_missed_file_count != 0 {
-        println!(
-            "{} missing files, {} errors, {} unprocessed non-English files found in {} projects.",
-            total_missed_file_count, total_error_count, total_non_english_file_count, project_count
-        );
-        return;
+    if kind == SourceFileKind::Form {
+        for diagnostic in lint_form_layout(path, contents)
+            .into_iter()
+            .chain(lint_event_handlers(path, contents))
+            .chain(lint_control_arrays(path, contents))
+        {
+            lint_results.push(diagnostic);
+        }
     }
-}
 
-fn is_project_file(entry: &Result<walkdir::DirEntry, walkdir::Error>) -> bool {
-    if entry.is_err() {
-        return false;
+    let mut suppressions = Suppressions::from_tokens(path, contents, tokens);
+    lint_results.remove_suppressed(&mut suppressions);
+
+    for diagnostic in lint_results.lint_findings {
+        check_results.push(diagnostic);
+    }
+    for diagnostic in suppressions.unused() {
+        check_results.push(diagnostic);
     }
+}
 
-    let entry = entry.as_ref().unwrap();
-    entry.path().extension() == Some("vbp".as_ref())
+/// Finds where `reference` is first mentioned in a project file so a missing
+/// member can be reported against the line that lists it.
+fn find_reference_region(project_contents: &[u8], reference: &str) -> Option<Region> {
+    let reference = reference.as_bytes();
+
+    project_contents
+        .split(|&byte| byte == b'\n')
+        .enumerate()
+        .find_map(|(line_index, line)| {
+            line.windows(reference.len().max(1))
+                .position(|window| window.eq_ignore_ascii_case(reference))
+                .map(|column_index| Region {
+                    line: line_index + 1,
+                    column: column_index + 1,
+                })
+        })
 }
 
-fn join_parent_project_path(parent_project_path: &Path, file_path: &str) -> PathBuf {
-    let path = PathBuf::from(parent_project_path);
+/// Checks a class, module, or form listed in a project, reporting it against
+/// the project if it doesn't exist.
+fn check_member_file(
+    kind: SourceFileKind,
+    member_reference: &str,
+    project_path: &Path,
+    project_contents: &[u8],
+    project_findings: &mut Vec<Diagnostic>,
+    check_results: &mut CheckResults,
+) {
+    let member_path = join_parent_project_path(project_directory(project_path), member_reference);
+    check_results.checked_files.push(member_path.clone());
+
+    if std::fs::metadata(&member_path).is_err() {
+        check_results.push(
+            Diagnostic::new(
+                DiagnosticKind::MissingFile,
+                project_path,
+                format!("{} not found: {}", kind.label(), member_path.display()),
+            )
+            .with_region(find_reference_region(project_contents, member_reference))
+            .with_member_path(&member_path),
+        );
 
-    if cfg!(target_os = "windows") {
-        path.join(file_path)
-    } else {
-        path.join(file_path.replace("\\", "/"))
+        return;
     }
+
+    let (member_findings, other_findings) = std::mem::take(project_findings)
+        .into_iter()
+        .partition(|diagnostic| diagnostic.path == member_path);
+    *project_findings = other_findings;
+
+    check_source_file(kind, &member_path, member_findings, check_results);
 }
 
 // TODO: Eventually we should be returning an object that contains the errors and the project information.
 // This will allow us to display the errors in a more structured way.
 // For now we just print the errors to the console and return the error count.
-fn check_project(check_settings: &CheckSettings) -> Result<CheckResults> {
-    let mut check_results = CheckResults {
-        project_path: check_settings.project_path.to_str().unwrap().to_string(),
-        parsing_errors: Vec::new(),
-        non_english_files: Vec::new(),
-        missing_files: Vec::new(),
-    };
+fn check_project(check_settings: &CheckSettings, project_path: &Path) -> Result<CheckResults> {
+    let mut check_results = CheckResults::new(project_path);
+    check_results.checked_files.push(project_path.to_path_buf());
 
-    let project_contents = std::fs::read(&check_settings.project_path).unwrap();
+    let raw_project_contents = read_file("project", project_path)?;
+    let project_contents = strip_project_comments(&raw_project_contents);
 
-    let file_name = check_settings
-        .project_path
-        .file_name()
-        .unwrap()
-        .to_str()
-        .unwrap();
-
-    let project = VB6Project::parse(file_name, project_contents.as_slice());
+    let file_name = display_file_name(project_path);
 
-    if project.is_err() {
-        check_results.parsing_errors.push(
-            project
-                .expect_err("Project parse error occurred but no error was returned")
-                .into(),
-        );
-
-        return Ok(check_results);
-    }
+    let project = match VB6Project::parse(file_name, project_contents.as_slice()) {
+        Ok(project) => project,
+        Err(e) => {
+            check_results.push(Diagnostic::from_error(
+                DiagnosticKind::ParseError,
+                project_path,
+                e.into(),
+            ));
 
-    let project = project.unwrap();
+            return Ok(check_results);
+        }
+    };
 
     //remove filename from path
-    let project_directory = std::path::Path::new(&check_settings.project_path)
-        .parent()
-        .unwrap();
+    let project_directory = project_directory(project_path);
 
     if check_settings.check_references {
         for reference in project.get_subproject_references() {
@@ -402,10 +1013,20 @@ fn check_project(check_settings: &CheckSettings) -> Result<CheckResults> {
                     let reference_path =
                         join_parent_project_path(project_directory, &path.to_string());
                     if std::fs::metadata(&reference_path).is_err() {
-                        check_results.missing_files.push(format!(
-                            "Sub-Project Reference not found: {}",
-                            reference_path.to_str().unwrap()
-                        ));
+                        check_results.push(
+                            Diagnostic::new(
+                                DiagnosticKind::MissingSubProjectReference,
+                                project_path,
+                                format!(
+                                    "Sub-Project Reference not found: {}",
+                                    reference_path.display()
+                                ),
+                            )
+                            .with_region(find_reference_region(
+                                &project_contents,
+                                &path.to_string(),
+                            )),
+                        );
                     }
                 }
                 // this should be unreachable, but if it is reached, we just skip it.
@@ -414,117 +1035,405 @@ fn check_project(check_settings: &CheckSettings) -> Result<CheckResults> {
         }
     }
 
+    // Files that can't be read are reported by the checks of each file.
+    let (sources, _) = Sources::from_project(project_path, &project);
+    let mut project_findings = find_dead_code(&sources);
+
+    // Procedures that are never called at all are already reported as unused.
+    let unreachable_findings: Vec<Diagnostic> = find_unreachable_code(&sources)
+        .into_iter()
+        .filter(|unreachable| {
+            !project_findings.iter().any(|unused| {
+                unused.kind == DiagnosticKind::UnusedProcedure
+                    && unused.path == unreachable.path
+                    && unused.region == unreachable.region
+            })
+        })
+        .collect();
+    project_findings.extend(unreachable_findings);
+
+    let libraries = check_settings
+        .type_libraries
+        .referenced(&sources.references);
+    for reference in &sources.references {
+        if check_settings
+            .type_libraries
+            .describing(reference)
+            .is_none()
+        {
+            check_results.push(
+                Diagnostic::new(
+                    DiagnosticKind::UndescribedReference,
+                    project_path,
+                    format!(
+                        "No type library description for '{}' {} {}, so the members used from it aren't checked",
+                        reference.name.trim(),
+                        reference.guid,
+                        reference.version
+                    ),
+                )
+                .with_region(find_reference_region(&project_contents, &reference.guid)),
+            );
+        }
+    }
+    project_findings.extend(find_undeclared_identifiers(&sources, &libraries));
+    project_findings.extend(find_unknown_members(&sources, &libraries));
+
     if check_settings.check_classes {
-        for class_reference in project.classes {
-            let class_path =
-                join_parent_project_path(project_directory, &class_reference.path.to_string());
+        for class_reference in &project.classes {
+            check_member_file(
+                SourceFileKind::Class,
+                &class_reference.path.to_string(),
+                project_path,
+                &project_contents,
+                &mut project_findings,
+                &mut check_results,
+            );
+        }
+    }
 
-            if std::fs::metadata(&class_path).is_err() {
-                check_results
-                    .missing_files
-                    .push(format!("Class not found: {}", class_path.to_str().unwrap()));
+    if check_settings.check_modules {
+        for module_reference in &project.modules {
+            check_member_file(
+                SourceFileKind::Module,
+                &module_reference.path.to_string(),
+                project_path,
+                &project_contents,
+                &mut project_findings,
+                &mut check_results,
+            );
+        }
+    }
 
-                continue;
-            }
+    if check_settings.check_forms {
+        for form_reference in &project.forms {
+            check_member_file(
+                SourceFileKind::Form,
+                &form_reference.to_string(),
+                project_path,
+                &project_contents,
+                &mut project_findings,
+                &mut check_results,
+            );
+        }
+    }
 
-            let file_name = class_path.file_name().unwrap().to_str().unwrap();
-            let class_contents = std::fs::read(&class_path).unwrap();
-            let class = VB6ClassFile::parse(file_name.to_owned(), &mut class_contents.as_slice());
+    for diagnostic in validate_project(
+        project_path,
+        &project_contents,
+        &project,
+        check_settings.repository_root.as_deref(),
+    ) {
+        check_results.push(diagnostic);
+    }
 
-            if class.is_err() {
-                let err = class.unwrap_err();
-                if err.kind == vb6parse::errors::VB6ErrorKind::LikelyNonEnglishCharacterSet {
-                    check_results.non_english_files.push(format!(
-                        "Class is likely not in an English character set: {}",
-                        file_name
-                    ));
+    let mut suppressions = Suppressions::from_project(project_path, &raw_project_contents);
+    check_results.remove_suppressed(&mut suppressions);
 
-                    continue;
-                }
-                {
-                    check_results.parsing_errors.push(err.into());
+    // Skipped checks can't use their suppressions, so they'd all look unused.
+    if check_settings.check_classes
+        && check_settings.check_modules
+        && check_settings.check_forms
+        && check_settings.check_references
+    {
+        for diagnostic in suppressions.unused() {
+            check_results.push(diagnostic);
+        }
+    }
 
-                    continue;
-                }
-            }
+    Ok(check_results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an empty scratch directory unique to the calling test.
+    fn scratch_directory(test_name: &str) -> PathBuf {
+        let directory = std::env::temp_dir()
+            .join(format!("aspen-check-{}", std::process::id()))
+            .join(test_name);
 
-            let _class = class.unwrap();
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+
+        directory
+    }
+
+    fn settings() -> CheckSettings {
+        CheckSettings {
+            check_forms: true,
+            check_modules: true,
+            check_classes: true,
+            check_references: true,
+            walk_filter: WalkFilter::default(),
+            changed_files: None,
+            output_format: OutputFormat::Text,
+            baseline: None,
+            write_baseline: None,
+            type_libraries: TypeLibraries::default(),
+            repository_root: None,
         }
     }
 
-    if check_settings.check_modules {
-        for module_reference in project.modules {
-            let module_path =
-                join_parent_project_path(project_directory, &module_reference.path.to_string());
+    #[test]
+    fn unreadable_member_file_is_reported() {
+        let directory = scratch_directory("unreadable_member_file_is_reported");
+        let project_path = directory.join("Project1.vbp");
 
-            if std::fs::metadata(&module_path).is_err() {
-                check_results.missing_files.push(format!(
-                    "Module not found: {}",
-                    module_path.to_str().unwrap()
-                ));
+        std::fs::write(&project_path, "Type=Exe\r\nModule=Module1; Module1.bas\r\n").unwrap();
+        // A directory exists (so it isn't 'missing') but can't be read as a file.
+        std::fs::create_dir(directory.join("Module1.bas")).unwrap();
 
-                continue;
-            }
+        let results = check_project(&settings(), &project_path).unwrap();
 
-            let file_name = module_path.file_name().unwrap().to_str().unwrap();
-            let module_contents = std::fs::read(&module_path).unwrap();
-            let module = VB6ModuleFile::parse(file_name.to_owned(), &module_contents);
+        assert_eq!(results.parsing_errors.len(), 1);
+        assert!(results.parsing_errors[0]
+            .to_string()
+            .starts_with("Unable to read module"));
+        assert!(results.missing_files.is_empty());
+    }
 
-            if module.is_err() {
-                let err = module.unwrap_err();
-                if err.kind == vb6parse::errors::VB6ErrorKind::LikelyNonEnglishCharacterSet {
-                    check_results.non_english_files.push(format!(
-                        "Module is likely not in an English character set: {}",
-                        file_name
-                    ));
+    #[test]
+    fn unreadable_project_file_is_reported() {
+        let directory = scratch_directory("unreadable_project_file_is_reported");
+        let project_path = directory.join("Project1.vbp");
 
-                    continue;
-                } else {
-                    check_results.parsing_errors.push(err.into());
+        std::fs::create_dir(&project_path).unwrap();
 
-                    continue;
-                }
-            }
+        let results = check_single_project(&settings(), &project_path);
 
-            let _module = module.unwrap();
-        }
+        assert_eq!(results.parsing_errors.len(), 1);
+        assert!(results.parsing_errors[0]
+            .to_string()
+            .starts_with("Unable to read project"));
     }
 
-    if check_settings.check_forms {
-        for form_reference in project.forms {
-            let form_path =
-                join_parent_project_path(project_directory, &form_reference.to_string());
+    #[cfg(unix)]
+    #[test]
+    fn non_utf8_paths_are_reported() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
 
-            if std::fs::metadata(&form_path).is_err() {
-                check_results
-                    .missing_files
-                    .push(format!("Form not found: {}", form_path.to_str().unwrap()));
+        let directory = scratch_directory("non_utf8_paths_are_reported")
+            .join(OsStr::from_bytes(b"invalid-\xff-utf8"));
+        std::fs::create_dir(&directory).unwrap();
 
-                continue;
-            }
+        let project_path = directory.join("Project1.vbp");
+        std::fs::write(&project_path, "Type=Exe\r\nModule=Module1; Module1.bas\r\n").unwrap();
 
-            let file_name = form_path.file_name().unwrap().to_str().unwrap();
-            let form_contents = std::fs::read(&form_path).unwrap();
-            let form = VB6FormFile::parse(file_name.to_owned(), &mut form_contents.as_slice());
-
-            if form.is_err() {
-                let err = form.unwrap_err();
-                if err.kind == vb6parse::errors::VB6ErrorKind::LikelyNonEnglishCharacterSet {
-                    check_results.non_english_files.push(format!(
-                        "Form is likely not in an English character set: {}",
-                        file_name
-                    ));
-
-                    continue;
-                } else {
-                    check_results.parsing_errors.push(err.into());
-                    continue;
-                }
-            }
+        let results = check_project(&settings(), &project_path).unwrap();
+
+        assert!(results.project_path.contains("invalid-\u{FFFD}-utf8"));
+        assert_eq!(results.missing_files.len(), 1);
+        assert!(results.missing_files[0]
+            .message
+            .starts_with("Module not found"));
+    }
+
+    #[test]
+    fn project_at_filesystem_root_has_a_directory() {
+        assert_eq!(
+            project_directory(Path::new("/Project1.vbp")),
+            Path::new("/")
+        );
+        assert_eq!(project_directory(Path::new("Project1.vbp")), Path::new(""));
+        assert_eq!(project_directory(Path::new("/")), Path::new(""));
+
+        assert_eq!(display_file_name(Path::new("/")), "/");
+    }
+
+    #[test]
+    fn bad_project_does_not_hide_other_projects() {
+        let directory = scratch_directory("bad_project_does_not_hide_other_projects");
+
+        std::fs::create_dir(directory.join("Broken.vbp")).unwrap();
+        std::fs::write(directory.join("Good.vbp"), "Type=Exe\r\n").unwrap();
 
-            let _form = form.unwrap();
+        let mut results = check_directory(&settings(), &directory);
+        results.sort_by(|a, b| a.project_path.cmp(&b.project_path));
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0].project_path.ends_with("Broken.vbp"));
+        assert_eq!(results[0].parsing_errors.len(), 1);
+        assert!(results[1].project_path.ends_with("Good.vbp"));
+        assert!(results[1].parsing_errors.is_empty());
+    }
+
+    #[test]
+    fn source_files_are_checked_by_extension() {
+        let directory = scratch_directory("source_files_are_checked_by_extension");
+
+        let module_path = directory.join("Module1.BAS");
+        std::fs::write(
+            &module_path,
+            "Attribute VB_Name = \"Module1\"\r\nOption Explicit\r\n",
+        )
+        .unwrap();
+
+        let class_path = directory.join("Class1.cls");
+        std::fs::write(&class_path, "Not a class file\r\n").unwrap();
+
+        let results = check_path_by_kind(&settings(), &module_path);
+        assert!(results.is_clean());
+
+        let results = check_path_by_kind(&settings(), &class_path);
+        assert_eq!(results.parsing_errors.len(), 1);
+
+        let pattern = directory.join("*.bas");
+        let matched = expand_glob(&pattern.to_string_lossy()).unwrap();
+        assert_eq!(matched, vec![module_path]);
+    }
+
+    #[test]
+    fn globs_skip_unsupported_files_and_project_members() {
+        let directory = scratch_directory("globs_skip_unsupported_files_and_project_members");
+
+        std::fs::write(
+            directory.join("Demo.vbp"),
+            "Type=Exe\r\nModule=Module1; Module1.bas\r\nForm=frmMain.frm\r\n",
+        )
+        .unwrap();
+        for file_name in [
+            "Module1.bas",
+            "frmMain.frm",
+            "Extra.bas",
+            "Demo.vbw",
+            "frmMain.frx",
+        ] {
+            std::fs::write(directory.join(file_name), "").unwrap();
         }
+
+        let matched = expand_glob(&directory.join("*").to_string_lossy()).unwrap();
+        assert_eq!(matched.len(), 6);
+
+        assert_eq!(
+            checkable_glob_matches(matched),
+            vec![directory.join("Demo.vbp"), directory.join("Extra.bas")]
+        );
+
+        // Named explicitly, an unsupported file is reported rather than
+        // parsed as a project.
+        let results = check_path_by_kind(&settings(), &directory.join("Demo.vbw"));
+        assert_eq!(results.parsing_errors.len(), 1);
+        assert_eq!(
+            results.parsing_errors[0].kind,
+            DiagnosticKind::UnsupportedFile
+        );
     }
 
-    Ok(check_results)
+    #[test]
+    fn counts_are_described_in_one_line() {
+        assert_eq!(
+            describe_counts(1, 0, 0, 1),
+            "1 missing file, 1 lint finding"
+        );
+        assert_eq!(
+            describe_counts(2, 3, 1, 0),
+            "2 missing files, 3 errors, 1 unprocessed non-English file"
+        );
+        assert_eq!(describe_counts(0, 0, 0, 4), "4 lint findings");
+    }
+
+    #[test]
+    fn code_shared_between_projects_is_dead_only_if_no_project_uses_it() {
+        let directory =
+            scratch_directory("code_shared_between_projects_is_dead_only_if_no_project_uses_it");
+        std::fs::create_dir(directory.join("Common")).unwrap();
+        std::fs::create_dir(directory.join("Client")).unwrap();
+
+        std::fs::write(
+            directory.join("Common").join("Shared.bas"),
+            concat!(
+                "Attribute VB_Name = \"Shared\"\r\n",
+                "Option Explicit\r\n",
+                "Public Sub UsedByServer()\r\n",
+                "End Sub\r\n",
+                "Public Sub UsedByNobody()\r\n",
+                "End Sub\r\n",
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            directory.join("Server.vbp"),
+            "Type=Exe\r\nModule=Shared; Common\\Shared.bas\r\nModule=Main; Main.bas\r\n",
+        )
+        .unwrap();
+        std::fs::write(
+            directory.join("Main.bas"),
+            concat!(
+                "Attribute VB_Name = \"Main\"\r\n",
+                "Option Explicit\r\n",
+                "Sub Main()\r\n",
+                "    UsedByServer\r\n",
+                "End Sub\r\n",
+            ),
+        )
+        .unwrap();
+        // The client reaches the shared module through a different path.
+        std::fs::write(
+            directory.join("Client").join("Client.vbp"),
+            "Type=Exe\r\nModule=Shared; ..\\Common\\Shared.bas\r\n",
+        )
+        .unwrap();
+
+        let dead_code = |check_results: &CheckResults| {
+            check_results
+                .lint_findings
+                .iter()
+                .filter(|diagnostic| diagnostic.kind == DiagnosticKind::UnusedProcedure)
+                .map(|diagnostic| diagnostic.message.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let mut check_summary = vec![
+            check_single_project(&settings(), &directory.join("Server.vbp")),
+            check_single_project(&settings(), &directory.join("Client").join("Client.vbp")),
+        ];
+        assert_eq!(dead_code(&check_summary[1]).len(), 2);
+
+        remove_dead_code_used_elsewhere(&mut check_summary);
+
+        assert_eq!(
+            dead_code(&check_summary[0]),
+            ["Public procedure 'UsedByNobody' is never called"]
+        );
+        assert_eq!(
+            dead_code(&check_summary[1]),
+            ["Public procedure 'UsedByNobody' is never called"]
+        );
+    }
+
+    #[test]
+    fn references_without_a_description_are_noted() {
+        let directory = scratch_directory("references_without_a_description_are_noted");
+        let project_path = directory.join("Project1.vbp");
+
+        std::fs::write(
+            &project_path,
+            concat!(
+                "Type=Exe\r\n",
+                "Reference=*\\G{00020430-0000-0000-C000-000000000046}#2.0#0#C:\\Windows\\System32\\stdole2.tlb#OLE Automation\r\n",
+                "Object={831FDD16-0C5C-11D2-A9FC-0000F8754DA1}#2.2#0; MSCOMCTL.OCX\r\n",
+            ),
+        )
+        .unwrap();
+
+        let check_settings = CheckSettings {
+            type_libraries: TypeLibraries::load(&[]).unwrap(),
+            ..settings()
+        };
+        let results = check_project(&check_settings, &project_path).unwrap();
+
+        assert!(results.is_clean());
+        let notes: Vec<String> = results
+            .notes
+            .iter()
+            .map(|note| format!("{}: {}", note.region.unwrap().line, note.message))
+            .collect();
+        assert_eq!(
+            notes,
+            ["2: No type library description for 'OLE Automation' {00020430-0000-0000-C000-000000000046} 2.0, so the members used from it aren't checked"]
+        );
+    }
 }
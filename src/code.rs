@@ -0,0 +1,1132 @@
+//! A light reading of the code in a class, module, or form.
+//!
+//! vb6parse only tokenizes code, so this splits it into statements and picks
+//! out the declarations the checks need. It works line by line rather than
+//! from vb6parse's tokens so that it still works on files vb6parse rejects.
+
+use crate::runtime::is_built_in;
+
+/// The `DefType` statements setting the default type of variables by their
+/// first letter.
+pub const DEF_TYPE_KEYWORDS: [&str; 12] = [
+    "defbool", "defbyte", "defint", "deflng", "defcur", "defsng", "defdbl", "defdec", "defdate",
+    "defstr", "defobj", "defvar",
+];
+
+/// A single statement, with comments, line continuations, and the `:`
+/// separating statements on one line removed. Labels are statements of
+/// their own, keeping their `:`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Statement {
+    /// The 1-based line the statement starts on.
+    pub line: usize,
+    pub tokens: Vec<String>,
+}
+
+impl Statement {
+    /// The label the statement starts with: a name followed by `:` on its
+    /// own, or a line number in front of the rest of the statement.
+    pub fn label(&self) -> Option<&str> {
+        match self.tokens.as_slice() {
+            [name, colon] if colon == ":" => Some(name),
+            [number, ..] if number.chars().all(|character| character.is_ascii_digit()) => {
+                Some(number)
+            }
+            _ => None,
+        }
+    }
+
+    /// The tokens after the statement's label, if it has one.
+    pub fn code(&self) -> &[String] {
+        match self.tokens.as_slice() {
+            [_, colon] if colon == ":" => &[],
+            [number, rest @ ..] if number.chars().all(|character| character.is_ascii_digit()) => {
+                rest
+            }
+            tokens => tokens,
+        }
+    }
+
+    /// Checks if the statement starts with `words`, ignoring case.
+    pub fn starts_with(&self, words: &[&str]) -> bool {
+        self.tokens.len() >= words.len()
+            && self
+                .tokens
+                .iter()
+                .zip(words)
+                .all(|(token, word)| token.eq_ignore_ascii_case(word))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    Public,
+    Private,
+    Friend,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcedureKind {
+    Sub,
+    Function,
+    PropertyGet,
+    PropertyLet,
+    PropertySet,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parameter {
+    pub name: String,
+    /// The declared type, or `None` for an implicit Variant.
+    pub type_name: Option<String>,
+    pub by_val: bool,
+    pub optional: bool,
+    pub param_array: bool,
+    pub is_array: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Procedure {
+    pub name: String,
+    pub kind: ProcedureKind,
+    pub visibility: Visibility,
+    pub parameters: Vec<Parameter>,
+    /// The declared return type of a function or property get.
+    pub return_type: Option<String>,
+    /// The 1-based line the procedure is declared on.
+    pub line: usize,
+    /// The statements between the declaration and the `End`.
+    pub body: Vec<Statement>,
+}
+
+impl Procedure {
+    /// The variables declared with `Dim` or `Static` in the body.
+    pub fn local_variables(&self) -> Vec<Variable> {
+        self.body
+            .iter()
+            .filter(|statement| {
+                statement.starts_with(&["Dim"]) || statement.starts_with(&["Static"])
+            })
+            .flat_map(|statement| {
+                parse_variables(Visibility::Private, &statement.tokens[1..], statement.line)
+            })
+            .collect()
+    }
+
+    /// The constants declared with `Const` in the body.
+    pub fn local_constants(&self) -> Vec<Constant> {
+        self.body
+            .iter()
+            .filter(|statement| statement.starts_with(&["Const"]))
+            .flat_map(|statement| {
+                split_top_level(&statement.tokens[1..], ",")
+                    .into_iter()
+                    .filter_map(|tokens| tokens.first())
+                    .map(|name| Constant {
+                        name: name.clone(),
+                        visibility: Visibility::Private,
+                        line: statement.line,
+                    })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub name: String,
+    pub parameters: Vec<Parameter>,
+    pub line: usize,
+}
+
+/// A `Declare` statement naming a procedure in a DLL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Declare {
+    pub name: String,
+    pub visibility: Visibility,
+    /// Either [`ProcedureKind::Sub`] or [`ProcedureKind::Function`].
+    pub kind: ProcedureKind,
+    /// The library as written, such as `user32` or `kernel32.dll`.
+    pub library: String,
+    /// The name of the procedure in the library, when it differs.
+    pub alias: Option<String>,
+    pub parameters: Vec<Parameter>,
+    pub return_type: Option<String>,
+    pub line: usize,
+}
+
+impl Declare {
+    /// The name the procedure is exported from the library as.
+    pub fn entry_point(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// A constant declared outside any procedure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constant {
+    pub name: String,
+    pub visibility: Visibility,
+    pub line: usize,
+}
+
+/// A variable declared outside any procedure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variable {
+    pub name: String,
+    pub visibility: Visibility,
+    pub type_name: Option<String>,
+    pub is_array: bool,
+    pub line: usize,
+}
+
+/// An `Enum` and the names of its members.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Enumeration {
+    pub name: String,
+    pub members: Vec<String>,
+}
+
+/// The declarations found in a class, module, or form.
+#[derive(Debug, Default)]
+pub struct CodeModule {
+    pub procedures: Vec<Procedure>,
+    pub events: Vec<Event>,
+    pub variables: Vec<Variable>,
+    pub constants: Vec<Constant>,
+    pub declares: Vec<Declare>,
+    pub enums: Vec<Enumeration>,
+    /// The interfaces named by `Implements` statements.
+    pub interfaces: Vec<String>,
+    /// The statements outside any procedure, including declarations.
+    pub module_statements: Vec<Statement>,
+}
+
+impl CodeModule {
+    pub fn parse(source: &[u8]) -> Self {
+        let mut code_module = CodeModule::default();
+        let mut in_procedure = false;
+        let mut in_type_or_enum = false;
+        let mut in_enum = false;
+
+        for statement in statements(source) {
+            if in_procedure {
+                if statement.starts_with(&["End", "Sub"])
+                    || statement.starts_with(&["End", "Function"])
+                    || statement.starts_with(&["End", "Property"])
+                {
+                    in_procedure = false;
+                } else if let Some(procedure) = code_module.procedures.last_mut() {
+                    procedure.body.push(statement);
+                }
+                continue;
+            }
+
+            code_module.module_statements.push(statement.clone());
+
+            if in_type_or_enum {
+                if statement.starts_with(&["End", "Type"])
+                    || statement.starts_with(&["End", "Enum"])
+                {
+                    in_type_or_enum = false;
+                    in_enum = false;
+                } else if in_enum {
+                    if let Some(enumeration) = code_module.enums.last_mut() {
+                        enumeration.members.push(statement.tokens[0].clone());
+                    }
+                }
+                continue;
+            }
+
+            let (visibility, rest) = split_visibility(&statement.tokens);
+
+            if let Some(procedure) = parse_procedure(visibility, rest, statement.line) {
+                // The declaration belongs to the procedure rather than the
+                // module.
+                code_module.module_statements.pop();
+                code_module.procedures.push(procedure);
+                in_procedure = true;
+                continue;
+            }
+
+            match rest
+                .first()
+                .map(|token| token.to_ascii_lowercase())
+                .as_deref()
+            {
+                Some("type") => in_type_or_enum = true,
+                Some("enum") => {
+                    in_type_or_enum = true;
+                    in_enum = true;
+                    if let Some(name) = rest.get(1) {
+                        code_module.enums.push(Enumeration {
+                            name: name.clone(),
+                            members: Vec::new(),
+                        });
+                    }
+                }
+                Some("event") => {
+                    if let Some(name) = rest.get(1) {
+                        code_module.events.push(Event {
+                            name: name.clone(),
+                            parameters: parse_parameter_list(&rest[2..]).0,
+                            line: statement.line,
+                        });
+                    }
+                }
+                Some("implements") => {
+                    if let Some(name) = rest.get(1) {
+                        code_module.interfaces.push(name.clone());
+                    }
+                }
+                Some("declare") => {
+                    // Declares are public unless declared otherwise.
+                    let visibility = visibility.unwrap_or(Visibility::Public);
+                    if let Some(declare) = parse_declare(visibility, rest, statement.line) {
+                        code_module.declares.push(declare);
+                    }
+                }
+                Some("const") => {
+                    let visibility = visibility.unwrap_or(Visibility::Private);
+                    code_module.constants.extend(
+                        split_top_level(&rest[1..], ",")
+                            .into_iter()
+                            .filter_map(|tokens| tokens.first())
+                            .map(|name| Constant {
+                                name: name.clone(),
+                                visibility,
+                                line: statement.line,
+                            }),
+                    );
+                }
+                Some("option") => {}
+                Some(first) if DEF_TYPE_KEYWORDS.contains(&first) => {}
+                _ => {
+                    let is_declaration = visibility.is_some()
+                        || statement.starts_with(&["Dim"])
+                        || statement.starts_with(&["Global"]);
+
+                    if is_declaration {
+                        let visibility = match visibility {
+                            Some(visibility) => visibility,
+                            None if statement.starts_with(&["Global"]) => Visibility::Public,
+                            None => Visibility::Private,
+                        };
+                        let rest = match rest.first() {
+                            Some(first)
+                                if first.eq_ignore_ascii_case("Dim")
+                                    || first.eq_ignore_ascii_case("Global") =>
+                            {
+                                &rest[1..]
+                            }
+                            _ => rest,
+                        };
+
+                        code_module.variables.extend(parse_variables(
+                            visibility,
+                            rest,
+                            statement.line,
+                        ));
+                    }
+                }
+            }
+        }
+
+        code_module
+    }
+}
+
+/// The type given to a name by its type suffix, such as `String` for
+/// `Name$`.
+pub fn suffix_type(name: &str) -> Option<&'static str> {
+    match name.chars().last()? {
+        '%' => Some("Integer"),
+        '&' => Some("Long"),
+        '!' => Some("Single"),
+        '#' => Some("Double"),
+        '$' => Some("String"),
+        '@' => Some("Currency"),
+        _ => None,
+    }
+}
+
+/// The name VB6 matches an identifier by: without its case or type suffix.
+pub fn identifier_key(name: &str) -> String {
+    name.trim_end_matches(['%', '&', '$', '!', '#', '@'])
+        .to_ascii_lowercase()
+}
+
+/// Splits the code of a file into statements, skipping the header VB6 writes
+/// before the code and `Attribute` lines.
+pub fn statements(source: &[u8]) -> Vec<Statement> {
+    let source = String::from_utf8_lossy(source);
+    let lines: Vec<&str> = source.lines().collect();
+
+    // Everything before the VB_Name attribute is the file's header, and for
+    // forms the description of the controls on it.
+    let code_start = lines
+        .iter()
+        .position(|line| {
+            let words = split_words(line);
+            words.len() >= 2
+                && words[0].eq_ignore_ascii_case("Attribute")
+                && words[1].eq_ignore_ascii_case("VB_Name")
+        })
+        .unwrap_or(0);
+
+    let mut statements = Vec::new();
+    let mut line_index = code_start;
+
+    while line_index < lines.len() {
+        let first_line = line_index + 1;
+        let mut code = strip_comment(lines[line_index]).to_owned();
+
+        while ends_with_continuation(&code) && line_index + 1 < lines.len() {
+            code.truncate(code.trim_end().len() - 1);
+            line_index += 1;
+            code.push(' ');
+            code.push_str(strip_comment(lines[line_index]));
+        }
+        line_index += 1;
+
+        let words = split_words(&code);
+        if words
+            .first()
+            .is_some_and(|word| word.eq_ignore_ascii_case("Attribute"))
+        {
+            continue;
+        }
+
+        for tokens in split_statements(tokenize(&code)) {
+            if tokens.is_empty() {
+                continue;
+            }
+
+            // `Rem` comments out the rest of the line, like `'`.
+            if tokens[0].eq_ignore_ascii_case("Rem") {
+                break;
+            }
+
+            statements.push(Statement {
+                line: first_line,
+                tokens,
+            });
+        }
+    }
+
+    statements
+}
+
+fn split_words(line: &str) -> Vec<&str> {
+    line.split(|character: char| character.is_whitespace() || character == '=')
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+fn ends_with_continuation(code: &str) -> bool {
+    let code = code.trim_end();
+    code.ends_with(" _") || code.ends_with("\t_") || code == "_"
+}
+
+/// Removes a `'` comment from the end of a line, leaving any `'` in strings.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+
+    for (position, character) in line.char_indices() {
+        match character {
+            '"' => in_string = !in_string,
+            '\'' if !in_string => return &line[..position],
+            _ => {}
+        }
+    }
+
+    line
+}
+
+/// Splits a line of code into identifiers, literals, and operators.
+///
+/// Identifiers keep their type suffix (`Name$`), strings keep their quotes,
+/// and `[escaped names]` lose their brackets.
+pub fn tokenize(code: &str) -> Vec<String> {
+    let characters: Vec<char> = code.chars().collect();
+    let mut tokens = Vec::new();
+    let mut position = 0;
+
+    while position < characters.len() {
+        let character = characters[position];
+        let start = position;
+
+        if character.is_whitespace() {
+            position += 1;
+            continue;
+        }
+
+        if character == '"' {
+            position += 1;
+            loop {
+                match characters.get(position) {
+                    // A doubled quote is an escaped quote within the string.
+                    Some('"') if characters.get(position + 1) == Some(&'"') => position += 2,
+                    Some('"') => {
+                        position += 1;
+                        break;
+                    }
+                    Some(_) => position += 1,
+                    None => break,
+                }
+            }
+        } else if character == '[' {
+            let end = characters[position..]
+                .iter()
+                .position(|&character| character == ']')
+                .map_or(characters.len(), |end| position + end);
+            tokens.push(characters[position + 1..end].iter().collect());
+            position = (end + 1).min(characters.len());
+            continue;
+        } else if character.is_alphabetic() || character == '_' {
+            while characters
+                .get(position)
+                .is_some_and(|character| character.is_alphanumeric() || *character == '_')
+            {
+                position += 1;
+            }
+            if let Some('%' | '&' | '$' | '!' | '#' | '@') = characters.get(position) {
+                // `a!b` is a dictionary lookup rather than a suffix.
+                let is_lookup = characters[position] == '!'
+                    && characters
+                        .get(position + 1)
+                        .is_some_and(|character| character.is_alphabetic());
+                if !is_lookup {
+                    position += 1;
+                }
+            }
+        } else if character.is_ascii_digit()
+            || (character == '.'
+                && characters
+                    .get(position + 1)
+                    .is_some_and(char::is_ascii_digit))
+        {
+            while characters
+                .get(position)
+                .is_some_and(|character| character.is_ascii_alphanumeric() || *character == '.')
+            {
+                position += 1;
+            }
+            if let Some('%' | '&' | '!' | '#' | '@') = characters.get(position) {
+                position += 1;
+            }
+        } else if character == '&'
+            && characters
+                .get(position + 1)
+                .is_some_and(|next| matches!(next, 'H' | 'h' | 'O' | 'o'))
+        {
+            // Hex and octal literals, such as &HFF&.
+            position += 2;
+            while characters
+                .get(position)
+                .is_some_and(char::is_ascii_hexdigit)
+            {
+                position += 1;
+            }
+            if let Some('&' | '%') = characters.get(position) {
+                position += 1;
+            }
+        } else {
+            let pair: String = characters[position..(position + 2).min(characters.len())]
+                .iter()
+                .collect();
+            position += match pair.as_str() {
+                "<>" | "<=" | ">=" | ":=" => 2,
+                _ => 1,
+            };
+        }
+
+        tokens.push(characters[start..position].iter().collect());
+    }
+
+    tokens
+}
+
+/// Splits a line's tokens into statements at each `:`, except the `:` after
+/// a label at the start of the line, which stays with it as in `Failed :`.
+fn split_statements(tokens: Vec<String>) -> Vec<Vec<String>> {
+    let mut statements: Vec<Vec<String>> = vec![Vec::new()];
+
+    for token in tokens {
+        if token == ":" {
+            let is_label = match statements.as_slice() {
+                [first] => first.len() == 1 && is_label_name(&first[0]),
+                _ => false,
+            };
+            if is_label {
+                statements[0].push(token);
+            }
+            statements.push(Vec::new());
+        } else {
+            statements.last_mut().expect("never empty").push(token);
+        }
+    }
+
+    statements
+}
+
+fn is_label_name(name: &str) -> bool {
+    // `Else:` and `DoEvents:` are statements rather than labels.
+    name.starts_with(|character: char| character.is_alphabetic()) && !is_built_in(name)
+}
+
+fn split_visibility(tokens: &[String]) -> (Option<Visibility>, &[String]) {
+    let visibility = match tokens
+        .first()
+        .map(|token| token.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("public") => Some(Visibility::Public),
+        Some("private") => Some(Visibility::Private),
+        Some("friend") => Some(Visibility::Friend),
+        _ => None,
+    };
+
+    match visibility {
+        Some(_) => (visibility, &tokens[1..]),
+        None => (None, tokens),
+    }
+}
+
+fn parse_procedure(
+    visibility: Option<Visibility>,
+    tokens: &[String],
+    line: usize,
+) -> Option<Procedure> {
+    let mut tokens = tokens;
+    if tokens
+        .first()
+        .is_some_and(|token| token.eq_ignore_ascii_case("Static"))
+    {
+        tokens = &tokens[1..];
+    }
+
+    let keyword = tokens.first()?.to_ascii_lowercase();
+    let (kind, name_index) = match keyword.as_str() {
+        "sub" => (ProcedureKind::Sub, 1),
+        "function" => (ProcedureKind::Function, 1),
+        "property" => match tokens.get(1)?.to_ascii_lowercase().as_str() {
+            "get" => (ProcedureKind::PropertyGet, 2),
+            "let" => (ProcedureKind::PropertyLet, 2),
+            "set" => (ProcedureKind::PropertySet, 2),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    let name = tokens.get(name_index)?.clone();
+    let (parameters, rest) = parse_parameter_list(&tokens[name_index + 1..]);
+    let return_type = parse_as_clause(rest).map(|(type_name, _)| type_name);
+
+    Some(Procedure {
+        name,
+        kind,
+        // Procedures are public unless declared otherwise.
+        visibility: visibility.unwrap_or(Visibility::Public),
+        parameters,
+        return_type,
+        line,
+        body: Vec::new(),
+    })
+}
+
+/// Parses `Declare [PtrSafe] Sub|Function Name Lib "library" [Alias "name"]
+/// [(parameters)] [As Type]`.
+fn parse_declare(visibility: Visibility, tokens: &[String], line: usize) -> Option<Declare> {
+    let mut tokens = &tokens[1..];
+    if tokens
+        .first()
+        .is_some_and(|token| token.eq_ignore_ascii_case("PtrSafe"))
+    {
+        tokens = &tokens[1..];
+    }
+
+    let kind = match tokens.first()?.to_ascii_lowercase().as_str() {
+        "sub" => ProcedureKind::Sub,
+        "function" => ProcedureKind::Function,
+        _ => return None,
+    };
+    let name = tokens.get(1)?.clone();
+
+    if !tokens.get(2)?.eq_ignore_ascii_case("Lib") {
+        return None;
+    }
+    let library = unquote(tokens.get(3)?);
+    let mut rest = &tokens[4..];
+
+    let mut alias = None;
+    if rest
+        .first()
+        .is_some_and(|token| token.eq_ignore_ascii_case("Alias"))
+    {
+        alias = Some(unquote(rest.get(1)?));
+        rest = &rest[2..];
+    }
+
+    let (parameters, rest) = parse_parameter_list(rest);
+    let return_type = parse_as_clause(rest).map(|(type_name, _)| type_name);
+
+    Some(Declare {
+        name,
+        visibility,
+        kind,
+        library,
+        alias,
+        parameters,
+        return_type,
+        line,
+    })
+}
+
+fn unquote(token: &str) -> String {
+    token
+        .strip_prefix('"')
+        .and_then(|token| token.strip_suffix('"'))
+        .unwrap_or(token)
+        .replace("\"\"", "\"")
+}
+
+/// Parses a parenthesized parameter list, returning the tokens after it.
+fn parse_parameter_list(tokens: &[String]) -> (Vec<Parameter>, &[String]) {
+    if tokens.first().map(String::as_str) != Some("(") {
+        return (Vec::new(), tokens);
+    }
+
+    let mut depth = 0;
+    let mut end = tokens.len();
+    for (position, token) in tokens.iter().enumerate() {
+        match token.as_str() {
+            "(" => depth += 1,
+            ")" => {
+                depth -= 1;
+                if depth == 0 {
+                    end = position;
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let parameters = split_top_level(&tokens[1..end], ",")
+        .into_iter()
+        .filter(|tokens| !tokens.is_empty())
+        .filter_map(parse_parameter)
+        .collect();
+
+    (parameters, &tokens[(end + 1).min(tokens.len())..])
+}
+
+fn parse_parameter(tokens: &[String]) -> Option<Parameter> {
+    let mut parameter = Parameter {
+        name: String::new(),
+        type_name: None,
+        by_val: false,
+        optional: false,
+        param_array: false,
+        is_array: false,
+    };
+
+    let mut position = 0;
+    while let Some(token) = tokens.get(position) {
+        match token.to_ascii_lowercase().as_str() {
+            "optional" => parameter.optional = true,
+            "byval" => parameter.by_val = true,
+            "byref" => parameter.by_val = false,
+            "paramarray" => parameter.param_array = true,
+            _ => break,
+        }
+        position += 1;
+    }
+
+    parameter.name = tokens.get(position)?.clone();
+    let rest = &tokens[position + 1..];
+
+    let rest = if rest.first().map(String::as_str) == Some("(") {
+        parameter.is_array = true;
+        let close = rest.iter().position(|token| token == ")").unwrap_or(0);
+        &rest[close + 1..]
+    } else {
+        rest
+    };
+
+    parameter.type_name = parse_as_clause(rest).map(|(type_name, _)| type_name);
+
+    Some(parameter)
+}
+
+/// Parses `As [New] Type[.Member][()]`, returning the type and whether it
+/// is an array.
+fn parse_as_clause(tokens: &[String]) -> Option<(String, bool)> {
+    if !tokens.first()?.eq_ignore_ascii_case("As") {
+        return None;
+    }
+
+    let mut rest = &tokens[1..];
+    if rest
+        .first()
+        .is_some_and(|token| token.eq_ignore_ascii_case("New"))
+    {
+        rest = &rest[1..];
+    }
+
+    let mut type_name = rest.first()?.clone();
+    let mut position = 1;
+    while rest.get(position).map(String::as_str) == Some(".") {
+        type_name.push('.');
+        type_name.push_str(rest.get(position + 1)?);
+        position += 2;
+    }
+
+    let is_array = rest.get(position).map(String::as_str) == Some("(");
+
+    Some((type_name, is_array))
+}
+
+fn parse_variables(visibility: Visibility, tokens: &[String], line: usize) -> Vec<Variable> {
+    split_top_level(tokens, ",")
+        .into_iter()
+        .filter_map(|tokens| {
+            let tokens = match tokens.first() {
+                Some(first) if first.eq_ignore_ascii_case("WithEvents") => &tokens[1..],
+                _ => tokens,
+            };
+
+            let name = tokens.first()?.clone();
+            let mut rest = &tokens[1..];
+            let mut is_array = false;
+
+            if rest.first().map(String::as_str) == Some("(") {
+                is_array = true;
+                let close = rest.iter().position(|token| token == ")").unwrap_or(0);
+                rest = &rest[close + 1..];
+            }
+
+            let type_name = parse_as_clause(rest).map(|(type_name, _)| type_name);
+
+            Some(Variable {
+                name,
+                visibility,
+                type_name,
+                is_array,
+                line,
+            })
+        })
+        .collect()
+}
+
+/// Splits tokens on `separator`, ignoring any inside parentheses.
+fn split_top_level<'a>(tokens: &'a [String], separator: &str) -> Vec<&'a [String]> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (position, token) in tokens.iter().enumerate() {
+        match token.as_str() {
+            "(" => depth += 1,
+            ")" => depth -= 1,
+            token if token == separator && depth == 0 => {
+                parts.push(&tokens[start..position]);
+                start = position + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&tokens[start..]);
+
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn declarations_are_read() {
+        let source = concat!(
+            "VERSION 1.0 CLASS\r\n",
+            "BEGIN\r\n",
+            "  MultiUse = -1  'True\r\n",
+            "END\r\n",
+            "Attribute VB_Name = \"Widget\"\r\n",
+            "Option Explicit\r\n",
+            "Public Event Changed(ByVal Index As Long)\r\n",
+            "Public Caption As String, Count&\r\n",
+            "Private Declare Function GetWindowText Lib \"user32\" Alias \"GetWindowTextA\" _\r\n",
+            "    (ByVal hWnd As Long, ByVal lpString$, ByVal cch As Long) As Long\r\n",
+            "Private Type Point\r\n",
+            "  X As Long\r\n",
+            "End Type\r\n",
+            "Public Function Render(ByVal Scale As Single, _\r\n",
+            "    Optional Target As Object) As String ' draws it\r\n",
+            "Attribute Render.VB_UserMemId = 0\r\n",
+            "  Dim Local As Long: Local = 1\r\n",
+            "End Function\r\n",
+            "Property Let Value(ByRef NewValue() As Variant)\r\n",
+            "End Property\r\n",
+        );
+
+        let code_module = CodeModule::parse(source.as_bytes());
+
+        assert_eq!(code_module.events.len(), 1);
+        assert_eq!(code_module.events[0].parameters[0].name, "Index");
+
+        let variables: Vec<_> = code_module
+            .variables
+            .iter()
+            .map(|variable| (variable.name.as_str(), variable.type_name.as_deref()))
+            .collect();
+        assert_eq!(variables, [("Caption", Some("String")), ("Count&", None)]);
+
+        let declare = &code_module.declares[0];
+        assert_eq!(declare.visibility, Visibility::Private);
+        assert_eq!(declare.library, "user32");
+        assert_eq!(declare.entry_point(), "GetWindowTextA");
+        assert_eq!(declare.parameters.len(), 3);
+        assert_eq!(declare.return_type.as_deref(), Some("Long"));
+
+        assert_eq!(code_module.procedures.len(), 2);
+        let render = &code_module.procedures[0];
+        assert_eq!(render.name, "Render");
+        assert_eq!(render.kind, ProcedureKind::Function);
+        assert_eq!(render.line, 14);
+        assert_eq!(render.return_type.as_deref(), Some("String"));
+        assert!(render.parameters[0].by_val);
+        assert!(render.parameters[1].optional);
+
+        let value = &code_module.procedures[1];
+        assert_eq!(value.kind, ProcedureKind::PropertyLet);
+        assert_eq!(value.visibility, Visibility::Public);
+        assert!(value.parameters[0].is_array);
+    }
+
+    #[test]
+    fn procedures_are_read_with_their_bodies() {
+        let source = concat!(
+            "Attribute VB_Name = \"Module1\"\r\n",
+            "Private Sub Helper()\r\n",
+            "  Count = Count + 1: Done = True ' both on one line\r\n",
+            "Retry:\r\n",
+            "  If Done Then Else: DoEvents\r\n",
+            "End Sub\r\n",
+            "Friend Static Function Total%(ParamArray Values())\r\n",
+            "  Rem Total = 0: not a statement\r\n",
+            "End Function\r\n",
+            "Property Set Target(ByVal Value As Object)\r\n",
+            "End Property\r\n",
+            "Public Property Get Target() As Object\r\n",
+            "End Property\r\n",
+        );
+
+        let code_module = CodeModule::parse(source.as_bytes());
+        assert!(code_module.module_statements.is_empty());
+
+        let procedures: Vec<_> = code_module
+            .procedures
+            .iter()
+            .map(|procedure| {
+                (
+                    procedure.name.as_str(),
+                    procedure.kind,
+                    procedure.visibility,
+                    procedure.line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            procedures,
+            [
+                ("Helper", ProcedureKind::Sub, Visibility::Private, 2),
+                ("Total%", ProcedureKind::Function, Visibility::Friend, 7),
+                ("Target", ProcedureKind::PropertySet, Visibility::Public, 10),
+                ("Target", ProcedureKind::PropertyGet, Visibility::Public, 12),
+            ]
+        );
+
+        let helper = &code_module.procedures[0];
+        let body: Vec<(usize, String)> = helper
+            .body
+            .iter()
+            .map(|statement| (statement.line, statement.tokens.join(" ")))
+            .collect();
+        assert_eq!(
+            body,
+            [
+                (3, "Count = Count + 1".to_owned()),
+                (3, "Done = True".to_owned()),
+                (4, "Retry :".to_owned()),
+                (5, "If Done Then Else".to_owned()),
+                (5, "DoEvents".to_owned()),
+            ]
+        );
+        assert_eq!(helper.body[2].label(), Some("Retry"));
+        assert!(helper.body[2].code().is_empty());
+        assert_eq!(helper.body[3].label(), None);
+
+        let total = &code_module.procedures[1];
+        assert!(total.body.is_empty());
+        assert!(total.parameters[0].param_array);
+        assert!(total.parameters[0].is_array);
+
+        let target = &code_module.procedures[3];
+        assert_eq!(target.return_type.as_deref(), Some("Object"));
+    }
+
+    #[test]
+    fn declares_are_read() {
+        let source = concat!(
+            "Attribute VB_Name = \"Module1\"\r\n",
+            "Declare Sub Sleep Lib \"kernel32\" (ByVal dwMilliseconds As Long)\r\n",
+            "Private Declare PtrSafe Function GetTickCount& Lib \"kernel32.dll\" ()\r\n",
+            "Public Declare Function MessageBox Lib \"user32\" Alias \"MessageBoxA\" ( _\r\n",
+            "    ByVal hWnd As Long, _\r\n",
+            "    ByVal lpText As String, _\r\n",
+            "    ByVal lpCaption As String, _\r\n",
+            "    ByVal wType As Long) As Long\r\n",
+            "Private Declare Sub Broken Lib\r\n",
+        );
+
+        let code_module = CodeModule::parse(source.as_bytes());
+        assert_eq!(code_module.declares.len(), 3);
+
+        let sleep = &code_module.declares[0];
+        assert_eq!(sleep.visibility, Visibility::Public);
+        assert_eq!(sleep.kind, ProcedureKind::Sub);
+        assert_eq!(sleep.library, "kernel32");
+        assert_eq!(sleep.entry_point(), "Sleep");
+        assert_eq!(sleep.parameters[0].type_name.as_deref(), Some("Long"));
+        assert_eq!(sleep.return_type, None);
+
+        let get_tick_count = &code_module.declares[1];
+        assert_eq!(get_tick_count.name, "GetTickCount&");
+        assert_eq!(get_tick_count.visibility, Visibility::Private);
+        assert_eq!(get_tick_count.kind, ProcedureKind::Function);
+        assert_eq!(get_tick_count.library, "kernel32.dll");
+        assert!(get_tick_count.parameters.is_empty());
+
+        let message_box = &code_module.declares[2];
+        assert_eq!(message_box.line, 4);
+        assert_eq!(message_box.alias.as_deref(), Some("MessageBoxA"));
+        let parameters: Vec<&str> = message_box
+            .parameters
+            .iter()
+            .map(|parameter| parameter.name.as_str())
+            .collect();
+        assert_eq!(parameters, ["hWnd", "lpText", "lpCaption", "wType"]);
+        assert_eq!(message_box.return_type.as_deref(), Some("Long"));
+    }
+
+    #[test]
+    fn line_continuations_join_statements() {
+        let source = concat!(
+            "VERSION 5.00\r\n",
+            "Begin VB.Form frmMain\r\n",
+            "End\r\n",
+            "Attribute VB_Name = \"frmMain\"\r\n",
+            "Const Greeting = \"Hello _\" ' not continued\r\n",
+            "Const Path = \"C:\\It's\" & _\r\n",
+            "    \"here\"\r\n",
+            "Private Values( _\r\n",
+            "  1 To 3) As Long\r\n",
+            "Private Label$ ' a comment _\r\n",
+            "Private Width!\r\n",
+        );
+
+        let statements: Vec<(usize, Vec<String>)> = statements(source.as_bytes())
+            .into_iter()
+            .map(|statement| (statement.line, statement.tokens))
+            .collect();
+
+        let expected: Vec<(usize, Vec<&str>)> = vec![
+            (5, vec!["Const", "Greeting", "=", "\"Hello _\""]),
+            (
+                6,
+                vec!["Const", "Path", "=", "\"C:\\It's\"", "&", "\"here\""],
+            ),
+            (
+                8,
+                vec!["Private", "Values", "(", "1", "To", "3", ")", "As", "Long"],
+            ),
+            (10, vec!["Private", "Label$"]),
+            (11, vec!["Private", "Width!"]),
+        ];
+        assert_eq!(
+            statements,
+            expected
+                .into_iter()
+                .map(|(line, tokens)| (line, tokens.into_iter().map(str::to_owned).collect()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn every_variable_of_a_dim_is_read() {
+        let source = concat!(
+            "Attribute VB_Name = \"Module1\"\r\n",
+            "Dim First, Second As Long, Third$\r\n",
+            "Public Grid(1 To 3, 1 To 3) As Integer, WithEvents Timer As VB.Timer\r\n",
+            "Global Shared As New Collection\r\n",
+            "Public Sub Main()\r\n",
+            "  Dim Items() As String, Index&: Static Calls As Long\r\n",
+            "  Const Limit = 10, Name = \"Main\"\r\n",
+            "End Sub\r\n",
+        );
+
+        let code_module = CodeModule::parse(source.as_bytes());
+
+        let describe = |variable: &Variable| {
+            (
+                variable.name.clone(),
+                variable.visibility,
+                variable.type_name.clone(),
+                variable.is_array,
+                variable.line,
+            )
+        };
+        let variable = |name: &str, visibility, type_name: Option<&str>, is_array, line| {
+            (
+                name.to_owned(),
+                visibility,
+                type_name.map(str::to_owned),
+                is_array,
+                line,
+            )
+        };
+
+        let module_variables: Vec<_> = code_module.variables.iter().map(describe).collect();
+        assert_eq!(
+            module_variables,
+            [
+                variable("First", Visibility::Private, None, false, 2),
+                variable("Second", Visibility::Private, Some("Long"), false, 2),
+                variable("Third$", Visibility::Private, None, false, 2),
+                variable("Grid", Visibility::Public, Some("Integer"), true, 3),
+                variable("Timer", Visibility::Public, Some("VB.Timer"), false, 3),
+                variable("Shared", Visibility::Public, Some("Collection"), false, 4),
+            ]
+        );
+
+        let main = &code_module.procedures[0];
+        let local_variables: Vec<_> = main.local_variables().iter().map(describe).collect();
+        assert_eq!(
+            local_variables,
+            [
+                variable("Items", Visibility::Private, Some("String"), true, 6),
+                variable("Index&", Visibility::Private, None, false, 6),
+                variable("Calls", Visibility::Private, Some("Long"), false, 6),
+            ]
+        );
+
+        let local_constants: Vec<String> = main
+            .local_constants()
+            .into_iter()
+            .map(|constant| constant.name)
+            .collect();
+        assert_eq!(local_constants, ["Limit", "Name"]);
+    }
+}
@@ -0,0 +1,212 @@
+use std::path::{Path, PathBuf};
+
+use vb6parse::parsers::VB6Project;
+
+use crate::check::{join_parent_project_path, project_directory};
+use crate::code::CodeModule;
+use crate::diagnostic::{Diagnostic, DiagnosticKind, Region};
+use crate::project_settings::attribute_value;
+use crate::typelib::{Interface, TypeLibrary};
+
+/// The path of the type library dump describing a compatible binary:
+/// `Widgets.dll` is described by `Widgets.dll.json` beside it.
+pub fn type_library_dump_path(compatible_path: &Path) -> PathBuf {
+    let mut dump_path = compatible_path.as_os_str().to_owned();
+    dump_path.push(".json");
+    PathBuf::from(dump_path)
+}
+
+/// Compares the public classes of a project with binary compatibility
+/// against the dump of its compatible binary's type library, reporting every
+/// class and member that was removed or whose signature changed.
+///
+/// Members added since the binary was built are fine, as VB6 extends the
+/// interface rather than breaking it. `compatible_region` is where removed
+/// classes are reported in the project file, since they have no line of
+/// their own.
+pub fn check_binary_compatibility(
+    project_path: &Path,
+    project: &VB6Project,
+    compatible_path: &Path,
+    compatible_region: Option<Region>,
+) -> Vec<Diagnostic> {
+    let dump_path = type_library_dump_path(compatible_path);
+    if !dump_path.exists() {
+        return Vec::new();
+    }
+
+    let type_library = match TypeLibrary::load(&dump_path) {
+        Ok(type_library) => type_library,
+        Err(e) => {
+            return vec![Diagnostic::from_error(
+                DiagnosticKind::ParseError,
+                &dump_path,
+                e,
+            )]
+        }
+    };
+
+    let project_directory = project_directory(project_path);
+    let mut unreadable_classes = false;
+
+    let public_classes: Vec<(PathBuf, Interface)> = project
+        .classes
+        .iter()
+        .filter_map(|class| {
+            let class_path = join_parent_project_path(project_directory, &class.path.to_string());
+            let Ok(contents) = std::fs::read(&class_path) else {
+                unreadable_classes = true;
+                return None;
+            };
+
+            let is_exposed = attribute_value(&contents, "VB_Exposed")
+                .is_some_and(|value| value.eq_ignore_ascii_case("True"));
+            if !is_exposed {
+                return None;
+            }
+
+            let name =
+                attribute_value(&contents, "VB_Name").unwrap_or_else(|| class.name.to_string());
+            let interface = Interface::from_class(&name, &CodeModule::parse(&contents));
+
+            Some((class_path, interface))
+        })
+        .collect();
+
+    let mut diagnostics = Vec::new();
+
+    for compiled_class in &type_library.classes {
+        let current_class = public_classes
+            .iter()
+            .find(|(_, interface)| interface.name.eq_ignore_ascii_case(&compiled_class.name));
+
+        let Some((class_path, current_class)) = current_class else {
+            // A class that couldn't be read is reported as such, and may
+            // still be there.
+            if !unreadable_classes {
+                diagnostics.push(
+                    Diagnostic::new(
+                        DiagnosticKind::BrokenCompatibility,
+                        project_path,
+                        format!(
+                            "Public class '{}' in {} was removed or made private",
+                            compiled_class.name,
+                            display_name(compatible_path)
+                        ),
+                    )
+                    .with_region(compatible_region),
+                );
+            }
+            continue;
+        };
+
+        for compiled_member in &compiled_class.members {
+            match current_class.member(&compiled_member.name, compiled_member.kind) {
+                None => diagnostics.push(Diagnostic::new(
+                    DiagnosticKind::BrokenCompatibility,
+                    class_path,
+                    format!(
+                        "'{}.{}' was removed from the public interface of {}: {}",
+                        compiled_class.name,
+                        compiled_member.name,
+                        display_name(compatible_path),
+                        compiled_member.signature()
+                    ),
+                )),
+                Some(current_member) if !compiled_member.is_compatible_with(current_member) => {
+                    diagnostics.push(
+                        Diagnostic::new(
+                            DiagnosticKind::BrokenCompatibility,
+                            class_path,
+                            format!(
+                                "'{}.{}' changed from '{}' in {} to '{}'",
+                                compiled_class.name,
+                                compiled_member.name,
+                                compiled_member.signature(),
+                                display_name(compatible_path),
+                                current_member.signature()
+                            ),
+                        )
+                        .with_region(current_member.line.map(|line| Region { line, column: 1 })),
+                    )
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    diagnostics
+}
+
+fn display_name(path: &Path) -> String {
+    path.file_name()
+        .map(|file_name| file_name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removed_and_changed_members_break_compatibility() {
+        let directory =
+            std::env::temp_dir().join(format!("aspen-compatibility-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+
+        std::fs::write(
+            directory.join("Widget.cls"),
+            concat!(
+                "Attribute VB_Name = \"Widget\"\r\n",
+                "Attribute VB_Exposed = True\r\n",
+                "Public Function Render(ByVal Scale As Double) As String\r\n",
+                "End Function\r\n",
+                "Public Sub Added()\r\n",
+                "End Sub\r\n",
+            ),
+        )
+        .unwrap();
+
+        let compatible_path = directory.join("Widgets.dll");
+        std::fs::write(
+            type_library_dump_path(&compatible_path),
+            r#"{
+              "classes": [
+                { "name": "Widget", "members": [
+                  { "name": "Render", "kind": "function",
+                    "parameters": [{ "name": "Scale", "type": "Single", "byval": true }],
+                    "returns": "String" },
+                  { "name": "Reset", "kind": "sub" }
+                ] },
+                { "name": "Gadget", "members": [] }
+              ]
+            }"#,
+        )
+        .unwrap();
+
+        let project_text = "Type=OleDll\r\nClass=Widget; Widget.cls\r\n";
+        let project = VB6Project::parse("Widgets.vbp".to_owned(), project_text.as_bytes()).unwrap();
+
+        let messages: Vec<String> = check_binary_compatibility(
+            &directory.join("Widgets.vbp"),
+            &project,
+            &compatible_path,
+            None,
+        )
+        .into_iter()
+        .map(|diagnostic| diagnostic.message)
+        .collect();
+
+        assert_eq!(
+            messages,
+            [
+                "'Widget.Render' changed from 'Function Render(ByVal As Single) As String' in Widgets.dll to 'Function Render(ByVal As Double) As String'",
+                "'Widget.Reset' was removed from the public interface of Widgets.dll: Sub Reset()",
+                "Public class 'Gadget' in Widgets.dll was removed or made private",
+            ]
+        );
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
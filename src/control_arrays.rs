@@ -0,0 +1,205 @@
+use std::path::Path;
+
+use crate::code::{CodeModule, ProcedureKind};
+use crate::designer::{parse_designer, Control};
+use crate::diagnostic::{Diagnostic, DiagnosticKind, Region};
+
+/// Checks the control arrays on a form: that their members are all of one
+/// type with unique indices, that the indices have no gaps, and that the
+/// arrays' event handlers declare the `Index` of the member raising them.
+pub fn lint_control_arrays(path: &Path, contents: &[u8]) -> Vec<Diagnostic> {
+    let Ok(form) = parse_designer(contents) else {
+        return Vec::new();
+    };
+
+    let mut groups: Vec<Vec<&Control>> = Vec::new();
+    group_by_name(&form, &mut groups);
+
+    let mut diagnostics = Vec::new();
+    let report = |diagnostics: &mut Vec<Diagnostic>, kind, line, message: String| {
+        diagnostics.push(
+            Diagnostic::new(kind, path, message).with_region(Some(Region { line, column: 1 })),
+        );
+    };
+
+    let arrays: Vec<&Vec<&Control>> = groups
+        .iter()
+        .filter(|members| members.iter().any(|member| member.index().is_some()))
+        .collect();
+
+    for members in &arrays {
+        let first = members[0];
+
+        for member in members.iter().skip(1) {
+            if member.type_name != first.type_name {
+                report(
+                    &mut diagnostics,
+                    DiagnosticKind::InvalidControlArray,
+                    member.line,
+                    format!(
+                        "'{}' is a {}, but control array '{}' starts with a {}",
+                        member.display_name(),
+                        member.short_type_name(),
+                        first.name,
+                        first.short_type_name()
+                    ),
+                );
+            }
+        }
+
+        let mut indices: Vec<(i32, &Control)> = Vec::new();
+        for member in members.iter() {
+            let Some(index) = member.index() else {
+                report(
+                    &mut diagnostics,
+                    DiagnosticKind::InvalidControlArray,
+                    member.line,
+                    format!(
+                        "'{}' has no Index, but its name is used by control array '{}'",
+                        member.name, first.name
+                    ),
+                );
+                continue;
+            };
+
+            match indices.iter().find(|(other, _)| *other == index) {
+                Some((_, other)) => report(
+                    &mut diagnostics,
+                    DiagnosticKind::InvalidControlArray,
+                    member.line,
+                    format!(
+                        "'{}' has the same Index as the control on line {}",
+                        member.display_name(),
+                        other.line
+                    ),
+                ),
+                None => indices.push((index, member)),
+            }
+        }
+
+        indices.sort_by_key(|(index, _)| *index);
+        let missing: Vec<String> = indices
+            .windows(2)
+            .flat_map(|pair| pair[0].0 + 1..pair[1].0)
+            .map(|index| index.to_string())
+            .collect();
+        if !missing.is_empty() {
+            report(
+                &mut diagnostics,
+                DiagnosticKind::ControlArrayGap,
+                first.line,
+                format!(
+                    "Control array '{}' has no members with index {}",
+                    first.name,
+                    missing.join(", ")
+                ),
+            );
+        }
+    }
+
+    let code_module = CodeModule::parse(contents);
+
+    for procedure in &code_module.procedures {
+        if procedure.kind != ProcedureKind::Sub {
+            continue;
+        }
+
+        let Some((control_name, _)) = procedure.name.rsplit_once('_') else {
+            continue;
+        };
+        let Some(members) = arrays
+            .iter()
+            .find(|members| members[0].name.eq_ignore_ascii_case(control_name))
+        else {
+            continue;
+        };
+
+        let declares_index = procedure
+            .parameters
+            .first()
+            .is_some_and(|parameter| parameter.name.eq_ignore_ascii_case("Index"));
+        if !declares_index {
+            report(
+                &mut diagnostics,
+                DiagnosticKind::InvalidControlArray,
+                procedure.line,
+                format!(
+                    "'{}' handles an event of control array '{}', but does not declare Index As Integer first",
+                    procedure.name, members[0].name
+                ),
+            );
+        }
+    }
+
+    diagnostics
+}
+
+/// Groups the controls inside `container` by their name, ignoring case, in
+/// the order they first appear.
+fn group_by_name<'a>(container: &'a Control, groups: &mut Vec<Vec<&'a Control>>) {
+    for control in &container.children {
+        match groups
+            .iter_mut()
+            .find(|members| members[0].name.eq_ignore_ascii_case(&control.name))
+        {
+            Some(members) => members.push(control),
+            None => groups.push(vec![control]),
+        }
+
+        group_by_name(control, groups);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn control_array_members_must_agree() {
+        let source = concat!(
+            "VERSION 5.00\r\n",
+            "Begin VB.Form frmMain \r\n",
+            "   Begin VB.CommandButton cmdTool \r\n",
+            "      Index           =   0\r\n",
+            "   End\r\n",
+            "   Begin VB.CommandButton cmdTool \r\n",
+            "      Index           =   3\r\n",
+            "   End\r\n",
+            "   Begin VB.CheckBox cmdTool \r\n",
+            "      Index           =   3\r\n",
+            "   End\r\n",
+            "   Begin VB.TextBox txtName \r\n",
+            "   End\r\n",
+            "End\r\n",
+            "Attribute VB_Name = \"frmMain\"\r\n",
+            "Private Sub cmdTool_Click()\r\n",
+            "End Sub\r\n",
+            "Private Sub cmdTool_GotFocus(Index As Integer)\r\n",
+            "End Sub\r\n",
+            "Private Sub txtName_Change()\r\n",
+            "End Sub\r\n",
+        );
+
+        let messages: Vec<String> = lint_control_arrays(Path::new("Main.frm"), source.as_bytes())
+            .into_iter()
+            .map(|diagnostic| {
+                format!(
+                    "{}:{} {}",
+                    diagnostic.region.unwrap().line,
+                    diagnostic.kind.id(),
+                    diagnostic.message
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            messages,
+            [
+                "9:invalid-control-array 'cmdTool(3)' is a CheckBox, but control array 'cmdTool' starts with a CommandButton",
+                "9:invalid-control-array 'cmdTool(3)' has the same Index as the control on line 6",
+                "3:control-array-gap Control array 'cmdTool' has no members with index 1, 2",
+                "16:invalid-control-array 'cmdTool_Click' handles an event of control array 'cmdTool', but does not declare Index As Integer first",
+            ]
+        );
+    }
+}
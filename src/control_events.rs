@@ -0,0 +1,384 @@
+//! The events raised by forms and the intrinsic VB6 controls.
+
+/// A parameter of an event, with the type a handler must declare it as.
+pub type EventParameter = (&'static str, &'static str);
+
+const MOUSE: &[EventParameter] = &[
+    ("Button", "Integer"),
+    ("Shift", "Integer"),
+    ("X", "Single"),
+    ("Y", "Single"),
+];
+const KEY: &[EventParameter] = &[("KeyCode", "Integer"), ("Shift", "Integer")];
+
+/// Every event of the intrinsic controls, and the parameters it is raised
+/// with by all of them except those in [`OVERRIDDEN_EVENTS`].
+const EVENTS: &[(&str, &[EventParameter])] = &[
+    ("Activate", &[]),
+    ("Change", &[]),
+    ("Click", &[]),
+    ("DblClick", &[]),
+    ("Deactivate", &[]),
+    (
+        "DragDrop",
+        &[("Source", "Control"), ("X", "Single"), ("Y", "Single")],
+    ),
+    (
+        "DragOver",
+        &[
+            ("Source", "Control"),
+            ("X", "Single"),
+            ("Y", "Single"),
+            ("State", "Integer"),
+        ],
+    ),
+    ("DropDown", &[]),
+    ("Error", &[("DataErr", "Integer"), ("Response", "Integer")]),
+    ("GotFocus", &[]),
+    ("Initialize", &[]),
+    ("ItemCheck", &[("Item", "Integer")]),
+    ("KeyDown", KEY),
+    ("KeyPress", &[("KeyAscii", "Integer")]),
+    ("KeyUp", KEY),
+    ("LinkClose", &[]),
+    ("LinkError", &[("LinkErr", "Integer")]),
+    (
+        "LinkExecute",
+        &[("CmdStr", "String"), ("Cancel", "Integer")],
+    ),
+    ("LinkNotify", &[]),
+    ("LinkOpen", &[("Cancel", "Integer")]),
+    ("Load", &[]),
+    ("LostFocus", &[]),
+    ("MouseDown", MOUSE),
+    ("MouseMove", MOUSE),
+    ("MouseUp", MOUSE),
+    ("OLECompleteDrag", &[("Effect", "Long")]),
+    (
+        "OLEDragDrop",
+        &[
+            ("Data", "DataObject"),
+            ("Effect", "Long"),
+            ("Button", "Integer"),
+            ("Shift", "Integer"),
+            ("X", "Single"),
+            ("Y", "Single"),
+        ],
+    ),
+    (
+        "OLEDragOver",
+        &[
+            ("Data", "DataObject"),
+            ("Effect", "Long"),
+            ("Button", "Integer"),
+            ("Shift", "Integer"),
+            ("X", "Single"),
+            ("Y", "Single"),
+            ("State", "Integer"),
+        ],
+    ),
+    (
+        "OLEGiveFeedback",
+        &[("Effect", "Long"), ("DefaultCursors", "Boolean")],
+    ),
+    (
+        "OLESetData",
+        &[("Data", "DataObject"), ("DataFormat", "Integer")],
+    ),
+    (
+        "OLEStartDrag",
+        &[("Data", "DataObject"), ("AllowedEffects", "Long")],
+    ),
+    ("Paint", &[]),
+    ("PathChange", &[]),
+    ("PatternChange", &[]),
+    (
+        "QueryUnload",
+        &[("Cancel", "Integer"), ("UnloadMode", "Integer")],
+    ),
+    ("Reposition", &[]),
+    ("Resize", &[]),
+    ("Scroll", &[]),
+    ("Terminate", &[]),
+    ("Timer", &[]),
+    ("Unload", &[("Cancel", "Integer")]),
+    ("Validate", &[("Cancel", "Boolean")]),
+];
+
+/// Events a control raises with different parameters than other controls.
+const OVERRIDDEN_EVENTS: &[(&str, &str, &[EventParameter])] = &[(
+    "VB.Data",
+    "Validate",
+    &[("Action", "Integer"), ("Save", "Integer")],
+)];
+
+const OLE_DRAG: &[&str] = &[
+    "OLECompleteDrag",
+    "OLEDragDrop",
+    "OLEDragOver",
+    "OLEGiveFeedback",
+    "OLESetData",
+    "OLEStartDrag",
+];
+const LINK: &[&str] = &["LinkClose", "LinkError", "LinkNotify", "LinkOpen"];
+const FOCUS: &[&str] = &[
+    "GotFocus",
+    "KeyDown",
+    "KeyPress",
+    "KeyUp",
+    "LostFocus",
+    "Validate",
+];
+const DRAG: &[&str] = &["DragDrop", "DragOver"];
+const MOUSE_EVENTS: &[&str] = &["MouseDown", "MouseMove", "MouseUp"];
+
+/// The events of each intrinsic control, as groups of event names.
+const CONTROL_EVENTS: &[(&str, &[&[&str]])] = &[
+    (
+        "VB.Form",
+        &[
+            &[
+                "Activate",
+                "Click",
+                "DblClick",
+                "Deactivate",
+                "GotFocus",
+                "Initialize",
+                "KeyDown",
+                "KeyPress",
+                "KeyUp",
+                "LinkExecute",
+                "Load",
+                "LostFocus",
+                "Paint",
+                "QueryUnload",
+                "Resize",
+                "Terminate",
+                "Unload",
+            ],
+            LINK,
+            DRAG,
+            MOUSE_EVENTS,
+            OLE_DRAG,
+        ],
+    ),
+    (
+        "VB.MDIForm",
+        &[
+            &[
+                "Activate",
+                "Click",
+                "DblClick",
+                "Deactivate",
+                "Initialize",
+                "LinkExecute",
+                "Load",
+                "QueryUnload",
+                "Resize",
+                "Terminate",
+                "Unload",
+            ],
+            LINK,
+            DRAG,
+            MOUSE_EVENTS,
+            OLE_DRAG,
+        ],
+    ),
+    (
+        "VB.TextBox",
+        &[
+            &["Change", "Click", "DblClick"],
+            FOCUS,
+            LINK,
+            DRAG,
+            MOUSE_EVENTS,
+            OLE_DRAG,
+        ],
+    ),
+    (
+        "VB.Label",
+        &[
+            &["Change", "Click", "DblClick"],
+            LINK,
+            DRAG,
+            MOUSE_EVENTS,
+            OLE_DRAG,
+        ],
+    ),
+    (
+        "VB.CommandButton",
+        &[&["Click"], FOCUS, DRAG, MOUSE_EVENTS, OLE_DRAG],
+    ),
+    (
+        "VB.CheckBox",
+        &[&["Click"], FOCUS, DRAG, MOUSE_EVENTS, OLE_DRAG],
+    ),
+    (
+        "VB.OptionButton",
+        &[&["Click", "DblClick"], FOCUS, DRAG, MOUSE_EVENTS, OLE_DRAG],
+    ),
+    (
+        "VB.Frame",
+        &[&["Click", "DblClick"], DRAG, MOUSE_EVENTS, OLE_DRAG],
+    ),
+    (
+        "VB.ListBox",
+        &[
+            &["Click", "DblClick", "ItemCheck", "Scroll"],
+            FOCUS,
+            DRAG,
+            MOUSE_EVENTS,
+            OLE_DRAG,
+        ],
+    ),
+    (
+        "VB.ComboBox",
+        &[
+            &["Change", "Click", "DblClick", "DropDown", "Scroll"],
+            FOCUS,
+            DRAG,
+            OLE_DRAG,
+        ],
+    ),
+    ("VB.HScrollBar", &[&["Change", "Scroll"], FOCUS, DRAG]),
+    ("VB.VScrollBar", &[&["Change", "Scroll"], FOCUS, DRAG]),
+    ("VB.Timer", &[&["Timer"]]),
+    (
+        "VB.PictureBox",
+        &[
+            &["Change", "Click", "DblClick", "Paint", "Resize"],
+            FOCUS,
+            LINK,
+            DRAG,
+            MOUSE_EVENTS,
+            OLE_DRAG,
+        ],
+    ),
+    (
+        "VB.Image",
+        &[&["Click", "DblClick"], DRAG, MOUSE_EVENTS, OLE_DRAG],
+    ),
+    ("VB.Shape", &[]),
+    ("VB.Line", &[]),
+    (
+        "VB.DriveListBox",
+        &[&["Change", "Scroll"], FOCUS, DRAG, OLE_DRAG],
+    ),
+    (
+        "VB.DirListBox",
+        &[
+            &["Change", "Click", "Scroll"],
+            FOCUS,
+            DRAG,
+            MOUSE_EVENTS,
+            OLE_DRAG,
+        ],
+    ),
+    (
+        "VB.FileListBox",
+        &[
+            &["Click", "DblClick", "PathChange", "PatternChange", "Scroll"],
+            FOCUS,
+            DRAG,
+            MOUSE_EVENTS,
+            OLE_DRAG,
+        ],
+    ),
+    (
+        "VB.Data",
+        &[
+            &["Error", "Reposition", "Resize", "Validate"],
+            DRAG,
+            MOUSE_EVENTS,
+            OLE_DRAG,
+        ],
+    ),
+    ("VB.Menu", &[&["Click"]]),
+];
+
+/// The parameters `type_name` raises `event` with, or `None` when it is not
+/// one of its events.
+///
+/// Returns `None` for every event of controls that aren't intrinsic, whose
+/// events can't be known without their type library.
+pub fn event_parameters(type_name: &str, event: &str) -> Option<&'static [EventParameter]> {
+    let (_, groups) = CONTROL_EVENTS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(type_name))?;
+
+    let event = *groups
+        .iter()
+        .flat_map(|group| group.iter())
+        .find(|name| name.eq_ignore_ascii_case(event))?;
+
+    let overridden = OVERRIDDEN_EVENTS.iter().find(|(control, name, _)| {
+        control.eq_ignore_ascii_case(type_name) && name.eq_ignore_ascii_case(event)
+    });
+    if let Some((_, _, parameters)) = overridden {
+        return Some(parameters);
+    }
+
+    EVENTS
+        .iter()
+        .find(|(name, _)| *name == event)
+        .map(|(_, parameters)| *parameters)
+}
+
+/// Whether controls of `type_name` are intrinsic and so have known events.
+pub fn has_known_events(type_name: &str) -> bool {
+    CONTROL_EVENTS
+        .iter()
+        .any(|(name, _)| name.eq_ignore_ascii_case(type_name))
+}
+
+/// Checks if `name` is an event of any intrinsic control.
+pub fn is_event_name(name: &str) -> bool {
+    EVENTS
+        .iter()
+        .any(|(event, _)| event.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_looked_up_by_control_type() {
+        assert_eq!(event_parameters("VB.CommandButton", "Click"), Some(&[][..]));
+        assert_eq!(
+            event_parameters("vb.textbox", "keydown"),
+            Some(&[("KeyCode", "Integer"), ("Shift", "Integer")][..])
+        );
+        assert_eq!(
+            event_parameters("VB.Form", "Unload"),
+            Some(&[("Cancel", "Integer")][..])
+        );
+        assert_eq!(event_parameters("VB.Timer", "Timer"), Some(&[][..]));
+
+        // Validate is raised with different parameters by the Data control.
+        assert_eq!(
+            event_parameters("VB.TextBox", "Validate"),
+            Some(&[("Cancel", "Boolean")][..])
+        );
+        assert_eq!(
+            event_parameters("VB.Data", "Validate"),
+            Some(&[("Action", "Integer"), ("Save", "Integer")][..])
+        );
+
+        assert_eq!(event_parameters("VB.Timer", "Click"), None);
+        assert_eq!(event_parameters("VB.Label", "Bogus"), None);
+        assert_eq!(event_parameters("MSComctlLib.ListView", "Click"), None);
+    }
+
+    #[test]
+    fn only_intrinsic_controls_have_known_events() {
+        assert!(has_known_events("VB.Menu"));
+        assert!(has_known_events("vb.shape"));
+        assert!(!has_known_events("MSComctlLib.ListView"));
+        assert!(!has_known_events("Menu"));
+
+        assert!(is_event_name("mousemove"));
+        assert!(is_event_name("OLEStartDrag"));
+        assert!(!is_event_name("ItemClick"));
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+
+use crate::check::SourceFileKind;
+use crate::code::{identifier_key, Statement, Visibility};
+use crate::diagnostic::{Diagnostic, DiagnosticKind, Region};
+use crate::sources::{is_event_handler, SourceFile, Sources};
+
+/// Finds procedures, variables, constants, and `Declare` statements that
+/// nothing in the project uses.
+///
+/// Private declarations only need to be used in their own file, while public
+/// ones in modules can be used anywhere in the project. Public members of
+/// classes and forms are left alone, as they may be used from outside it.
+///
+/// Uses are found by name alone, so a declaration is only reported when its
+/// name doesn't appear anywhere it could be used from. Files shared with
+/// other projects are only analysed against this one, so `check` merges the
+/// findings of every project it checks.
+pub fn find_dead_code(sources: &Sources) -> Vec<Diagnostic> {
+    let usages: Vec<Usage> = sources.files.iter().map(Usage::of).collect();
+
+    let mut diagnostics = Vec::new();
+
+    for (file, usage) in sources.files.iter().zip(&usages) {
+        let is_module = file.kind == SourceFileKind::Module;
+
+        // Whether a declaration with `visibility` can only be used from its
+        // own file.
+        let is_private = |visibility| visibility == Visibility::Private;
+        let is_checked = |visibility| is_private(visibility) || is_module;
+
+        let is_used = |name: &str, visibility, own_procedure: Option<&str>| {
+            let key = identifier_key(name);
+            let excluded = own_procedure.map(identifier_key);
+
+            usage.count(&key, excluded.as_deref()) > 0
+                || (!is_private(visibility)
+                    && usages
+                        .iter()
+                        .filter(|other| !std::ptr::eq(*other, usage))
+                        .any(|other| other.count(&key, None) > 0))
+        };
+
+        let event_sources = file.event_sources();
+
+        for procedure in &file.code.procedures {
+            if !is_checked(procedure.visibility) || procedure.visibility == Visibility::Friend {
+                continue;
+            }
+            if is_event_handler(&procedure.name, &event_sources) {
+                continue;
+            }
+            if is_module && procedure.name.eq_ignore_ascii_case("Main") {
+                continue;
+            }
+
+            if !is_used(&procedure.name, procedure.visibility, Some(&procedure.name)) {
+                diagnostics.push(unused(
+                    DiagnosticKind::UnusedProcedure,
+                    file,
+                    procedure.line,
+                    format!(
+                        "{} '{}' is never called",
+                        describe(procedure.visibility, "procedure"),
+                        procedure.name
+                    ),
+                ));
+            }
+        }
+
+        // Module level declarations appear once where they are declared.
+        let is_declaration_used = |name: &str, visibility| {
+            let key = identifier_key(name);
+            usage.count(&key, None) > 1
+                || (!is_private(visibility)
+                    && usages
+                        .iter()
+                        .filter(|other| !std::ptr::eq(*other, usage))
+                        .any(|other| other.count(&key, None) > 0))
+        };
+
+        for variable in &file.code.variables {
+            if is_checked(variable.visibility)
+                && !is_declaration_used(&variable.name, variable.visibility)
+            {
+                diagnostics.push(unused(
+                    DiagnosticKind::UnusedVariable,
+                    file,
+                    variable.line,
+                    format!(
+                        "{} '{}' is never used",
+                        describe(variable.visibility, "variable"),
+                        variable.name
+                    ),
+                ));
+            }
+        }
+
+        for constant in &file.code.constants {
+            if is_checked(constant.visibility)
+                && !is_declaration_used(&constant.name, constant.visibility)
+            {
+                diagnostics.push(unused(
+                    DiagnosticKind::UnusedConstant,
+                    file,
+                    constant.line,
+                    format!(
+                        "{} '{}' is never used",
+                        describe(constant.visibility, "constant"),
+                        constant.name
+                    ),
+                ));
+            }
+        }
+
+        for declare in &file.code.declares {
+            if is_checked(declare.visibility)
+                && !is_declaration_used(&declare.name, declare.visibility)
+            {
+                diagnostics.push(unused(
+                    DiagnosticKind::UnusedDeclare,
+                    file,
+                    declare.line,
+                    format!(
+                        "{} '{}' from '{}' is never called",
+                        describe(declare.visibility, "Declare"),
+                        declare.name,
+                        declare.library
+                    ),
+                ));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// How often each identifier appears in a file, at module level and in each
+/// procedure.
+struct Usage {
+    module_level: HashMap<String, usize>,
+    procedures: Vec<(String, HashMap<String, usize>)>,
+}
+
+impl Usage {
+    fn of(file: &SourceFile) -> Usage {
+        Usage {
+            module_level: count_identifiers(&file.code.module_statements),
+            procedures: file
+                .code
+                .procedures
+                .iter()
+                .map(|procedure| {
+                    (
+                        identifier_key(&procedure.name),
+                        count_identifiers(&procedure.body),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// How often `key` appears, leaving out the bodies of procedures named
+    /// `excluded` so a procedure calling itself doesn't count as a use.
+    fn count(&self, key: &str, excluded: Option<&str>) -> usize {
+        let in_procedures: usize = self
+            .procedures
+            .iter()
+            .filter(|(name, _)| Some(name.as_str()) != excluded)
+            .filter_map(|(_, counts)| counts.get(key))
+            .sum();
+
+        self.module_level.get(key).copied().unwrap_or(0) + in_procedures
+    }
+}
+
+fn count_identifiers(statements: &[Statement]) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+
+    for token in statements.iter().flat_map(|statement| &statement.tokens) {
+        if token.starts_with(|character: char| character.is_alphabetic() || character == '_') {
+            *counts.entry(identifier_key(token)).or_insert(0) += 1;
+        }
+    }
+
+    counts
+}
+
+fn describe(visibility: Visibility, noun: &str) -> String {
+    let visibility = match visibility {
+        Visibility::Public => "Public",
+        Visibility::Private => "Private",
+        Visibility::Friend => "Friend",
+    };
+
+    format!("{} {}", visibility, noun)
+}
+
+fn unused(kind: DiagnosticKind, file: &SourceFile, line: usize, message: String) -> Diagnostic {
+    Diagnostic::new(kind, &file.path, message).with_region(Some(Region { line, column: 1 }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn source_file(kind: SourceFileKind, path: &str, contents: &str) -> SourceFile {
+        SourceFile::new(kind, Path::new(path), contents.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn unused_declarations_are_found() {
+        let module = source_file(
+            SourceFileKind::Module,
+            "Module1.bas",
+            concat!(
+                "Attribute VB_Name = \"Module1\"\r\n",
+                "Private Declare Function GetTickCount Lib \"kernel32\" () As Long\r\n",
+                "Private Const MAX_ITEMS = 10, MIN_ITEMS = MAX_ITEMS - 5\r\n",
+                "Public Const UNUSED_LIMIT = 1\r\n",
+                "Private m_Count As Long\r\n",
+                "Public Sub Main()\r\n",
+                "  Helper\r\n",
+                "End Sub\r\n",
+                "Private Sub Helper()\r\n",
+                "  m_Count = m_Count + 1\r\n",
+                "End Sub\r\n",
+                "Private Function Recurse(ByVal Depth As Long) As Long\r\n",
+                "  Recurse = Recurse(Depth - 1)\r\n",
+                "End Function\r\n",
+                "Public Sub Shared()\r\n",
+                "End Sub\r\n",
+                "Public Sub Forgotten()\r\n",
+                "End Sub\r\n",
+            ),
+        );
+        let form = source_file(
+            SourceFileKind::Form,
+            "frmMain.frm",
+            concat!(
+                "VERSION 5.00\r\n",
+                "Begin VB.Form frmMain \r\n",
+                "   Begin VB.CommandButton cmdOK \r\n",
+                "   End\r\n",
+                "End\r\n",
+                "Attribute VB_Name = \"frmMain\"\r\n",
+                "Private Sub Form_Load()\r\n",
+                "End Sub\r\n",
+                "Private Sub cmdOK_Click()\r\n",
+                "  Module1.Shared\r\n",
+                "End Sub\r\n",
+                "Public Sub Refresh()\r\n",
+                "End Sub\r\n",
+            ),
+        );
+
+        let sources = Sources {
+            project_path: None,
+            startup: None,
+            is_activex: false,
+            references: Vec::new(),
+            files: vec![module, form],
+        };
+
+        let messages: Vec<String> = find_dead_code(&sources)
+            .into_iter()
+            .map(|diagnostic| {
+                format!(
+                    "{}:{} {}",
+                    diagnostic.path.display(),
+                    diagnostic.region.unwrap().line,
+                    diagnostic.message
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            messages,
+            [
+                "Module1.bas:12 Private procedure 'Recurse' is never called",
+                "Module1.bas:17 Public procedure 'Forgotten' is never called",
+                "Module1.bas:3 Private constant 'MIN_ITEMS' is never used",
+                "Module1.bas:4 Public constant 'UNUSED_LIMIT' is never used",
+                "Module1.bas:2 Private Declare 'GetTickCount' from 'kernel32' is never called",
+            ]
+        );
+    }
+}
@@ -0,0 +1,456 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::code::{suffix_type, Declare, Parameter, ProcedureKind};
+use crate::info::InfoFormat;
+use crate::sources::Sources;
+
+/// Libraries that only exist as 32-bit DLLs.
+const THIRTY_TWO_BIT_LIBRARIES: [&str; 3] = ["msvbvm60", "msvbvm50", "vb40032"];
+
+/// Parameter names that conventionally hold handles.
+const HANDLE_NAMES: [&str; 14] = [
+    "handle",
+    "hbitmap",
+    "hdc",
+    "hfile",
+    "hfont",
+    "hicon",
+    "hinstance",
+    "hkey",
+    "hmenu",
+    "hmodule",
+    "hobject",
+    "hprocess",
+    "hthread",
+    "hwnd",
+];
+
+/// Entry points by library, keyed by the library's normalized name and the
+/// entry point's lower case name.
+type Libraries<'a> = BTreeMap<String, BTreeMap<String, EntryPoint<'a>>>;
+
+/// The names of the entry points a project uses from each library.
+type ProjectLibraries = Vec<(String, Vec<String>)>;
+
+/// A `Declare` statement and where it was found.
+struct Declaration<'a> {
+    project: Option<String>,
+    path: &'a Path,
+    declare: &'a Declare,
+}
+
+/// The declarations of one procedure exported by a library.
+struct EntryPoint<'a> {
+    /// The exported name, as it was first written.
+    name: String,
+    declarations: Vec<Declaration<'a>>,
+}
+
+impl EntryPoint<'_> {
+    /// Checks if the procedure is declared with different parameter or return
+    /// types in different places.
+    fn has_mismatched_signatures(&self) -> bool {
+        let first = signature_key(self.declarations[0].declare);
+
+        self.declarations
+            .iter()
+            .any(|declaration| signature_key(declaration.declare) != first)
+    }
+
+    /// Why the declarations can only work in a 32-bit process.
+    fn thirty_two_bit_only(&self, library: &str) -> Vec<String> {
+        let mut reasons = Vec::new();
+
+        if THIRTY_TWO_BIT_LIBRARIES.contains(&library) {
+            reasons.push("the VB6 runtime is only available to 32-bit processes".to_owned());
+        }
+
+        for declaration in &self.declarations {
+            for parameter in &declaration.declare.parameters {
+                if is_pointer_name(&parameter.name)
+                    && type_name(parameter).eq_ignore_ascii_case("Long")
+                {
+                    let reason = format!(
+                        "'{}' holds a handle or pointer but is declared As Long",
+                        parameter.name
+                    );
+                    if !reasons.contains(&reason) {
+                        reasons.push(reason);
+                    }
+                }
+            }
+        }
+
+        reasons
+    }
+}
+
+/// Lists every DLL procedure declared in `paths`, which may be `.vbp`
+/// projects or individual files, grouped by library and by project.
+///
+/// Returns `false` if any file couldn't be read.
+pub fn declares_subcommand(paths: &[PathBuf], format: InfoFormat) -> Result<bool> {
+    let (all_sources, errors) = Sources::load(paths);
+    for error in &errors {
+        eprintln!("{}", error);
+    }
+
+    // Windows looks up both libraries and entry points ignoring case.
+    let mut libraries: Libraries = BTreeMap::new();
+
+    for sources in &all_sources {
+        for file in &sources.files {
+            for declare in &file.code.declares {
+                libraries
+                    .entry(library_name(&declare.library))
+                    .or_default()
+                    .entry(declare.entry_point().to_ascii_lowercase())
+                    .or_insert_with(|| EntryPoint {
+                        name: declare.entry_point().to_owned(),
+                        declarations: Vec::new(),
+                    })
+                    .declarations
+                    .push(Declaration {
+                        project: sources.display_project(),
+                        path: &file.path,
+                        declare,
+                    });
+            }
+        }
+    }
+
+    match format {
+        InfoFormat::Text => print_text(&libraries, &all_sources),
+        InfoFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&declares_json(&libraries, &all_sources))?
+        ),
+        InfoFormat::Yaml => print!(
+            "{}",
+            serde_yaml::to_string(&declares_json(&libraries, &all_sources))?
+        ),
+    }
+
+    Ok(errors.is_empty())
+}
+
+fn print_text(libraries: &Libraries, all_sources: &[Sources]) {
+    for (library, entry_points) in libraries {
+        match entry_points.len() {
+            1 => println!("{} (1 entry point)", library),
+            count => println!("{} ({} entry points)", library, count),
+        }
+
+        for entry_point in entry_points.values() {
+            let mut line = format!("  {}", entry_point.name);
+            if entry_point.has_mismatched_signatures() {
+                line.push_str(" [mismatched signatures]");
+            }
+            println!("{}", line);
+
+            for reason in entry_point.thirty_two_bit_only(library) {
+                println!("    32-bit only: {}", reason);
+            }
+            for declaration in &entry_point.declarations {
+                println!(
+                    "    {}:{}: {}",
+                    declaration.path.display(),
+                    declaration.declare.line,
+                    declaration_text(declaration.declare)
+                );
+            }
+        }
+    }
+
+    for (project, project_libraries) in project_libraries(libraries, all_sources) {
+        println!();
+        println!("{}", project.unwrap_or_else(|| "Files".to_owned()));
+        for (library, entry_points) in project_libraries {
+            println!("  {}: {}", library, entry_points.join(", "));
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct DeclaresJson<'a> {
+    libraries: Vec<LibraryJson<'a>>,
+    projects: Vec<ProjectJson>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LibraryJson<'a> {
+    name: &'a str,
+    entry_points: Vec<EntryPointJson<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EntryPointJson<'a> {
+    name: &'a str,
+    mismatched_signatures: bool,
+    thirty_two_bit_only: Vec<String>,
+    declarations: Vec<DeclarationJson<'a>>,
+}
+
+#[derive(Serialize)]
+struct DeclarationJson<'a> {
+    project: &'a Option<String>,
+    path: String,
+    line: usize,
+    name: &'a str,
+    declaration: String,
+}
+
+#[derive(Serialize)]
+struct ProjectJson {
+    project: Option<String>,
+    libraries: Vec<ProjectLibraryJson>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProjectLibraryJson {
+    name: String,
+    entry_points: Vec<String>,
+}
+
+fn declares_json<'a>(libraries: &'a Libraries, all_sources: &[Sources]) -> DeclaresJson<'a> {
+    let libraries_json = libraries
+        .iter()
+        .map(|(library, entry_points)| LibraryJson {
+            name: library,
+            entry_points: entry_points
+                .values()
+                .map(|entry_point| EntryPointJson {
+                    name: &entry_point.name,
+                    mismatched_signatures: entry_point.has_mismatched_signatures(),
+                    thirty_two_bit_only: entry_point.thirty_two_bit_only(library),
+                    declarations: entry_point
+                        .declarations
+                        .iter()
+                        .map(|declaration| DeclarationJson {
+                            project: &declaration.project,
+                            path: declaration.path.display().to_string(),
+                            line: declaration.declare.line,
+                            name: &declaration.declare.name,
+                            declaration: declaration_text(declaration.declare),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        })
+        .collect();
+
+    let projects_json = project_libraries(libraries, all_sources)
+        .into_iter()
+        .map(|(project, project_libraries)| ProjectJson {
+            project,
+            libraries: project_libraries
+                .into_iter()
+                .map(|(name, entry_points)| ProjectLibraryJson { name, entry_points })
+                .collect(),
+        })
+        .collect();
+
+    DeclaresJson {
+        libraries: libraries_json,
+        projects: projects_json,
+    }
+}
+
+/// The libraries each project uses, with the entry points it uses from each.
+fn project_libraries(
+    libraries: &Libraries,
+    all_sources: &[Sources],
+) -> Vec<(Option<String>, ProjectLibraries)> {
+    all_sources
+        .iter()
+        .map(|sources| {
+            let project = sources.display_project();
+            let used = libraries
+                .iter()
+                .filter_map(|(library, entry_points)| {
+                    let names: Vec<String> = entry_points
+                        .values()
+                        .filter(|entry_point| {
+                            entry_point
+                                .declarations
+                                .iter()
+                                .any(|declaration| declaration.project == project)
+                        })
+                        .map(|entry_point| entry_point.name.clone())
+                        .collect();
+
+                    (!names.is_empty()).then(|| (library.clone(), names))
+                })
+                .collect();
+
+            (project, used)
+        })
+        .collect()
+}
+
+/// The library's name in lower case without a `.dll` extension, as
+/// `"User32.dll"` and `"user32"` load the same library.
+fn library_name(library: &str) -> String {
+    let library = library.trim().to_ascii_lowercase();
+
+    match library.strip_suffix(".dll") {
+        Some(stem) => stem.to_owned(),
+        None => library,
+    }
+}
+
+fn type_name(parameter: &Parameter) -> &str {
+    parameter
+        .type_name
+        .as_deref()
+        .or_else(|| suffix_type(&parameter.name))
+        .unwrap_or("Variant")
+}
+
+/// The parameter and return types of a declaration, which must agree
+/// wherever the same procedure is declared.
+fn signature_key(declare: &Declare) -> String {
+    let parameters: Vec<String> = declare
+        .parameters
+        .iter()
+        .map(|parameter| {
+            format!(
+                "{}{}{}",
+                if parameter.by_val { "byval " } else { "" },
+                type_name(parameter).to_ascii_lowercase(),
+                if parameter.is_array { "()" } else { "" }
+            )
+        })
+        .collect();
+
+    let return_type = match declare.kind {
+        ProcedureKind::Function => declare
+            .return_type
+            .as_deref()
+            .or_else(|| suffix_type(&declare.name))
+            .unwrap_or("Variant")
+            .to_ascii_lowercase(),
+        _ => String::new(),
+    };
+
+    format!("({}) {}", parameters.join(", "), return_type)
+}
+
+/// The declaration as VB6 would write it.
+fn declaration_text(declare: &Declare) -> String {
+    let parameters: Vec<String> = declare
+        .parameters
+        .iter()
+        .map(|parameter| {
+            let mut text = String::new();
+            if parameter.optional {
+                text.push_str("Optional ");
+            }
+            if parameter.by_val {
+                text.push_str("ByVal ");
+            }
+            if parameter.param_array {
+                text.push_str("ParamArray ");
+            }
+            text.push_str(&parameter.name);
+            if parameter.is_array {
+                text.push_str("()");
+            }
+            if let Some(type_name) = &parameter.type_name {
+                text.push_str(" As ");
+                text.push_str(type_name);
+            }
+            text
+        })
+        .collect();
+
+    let mut text = format!(
+        "Declare {} {} Lib \"{}\"",
+        if declare.kind == ProcedureKind::Function {
+            "Function"
+        } else {
+            "Sub"
+        },
+        declare.name,
+        declare.library
+    );
+    if let Some(alias) = &declare.alias {
+        text.push_str(&format!(" Alias \"{}\"", alias));
+    }
+    text.push_str(&format!(" ({})", parameters.join(", ")));
+    if let Some(return_type) = &declare.return_type {
+        text.push_str(" As ");
+        text.push_str(return_type);
+    }
+
+    text
+}
+
+/// Checks if a parameter's name marks it as a handle or pointer, such as
+/// `hWnd`, `lpBuffer`, or `pidlPtr`, which are 64 bits in a 64-bit process.
+fn is_pointer_name(name: &str) -> bool {
+    let name = name.trim_end_matches(['%', '&', '$', '!', '#', '@']);
+    let characters: Vec<char> = name.chars().collect();
+
+    let is_prefixed = |prefix: &str| {
+        name.starts_with(prefix)
+            && characters
+                .get(prefix.len())
+                .is_some_and(char::is_ascii_uppercase)
+    };
+
+    HANDLE_NAMES.contains(&name.to_ascii_lowercase().as_str())
+        || is_prefixed("h")
+        || is_prefixed("lp")
+        || name.ends_with("Ptr")
+        || name.ends_with("Handle")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code::CodeModule;
+
+    fn declaration(code: &CodeModule) -> Declaration<'_> {
+        Declaration {
+            project: None,
+            path: Path::new("Module1.bas"),
+            declare: &code.declares[0],
+        }
+    }
+
+    #[test]
+    fn declarations_are_compared_by_type() {
+        let first = CodeModule::parse(
+            b"Declare Function SendMessage Lib \"User32.dll\" Alias \"SendMessageA\" (ByVal hWnd As Long, ByVal wMsg As Long, lParam As Any) As Long\r\n",
+        );
+        let second = CodeModule::parse(
+            b"Private Declare Function SendMessageA Lib \"user32\" (ByVal Window&, ByVal Message As Long, Param As Any) As Long\r\n",
+        );
+        let third = CodeModule::parse(
+            b"Private Declare Function SendMessageA Lib \"user32\" (ByVal Window As Long, ByVal Message As Long, ByVal Param As Long) As Long\r\n",
+        );
+
+        assert_eq!(library_name(&first.declares[0].library), "user32");
+
+        let mut entry_point = EntryPoint {
+            name: "SendMessageA".to_owned(),
+            declarations: vec![declaration(&first), declaration(&second)],
+        };
+        assert!(!entry_point.has_mismatched_signatures());
+        assert_eq!(
+            entry_point.thirty_two_bit_only("user32"),
+            ["'hWnd' holds a handle or pointer but is declared As Long"]
+        );
+
+        entry_point.declarations.push(declaration(&third));
+        assert!(entry_point.has_mismatched_signatures());
+    }
+}
@@ -0,0 +1,344 @@
+//! The control tree described at the top of a form.
+//!
+//! vb6parse reads these too, but drops the `Index` of control array
+//! members, the controls inside picture boxes, and the type of custom
+//! controls, all of which matter when looking at a form's layout. This reads
+//! the `Begin ... End` blocks directly instead, keeping every property as it
+//! was written.
+
+use anyhow::{anyhow, Result};
+
+/// A control, or the form itself, with the controls inside it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Control {
+    /// The fully qualified type, such as `VB.CommandButton`.
+    pub type_name: String,
+    pub name: String,
+    /// The 1-based line of the control's `Begin` line.
+    pub line: usize,
+    /// Properties in the order they were written, with quotes removed from
+    /// strings. Property groups such as `Font` are not included.
+    pub properties: Vec<(String, String)>,
+    pub children: Vec<Control>,
+}
+
+impl Control {
+    /// The type without the `VB.` namespace of intrinsic controls.
+    pub fn short_type_name(&self) -> &str {
+        self.type_name
+            .strip_prefix("VB.")
+            .unwrap_or(&self.type_name)
+    }
+
+    /// Looks up a property by name, ignoring case.
+    pub fn property(&self, name: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn integer_property(&self, name: &str) -> Option<i32> {
+        self.property(name)?.trim().parse().ok()
+    }
+
+    /// The control's index within its control array, if it is in one.
+    pub fn index(&self) -> Option<i32> {
+        self.integer_property("Index")
+    }
+
+    /// The control's name, with its index when it's in a control array.
+    pub fn display_name(&self) -> String {
+        match self.index() {
+            Some(index) => format!("{}({})", self.name, index),
+            None => self.name.clone(),
+        }
+    }
+
+    pub fn is_form(&self) -> bool {
+        matches!(self.type_name.as_str(), "VB.Form" | "VB.MDIForm")
+    }
+
+    /// The position and size of the control relative to its container, in
+    /// the container's units. Forms use their client area in twips, which
+    /// starts at the origin.
+    pub fn bounds(&self) -> Option<Bounds> {
+        if self.is_form() {
+            return Some(Bounds {
+                left: 0,
+                top: 0,
+                width: self.integer_property("ClientWidth")?,
+                height: self.integer_property("ClientHeight")?,
+            });
+        }
+
+        Some(Bounds {
+            left: self.integer_property("Left")?,
+            top: self.integer_property("Top")?,
+            width: self.integer_property("Width")?,
+            height: self.integer_property("Height")?,
+        })
+    }
+
+    /// The units the control's children are positioned in, given the units
+    /// of the control's own position, or `None` when they can't be known.
+    pub fn child_units(&self, units: Option<Units>) -> Option<Units> {
+        match self.integer_property("ScaleMode") {
+            Some(scale_mode) => Units::from_scale_mode(scale_mode),
+            // Forms and picture boxes default to twips, while frames use the
+            // units of the container they are in.
+            None if self.is_form() || self.type_name == "VB.PictureBox" => Some(Units::TWIPS),
+            None => units,
+        }
+    }
+}
+
+/// The unit a container's children are positioned in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Units {
+    pub twips_per_unit: f64,
+}
+
+impl Units {
+    pub const TWIPS: Units = Units {
+        twips_per_unit: 1.0,
+    };
+
+    /// The units of a `ScaleMode` other than user defined or characters,
+    /// which differ horizontally and vertically.
+    fn from_scale_mode(scale_mode: i32) -> Option<Units> {
+        let twips_per_unit = match scale_mode {
+            1 => 1.0,
+            2 => 20.0,
+            3 => 15.0,
+            5 => 1440.0,
+            6 => 1440.0 / 25.4,
+            7 => 1440.0 / 2.54,
+            _ => return None,
+        };
+
+        Some(Units { twips_per_unit })
+    }
+}
+
+/// A rectangle in the units of a container.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub left: i32,
+    pub top: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// Reads the control tree from the source of a form.
+pub fn parse_designer(source: &[u8]) -> Result<Control> {
+    let source = String::from_utf8_lossy(source);
+    let mut lines = source
+        .lines()
+        .enumerate()
+        .map(|(line_index, line)| (line_index + 1, line.trim()));
+
+    for (line, text) in lines.by_ref() {
+        if let Some((type_name, name)) = parse_begin(text) {
+            return parse_block(type_name, name, line, &mut lines);
+        }
+    }
+
+    Err(anyhow!("No 'Begin' block describing the form was found"))
+}
+
+fn parse_begin(text: &str) -> Option<(String, String)> {
+    let mut words = text.split_whitespace();
+
+    if !words.next()?.eq_ignore_ascii_case("Begin") {
+        return None;
+    }
+
+    Some((words.next()?.to_owned(), words.next()?.to_owned()))
+}
+
+fn parse_block<'a>(
+    type_name: String,
+    name: String,
+    line: usize,
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+) -> Result<Control> {
+    let mut control = Control {
+        type_name,
+        name,
+        line,
+        properties: Vec::new(),
+        children: Vec::new(),
+    };
+    let mut property_group_depth = 0;
+
+    while let Some((line, text)) = lines.next() {
+        let first_word = text.split_whitespace().next().unwrap_or_default();
+
+        if first_word.eq_ignore_ascii_case("BeginProperty") {
+            property_group_depth += 1;
+        } else if first_word.eq_ignore_ascii_case("EndProperty") {
+            property_group_depth -= 1;
+        } else if property_group_depth > 0 {
+            continue;
+        } else if first_word.eq_ignore_ascii_case("End") {
+            return Ok(control);
+        } else if let Some((type_name, name)) = parse_begin(text) {
+            control
+                .children
+                .push(parse_block(type_name, name, line, lines)?);
+        } else if let Some((key, value)) = text.split_once('=') {
+            control
+                .properties
+                .push((key.trim().to_owned(), parse_value(value)));
+        }
+    }
+
+    Err(anyhow!(
+        "The 'Begin' block for '{}' on line {} has no 'End'",
+        control.name,
+        control.line
+    ))
+}
+
+/// Unquotes a property value and removes any trailing `'` comment.
+fn parse_value(value: &str) -> String {
+    let value = value.trim();
+
+    if let Some(quoted) = value.strip_prefix('"') {
+        let mut unquoted = String::new();
+        let mut characters = quoted.chars().peekable();
+
+        while let Some(character) = characters.next() {
+            match character {
+                '"' if characters.peek() == Some(&'"') => {
+                    unquoted.push('"');
+                    characters.next();
+                }
+                '"' => break,
+                character => unquoted.push(character),
+            }
+        }
+
+        return unquoted;
+    }
+
+    match value.find('\'') {
+        Some(comment_start) => value[..comment_start].trim_end().to_owned(),
+        None => value.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_controls_keep_their_properties() {
+        let source = concat!(
+            "VERSION 5.00\r\n",
+            "Begin VB.Form frmMain \r\n",
+            "   BorderStyle     =   1  'Fixed Single\r\n",
+            "   Caption         =   \"Say \"\"Hi\"\"\"\r\n",
+            "   ClientHeight    =   3000\r\n",
+            "   ClientWidth     =   4000\r\n",
+            "   BeginProperty Font \r\n",
+            "      Name            =   \"MS Sans Serif\"\r\n",
+            "   EndProperty\r\n",
+            "   Begin VB.PictureBox picHolder \r\n",
+            "      Height          =   1000\r\n",
+            "      Left            =   100\r\n",
+            "      Top             =   100\r\n",
+            "      Width           =   2000\r\n",
+            "      Begin MSComctlLib.ListView lvwItems \r\n",
+            "         Index           =   2\r\n",
+            "      End\r\n",
+            "   End\r\n",
+            "End\r\n",
+            "Attribute VB_Name = \"frmMain\"\r\n",
+        );
+
+        let form = parse_designer(source.as_bytes()).unwrap();
+
+        assert_eq!(form.short_type_name(), "Form");
+        assert_eq!(form.property("caption"), Some("Say \"Hi\""));
+        assert_eq!(form.property("BorderStyle"), Some("1"));
+        assert_eq!(form.property("Name"), None);
+        assert_eq!(
+            form.bounds(),
+            Some(Bounds {
+                left: 0,
+                top: 0,
+                width: 4000,
+                height: 3000
+            })
+        );
+
+        let picture_box = &form.children[0];
+        assert_eq!(picture_box.line, 10);
+        assert_eq!(picture_box.bounds().unwrap().width, 2000);
+
+        let list_view = &picture_box.children[0];
+        assert_eq!(list_view.short_type_name(), "MSComctlLib.ListView");
+        assert_eq!(list_view.display_name(), "lvwItems(2)");
+    }
+
+    #[test]
+    fn control_arrays_inside_nested_containers() {
+        let source = concat!(
+            "Begin VB.Form frmOrders \r\n",
+            "   Begin VB.Frame fraActions \r\n",
+            "      Begin VB.PictureBox picButtons \r\n",
+            "         Begin VB.CommandButton cmdAction \r\n",
+            "            Index           =   0\r\n",
+            "         End\r\n",
+            "         Begin VB.CommandButton cmdAction \r\n",
+            "            Caption         =   \"Cancel\"   'Closes the form\r\n",
+            "            Index           =   1\r\n",
+            "         End\r\n",
+            "      End\r\n",
+            "      Begin VB.Label lblStatus \r\n",
+            "      End\r\n",
+            "   End\r\n",
+            "End\r\n",
+        );
+
+        let form = parse_designer(source.as_bytes()).unwrap();
+
+        let frame = &form.children[0];
+        assert_eq!(form.children.len(), 1);
+        assert_eq!(
+            frame
+                .children
+                .iter()
+                .map(Control::display_name)
+                .collect::<Vec<_>>(),
+            ["picButtons", "lblStatus"]
+        );
+
+        let buttons = &frame.children[0].children;
+        assert_eq!(
+            buttons
+                .iter()
+                .map(|button| (button.display_name(), button.line))
+                .collect::<Vec<_>>(),
+            [
+                ("cmdAction(0)".to_owned(), 4),
+                ("cmdAction(1)".to_owned(), 7)
+            ]
+        );
+        assert_eq!(buttons[1].property("Caption"), Some("Cancel"));
+        assert_eq!(frame.children[1].index(), None);
+    }
+
+    #[test]
+    fn unterminated_blocks_are_errors() {
+        let source = "Begin VB.Form frmMain\r\n   Begin VB.Frame fraOptions\r\n   End\r\n";
+
+        assert_eq!(
+            parse_designer(source.as_bytes()).unwrap_err().to_string(),
+            "The 'Begin' block for 'frmMain' on line 1 has no 'End'"
+        );
+        assert!(parse_designer(b"VERSION 5.00\r\n").is_err());
+    }
+}
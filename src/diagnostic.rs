@@ -0,0 +1,362 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use anyhow::Error;
+use clap::builder::PossibleValue;
+use clap::ValueEnum;
+use vb6parse::errors::VB6Error;
+
+/// How diagnostics are written to stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Human readable text.
+    #[default]
+    Text,
+    /// A SARIF 2.1.0 log for code scanning tools.
+    Sarif,
+    /// A JUnit XML report for test result dashboards.
+    Junit,
+    /// The findings of each project as JSON, for scripts.
+    Json,
+}
+
+impl ValueEnum for OutputFormat {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[
+            OutputFormat::Text,
+            OutputFormat::Sarif,
+            OutputFormat::Junit,
+            OutputFormat::Json,
+        ]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            OutputFormat::Text => PossibleValue::new("text").help("human readable text"),
+            OutputFormat::Sarif => {
+                PossibleValue::new("sarif").help("SARIF 2.1.0 for code scanning tools")
+            }
+            OutputFormat::Junit => {
+                PossibleValue::new("junit").help("JUnit XML for test result dashboards")
+            }
+            OutputFormat::Json => {
+                PossibleValue::new("json").help("the findings of each project as JSON")
+            }
+        })
+    }
+}
+
+/// The category of a problem aspen found.
+///
+/// Each kind has a stable rule id used by machine readable output formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DiagnosticKind {
+    MissingFile,
+    MissingSubProjectReference,
+    UnreadableFile,
+    ParseError,
+    UnsupportedFile,
+    NonEnglishEncoding,
+    UnusedSuppression,
+    InvalidStartup,
+    InvalidOutputPath,
+    ProjectTypeMismatch,
+    InvalidVersion,
+    MissingCompatibleBinary,
+    BrokenCompatibility,
+    ControlOutsideContainer,
+    OverlappingControls,
+    InvalidTabOrder,
+    DuplicateAccessKey,
+    ZeroSizeControl,
+    OrphanedEventHandler,
+    EventHandlerMismatch,
+    InvalidControlArray,
+    ControlArrayGap,
+    UnusedProcedure,
+    UnusedVariable,
+    UnusedConstant,
+    UnusedDeclare,
+    UnreachableProcedure,
+    ImplicitVariant,
+    UndeclaredIdentifier,
+    UnknownMember,
+    UndescribedReference,
+    MissingErrorLabel,
+    ErrorHandlerFallThrough,
+    SwallowedErrors,
+    UnhandledEntryPoint,
+}
+
+impl DiagnosticKind {
+    pub const ALL: [DiagnosticKind; 35] = [
+        DiagnosticKind::MissingFile,
+        DiagnosticKind::MissingSubProjectReference,
+        DiagnosticKind::UnreadableFile,
+        DiagnosticKind::ParseError,
+        DiagnosticKind::UnsupportedFile,
+        DiagnosticKind::NonEnglishEncoding,
+        DiagnosticKind::UnusedSuppression,
+        DiagnosticKind::InvalidStartup,
+        DiagnosticKind::InvalidOutputPath,
+        DiagnosticKind::ProjectTypeMismatch,
+        DiagnosticKind::InvalidVersion,
+        DiagnosticKind::MissingCompatibleBinary,
+        DiagnosticKind::BrokenCompatibility,
+        DiagnosticKind::ControlOutsideContainer,
+        DiagnosticKind::OverlappingControls,
+        DiagnosticKind::InvalidTabOrder,
+        DiagnosticKind::DuplicateAccessKey,
+        DiagnosticKind::ZeroSizeControl,
+        DiagnosticKind::OrphanedEventHandler,
+        DiagnosticKind::EventHandlerMismatch,
+        DiagnosticKind::InvalidControlArray,
+        DiagnosticKind::ControlArrayGap,
+        DiagnosticKind::UnusedProcedure,
+        DiagnosticKind::UnusedVariable,
+        DiagnosticKind::UnusedConstant,
+        DiagnosticKind::UnusedDeclare,
+        DiagnosticKind::UnreachableProcedure,
+        DiagnosticKind::ImplicitVariant,
+        DiagnosticKind::UndeclaredIdentifier,
+        DiagnosticKind::UnknownMember,
+        DiagnosticKind::UndescribedReference,
+        DiagnosticKind::MissingErrorLabel,
+        DiagnosticKind::ErrorHandlerFallThrough,
+        DiagnosticKind::SwallowedErrors,
+        DiagnosticKind::UnhandledEntryPoint,
+    ];
+
+    /// The stable, kebab-case identifier of the rule.
+    pub fn id(self) -> &'static str {
+        match self {
+            DiagnosticKind::MissingFile => "missing-file",
+            DiagnosticKind::MissingSubProjectReference => "missing-sub-project-reference",
+            DiagnosticKind::UnreadableFile => "unreadable-file",
+            DiagnosticKind::ParseError => "parse-error",
+            DiagnosticKind::UnsupportedFile => "unsupported-file",
+            DiagnosticKind::NonEnglishEncoding => "non-english-encoding",
+            DiagnosticKind::UnusedSuppression => "unused-suppression",
+            DiagnosticKind::InvalidStartup => "invalid-startup",
+            DiagnosticKind::InvalidOutputPath => "invalid-output-path",
+            DiagnosticKind::ProjectTypeMismatch => "project-type-mismatch",
+            DiagnosticKind::InvalidVersion => "invalid-version",
+            DiagnosticKind::MissingCompatibleBinary => "missing-compatible-binary",
+            DiagnosticKind::BrokenCompatibility => "broken-compatibility",
+            DiagnosticKind::ControlOutsideContainer => "control-outside-container",
+            DiagnosticKind::OverlappingControls => "overlapping-controls",
+            DiagnosticKind::InvalidTabOrder => "invalid-tab-order",
+            DiagnosticKind::DuplicateAccessKey => "duplicate-access-key",
+            DiagnosticKind::ZeroSizeControl => "zero-size-control",
+            DiagnosticKind::OrphanedEventHandler => "orphaned-event-handler",
+            DiagnosticKind::EventHandlerMismatch => "event-handler-mismatch",
+            DiagnosticKind::InvalidControlArray => "invalid-control-array",
+            DiagnosticKind::ControlArrayGap => "control-array-gap",
+            DiagnosticKind::UnusedProcedure => "unused-procedure",
+            DiagnosticKind::UnusedVariable => "unused-variable",
+            DiagnosticKind::UnusedConstant => "unused-constant",
+            DiagnosticKind::UnusedDeclare => "unused-declare",
+            DiagnosticKind::UnreachableProcedure => "unreachable-procedure",
+            DiagnosticKind::ImplicitVariant => "implicit-variant",
+            DiagnosticKind::UndeclaredIdentifier => "undeclared-identifier",
+            DiagnosticKind::UnknownMember => "unknown-member",
+            DiagnosticKind::UndescribedReference => "undescribed-reference",
+            DiagnosticKind::MissingErrorLabel => "missing-error-label",
+            DiagnosticKind::ErrorHandlerFallThrough => "error-handler-fall-through",
+            DiagnosticKind::SwallowedErrors => "swallowed-errors",
+            DiagnosticKind::UnhandledEntryPoint => "unhandled-entry-point",
+        }
+    }
+
+    /// A one sentence description of the rule.
+    pub fn description(self) -> &'static str {
+        match self {
+            DiagnosticKind::MissingFile => {
+                "A file listed in the project, or given on the command line, does not exist."
+            }
+            DiagnosticKind::MissingSubProjectReference => {
+                "A sub-project referenced by the project does not exist."
+            }
+            DiagnosticKind::UnreadableFile => "A file exists but could not be read.",
+            DiagnosticKind::ParseError => "A file could not be parsed.",
+            DiagnosticKind::UnsupportedFile => {
+                "A file given to check is not a .vbp project or a .bas, .cls, or .frm file."
+            }
+            DiagnosticKind::NonEnglishEncoding => {
+                "A file is likely saved in a non-English character set and was not processed."
+            }
+            DiagnosticKind::UnusedSuppression => {
+                "An aspen:ignore comment does not suppress any findings."
+            }
+            DiagnosticKind::InvalidStartup => {
+                "The project's startup object does not exist, or a Standard EXE has none."
+            }
+            DiagnosticKind::InvalidOutputPath => {
+                "ExeName32 or Path32 is not a usable file name or path for the build output."
+            }
+            DiagnosticKind::ProjectTypeMismatch => {
+                "The project's Type does not match its members, such as an ActiveX DLL without public classes."
+            }
+            DiagnosticKind::InvalidVersion => {
+                "MajorVer, MinorVer, and RevisionVer are out of range, duplicated, or incomplete."
+            }
+            DiagnosticKind::MissingCompatibleBinary => {
+                "Version compatibility is enabled but the CompatibleEXE32 binary is not set, does not exist, or is outside the repository."
+            }
+            DiagnosticKind::BrokenCompatibility => {
+                "A public class or member in the binary compatible type library was removed or its signature changed."
+            }
+            DiagnosticKind::ControlOutsideContainer => {
+                "A control on a form is partly or wholly outside its container's client area."
+            }
+            DiagnosticKind::OverlappingControls => {
+                "Two visible controls in the same container overlap."
+            }
+            DiagnosticKind::InvalidTabOrder => {
+                "Controls on a form share a TabIndex, or the tab order skips values."
+            }
+            DiagnosticKind::DuplicateAccessKey => {
+                "Two controls on a form, or two items on the same menu, use the same & access key."
+            }
+            DiagnosticKind::ZeroSizeControl => {
+                "A control on a form has no width or no height."
+            }
+            DiagnosticKind::OrphanedEventHandler => {
+                "A procedure named like an event handler does not handle an event of any control on the form."
+            }
+            DiagnosticKind::EventHandlerMismatch => {
+                "An event handler's parameters do not match its event, such as an Index parameter for a control that is not in a control array."
+            }
+            DiagnosticKind::InvalidControlArray => {
+                "The members of a control array differ in type or share an Index, or one of its event handlers does not declare an Index."
+            }
+            DiagnosticKind::ControlArrayGap => {
+                "The indices of a control array skip values."
+            }
+            DiagnosticKind::UnusedProcedure => {
+                "A private procedure, or a public procedure in a module, is never called in the project."
+            }
+            DiagnosticKind::UnusedVariable => {
+                "A private variable, or a public variable in a module, is never used in the project."
+            }
+            DiagnosticKind::UnusedConstant => {
+                "A private constant, or a public constant in a module, is never used in the project."
+            }
+            DiagnosticKind::UnusedDeclare => {
+                "A private Declare, or a public Declare in a module, is never called in the project."
+            }
+            DiagnosticKind::UnreachableProcedure => {
+                "A procedure is not called from the project's startup object, an event handler, or a public member of an ActiveX component, even indirectly."
+            }
+            DiagnosticKind::ImplicitVariant => {
+                "A variable, parameter, or function return value is a Variant because it has no As clause, type suffix, or DefType default."
+            }
+            DiagnosticKind::UndeclaredIdentifier => {
+                "A file without Option Explicit uses a name that is not declared anywhere it can see, which VB6 turns into a new Variant."
+            }
+            DiagnosticKind::UnknownMember => {
+                "Code uses a property or method that the referenced COM library's class or control does not have."
+            }
+            DiagnosticKind::UndescribedReference => {
+                "A COM library the project references has no type library description, so code using it is only partly checked."
+            }
+            DiagnosticKind::MissingErrorLabel => {
+                "An On Error GoTo statement names a label that is not defined in the procedure, which VB6 refuses to compile."
+            }
+            DiagnosticKind::ErrorHandlerFallThrough => {
+                "An error handler has no Exit Sub, Exit Function or Exit Property before it, so it also runs when no error occurred."
+            }
+            DiagnosticKind::SwallowedErrors => {
+                "On Error Resume Next, or a handler that resumes with the next statement, ignores errors without ever checking Err."
+            }
+            DiagnosticKind::UnhandledEntryPoint => {
+                "An event handler or public class member has no On Error statement, so an error in it ends the program or surprises its caller."
+            }
+        }
+    }
+}
+
+/// A 1-based line and column within a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Region {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A single problem found in a file.
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    /// The file the problem is in. For a missing member this is the project
+    /// that lists it, not the missing file itself.
+    pub path: PathBuf,
+    pub message: String,
+    pub region: Option<Region>,
+    /// The project member the problem is about, when it's reported against
+    /// the project rather than the member itself.
+    pub member_path: Option<PathBuf>,
+    /// The underlying error, kept so it can be rendered in full for people.
+    pub error: Option<Error>,
+}
+
+impl Diagnostic {
+    pub fn new(kind: DiagnosticKind, path: &Path, message: impl Into<String>) -> Self {
+        Diagnostic {
+            kind,
+            path: path.to_path_buf(),
+            message: message.into(),
+            region: None,
+            member_path: None,
+            error: None,
+        }
+    }
+
+    pub fn with_region(mut self, region: Option<Region>) -> Self {
+        self.region = region;
+        self
+    }
+
+    pub fn with_member_path(mut self, member_path: &Path) -> Self {
+        self.member_path = Some(member_path.to_path_buf());
+        self
+    }
+
+    /// The file this diagnostic is about: the member it names, if any,
+    /// otherwise the file it is in.
+    pub fn subject_path(&self) -> &Path {
+        self.member_path.as_deref().unwrap_or(&self.path)
+    }
+
+    /// Creates a diagnostic from an arbitrary error, picking up the location
+    /// of the problem when it is a parser error.
+    pub fn from_error(kind: DiagnosticKind, path: &Path, error: Error) -> Self {
+        let (message, region) = match error.downcast_ref::<VB6Error>() {
+            Some(parse_error) => (
+                parse_error.kind.to_string(),
+                Some(Region {
+                    line: parse_error.line_number,
+                    column: parse_error.column,
+                }),
+            ),
+            None => (error.to_string(), None),
+        };
+
+        Diagnostic {
+            kind,
+            path: path.to_path_buf(),
+            message,
+            region,
+            member_path: None,
+            error: Some(error),
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.error {
+            Some(error) => write!(f, "{}", error),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
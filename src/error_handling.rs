@@ -0,0 +1,615 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::check::SourceFileKind;
+use crate::code::{identifier_key, Procedure, ProcedureKind, Statement, Visibility};
+use crate::diagnostic::{Diagnostic, DiagnosticKind, Region};
+use crate::info::InfoFormat;
+use crate::runtime::contains;
+use crate::sources::{is_event_handler, SourceFile, Sources};
+
+/// How a procedure handles errors, by the `On Error` statements in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// `On Error GoTo` a label, even if it also uses `On Error Resume Next`.
+    GoTo,
+    ResumeNext,
+    None,
+}
+
+impl Strategy {
+    const ALL: [Strategy; 3] = [Strategy::GoTo, Strategy::ResumeNext, Strategy::None];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Strategy::GoTo => "goto",
+            Strategy::ResumeNext => "resume-next",
+            Strategy::None => "none",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Strategy::GoTo => "On Error GoTo",
+            Strategy::ResumeNext => "On Error Resume Next",
+            Strategy::None => "none",
+        }
+    }
+}
+
+/// What an `On Error` statement does.
+enum OnError<'a> {
+    GoTo(&'a str),
+    ResumeNext,
+    /// `On Error GoTo 0`, or `On Error GoTo -1`.
+    Disable,
+}
+
+fn on_error(code: &[String]) -> Option<OnError<'_>> {
+    let mut words = code.iter().map(String::as_str).peekable();
+    if !words.next()?.eq_ignore_ascii_case("On") {
+        return None;
+    }
+    if words.peek()?.eq_ignore_ascii_case("Local") {
+        words.next();
+    }
+    if !words.next()?.eq_ignore_ascii_case("Error") {
+        return None;
+    }
+
+    let action = words.next()?;
+    let target = words.next()?;
+    if action.eq_ignore_ascii_case("Resume") && target.eq_ignore_ascii_case("Next") {
+        Some(OnError::ResumeNext)
+    } else if action.eq_ignore_ascii_case("GoTo") {
+        match target {
+            "0" | "-" => Some(OnError::Disable),
+            label => Some(OnError::GoTo(label)),
+        }
+    } else {
+        None
+    }
+}
+
+/// Checks if code looks at the error, or raises it again.
+fn mentions_error(code: &[String]) -> bool {
+    on_error(code).is_none()
+        && code
+            .iter()
+            .any(|token| contains(&["Err", "Erl", "Error", "Error$"], token))
+}
+
+/// Checks if running never continues from code to the statement after it.
+fn leaves_flow(code: &[String]) -> bool {
+    let first = code.first().map(String::as_str).unwrap_or_default();
+    matches!(code, [end] if end.eq_ignore_ascii_case("End"))
+        || contains(&["Exit", "GoTo", "Resume"], first)
+}
+
+/// The error handling of one procedure, and what is wrong with it.
+pub struct ProcedureAudit<'a> {
+    pub procedure: &'a Procedure,
+    pub strategy: Strategy,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Classifies the error handling of every procedure in a file, and reports:
+///
+/// - `On Error GoTo` statements naming a label that doesn't exist.
+/// - Error handlers the code before them falls into without an error, as
+///   there is no `Exit Sub` or the like before the label.
+/// - `On Error Resume Next` without anything looking at `Err` afterwards,
+///   and handlers that `Resume Next` without looking at it, which both
+///   ignore every error.
+/// - Event handlers and public members of classes without error handling,
+///   where an error ends the program or reaches code that didn't call them.
+pub fn audit_error_handling(file: &SourceFile) -> Vec<ProcedureAudit<'_>> {
+    let event_sources = file.event_sources();
+
+    file.code
+        .procedures
+        .iter()
+        .map(|procedure| {
+            let mut audit = audit_procedure(&file.path, procedure);
+
+            let has_code = procedure
+                .body
+                .iter()
+                .any(|statement| !statement.code().is_empty());
+            let is_event_handler = is_event_handler(&procedure.name, &event_sources);
+            let is_public_member =
+                file.kind == SourceFileKind::Class && procedure.visibility == Visibility::Public;
+
+            if audit.strategy == Strategy::None
+                && has_code
+                && (is_event_handler || is_public_member)
+            {
+                let message = if is_event_handler {
+                    format!("Event handler '{}' has no error handling", procedure.name)
+                } else {
+                    format!(
+                        "Public member '{}' of class '{}' has no error handling",
+                        procedure.name, file.name
+                    )
+                };
+                audit.diagnostics.push(diagnostic(
+                    DiagnosticKind::UnhandledEntryPoint,
+                    &file.path,
+                    procedure.line,
+                    message,
+                ));
+            }
+
+            audit
+        })
+        .collect()
+}
+
+fn audit_procedure<'a>(path: &Path, procedure: &'a Procedure) -> ProcedureAudit<'a> {
+    let body = &procedure.body;
+    let mut strategy = Strategy::None;
+    let mut diagnostics = Vec::new();
+    let mut handlers = Vec::new();
+
+    let label_position = |name: &str| {
+        body.iter().position(|statement| {
+            statement
+                .label()
+                .is_some_and(|label| identifier_key(label) == identifier_key(name))
+        })
+    };
+
+    for (position, statement) in body.iter().enumerate() {
+        match on_error(statement.code()) {
+            Some(OnError::GoTo(label)) => {
+                strategy = Strategy::GoTo;
+                match label_position(label) {
+                    Some(label_position) => {
+                        if !handlers.contains(&label_position) {
+                            handlers.push(label_position);
+                        }
+                    }
+                    None => diagnostics.push(diagnostic(
+                        DiagnosticKind::MissingErrorLabel,
+                        path,
+                        statement.line,
+                        format!(
+                            "'On Error GoTo {}' in '{}' names a label that doesn't exist",
+                            label, procedure.name
+                        ),
+                    )),
+                }
+            }
+            Some(OnError::ResumeNext) => {
+                if strategy == Strategy::None {
+                    strategy = Strategy::ResumeNext;
+                }
+
+                let rest = &body[position + 1..];
+                let is_checked = rest.iter().any(|statement| {
+                    on_error(statement.code()).is_some() || mentions_error(statement.code())
+                });
+                if !is_checked {
+                    diagnostics.push(diagnostic(
+                        DiagnosticKind::SwallowedErrors,
+                        path,
+                        statement.line,
+                        format!(
+                            "'On Error Resume Next' in '{}' ignores every error, as nothing checks Err afterwards",
+                            procedure.name
+                        ),
+                    ));
+                }
+            }
+            Some(OnError::Disable) | None => {}
+        }
+    }
+
+    let exit = match procedure.kind {
+        ProcedureKind::Sub => "Exit Sub",
+        ProcedureKind::Function => "Exit Function",
+        _ => "Exit Property",
+    };
+
+    for handler in handlers {
+        let label = body[handler].label().unwrap_or_default();
+        let line = body[handler].line;
+
+        let previous = body[..handler]
+            .iter()
+            .rev()
+            .map(Statement::code)
+            .find(|code| !code.is_empty());
+        if previous.is_some_and(|code| !leaves_flow(code)) {
+            diagnostics.push(diagnostic(
+                DiagnosticKind::ErrorHandlerFallThrough,
+                path,
+                line,
+                format!(
+                    "Error handler '{}' in '{}' also runs without an error, as there is no '{}' before it",
+                    label, procedure.name, exit
+                ),
+            ));
+        }
+
+        let handler_code: Vec<&[String]> = body[handler..].iter().map(Statement::code).collect();
+        let resumes_next = handler_code.iter().any(|code| {
+            code.len() == 2
+                && code[0].eq_ignore_ascii_case("Resume")
+                && code[1].eq_ignore_ascii_case("Next")
+        });
+        if resumes_next && !handler_code.iter().any(|code| mentions_error(code)) {
+            diagnostics.push(diagnostic(
+                DiagnosticKind::SwallowedErrors,
+                path,
+                line,
+                format!(
+                    "Error handler '{}' in '{}' resumes with the next statement without checking Err",
+                    label, procedure.name
+                ),
+            ));
+        }
+    }
+
+    diagnostics.sort_by_key(|diagnostic| diagnostic.region.map(|region| region.line));
+
+    ProcedureAudit {
+        procedure,
+        strategy,
+        diagnostics,
+    }
+}
+
+fn diagnostic(kind: DiagnosticKind, path: &Path, line: usize, message: String) -> Diagnostic {
+    Diagnostic::new(kind, path, message).with_region(Some(Region { line, column: 1 }))
+}
+
+/// Reports the problems [`audit_error_handling`] finds in a file.
+pub fn lint_error_handling(kind: SourceFileKind, path: &Path, contents: &[u8]) -> Vec<Diagnostic> {
+    let file = SourceFile::new(kind, path, contents.to_vec());
+
+    audit_error_handling(&file)
+        .into_iter()
+        .flat_map(|audit| audit.diagnostics)
+        .collect()
+}
+
+/// The audits of every file in a project, with the file each is for.
+type ProjectAudit<'a> = Vec<(&'a SourceFile, Vec<ProcedureAudit<'a>>)>;
+
+/// Shows how every procedure in the projects or files at `paths` handles
+/// errors, with a summary for each project. Returns `false` if any file
+/// couldn't be read or any procedure has a finding, so the audit can gate
+/// a build.
+pub fn error_handling_subcommand(paths: &[PathBuf], format: InfoFormat) -> Result<bool> {
+    let (all_sources, errors) = Sources::load(paths);
+    for error in &errors {
+        eprintln!("{}", error);
+    }
+
+    let audits: Vec<(&Sources, ProjectAudit)> = all_sources
+        .iter()
+        .map(|sources| {
+            let files = sources
+                .files
+                .iter()
+                .map(|file| (file, audit_error_handling(file)))
+                .collect();
+            (sources, files)
+        })
+        .collect();
+
+    match format {
+        InfoFormat::Text => {
+            for (position, (sources, files)) in audits.iter().enumerate() {
+                if position > 0 {
+                    println!();
+                }
+                print_text(sources, files);
+            }
+        }
+        InfoFormat::Json => println!("{}", serde_json::to_string_pretty(&audits_json(&audits))?),
+        InfoFormat::Yaml => print!("{}", serde_yaml::to_string(&audits_json(&audits))?),
+    }
+
+    let has_findings = audits.iter().any(|(_, files)| {
+        files
+            .iter()
+            .any(|(_, procedures)| procedures.iter().any(|audit| !audit.diagnostics.is_empty()))
+    });
+
+    Ok(errors.is_empty() && !has_findings)
+}
+
+fn print_text(sources: &Sources, files: &ProjectAudit) {
+    println!(
+        "{}",
+        sources
+            .display_project()
+            .unwrap_or_else(|| "Files".to_owned())
+    );
+
+    let rows: Vec<(String, &ProcedureAudit)> = files
+        .iter()
+        .flat_map(|(file, audits)| {
+            audits
+                .iter()
+                .map(|audit| (format!("{}.{}", file.name, audit.procedure.name), audit))
+        })
+        .collect();
+
+    let width = rows
+        .iter()
+        .map(|(name, _)| name.len())
+        .chain(["Procedure".len()])
+        .max()
+        .unwrap_or_default();
+    println!(
+        "  {:<width$}  {:<20}  Issues",
+        "Procedure", "Error handling"
+    );
+    for (name, audit) in &rows {
+        println!(
+            "  {:<width$}  {:<20}  {}",
+            name,
+            audit.strategy.label(),
+            audit.diagnostics.len()
+        );
+    }
+
+    let counts: Vec<String> = Strategy::ALL
+        .iter()
+        .map(|strategy| {
+            let count = rows
+                .iter()
+                .filter(|(_, audit)| audit.strategy == *strategy)
+                .count();
+            format!("{} {}", count, strategy.label())
+        })
+        .collect();
+    println!("  {} procedures: {}", rows.len(), counts.join(", "));
+
+    let diagnostics: Vec<&Diagnostic> = rows
+        .iter()
+        .flat_map(|(_, audit)| &audit.diagnostics)
+        .collect();
+    if !diagnostics.is_empty() {
+        println!("  Issues:");
+        for diagnostic in diagnostics {
+            println!(
+                "    {}:{}: {} [{}]",
+                diagnostic.path.display(),
+                diagnostic
+                    .region
+                    .map(|region| region.line)
+                    .unwrap_or_default(),
+                diagnostic,
+                diagnostic.kind.id()
+            );
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ProjectJson<'a> {
+    project: Option<String>,
+    summary: SummaryJson,
+    procedures: Vec<ProcedureJson<'a>>,
+}
+
+/// How many procedures use each strategy, and the issues found in them.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SummaryJson {
+    goto: usize,
+    resume_next: usize,
+    none: usize,
+    issues: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProcedureJson<'a> {
+    module: &'a str,
+    name: &'a str,
+    path: String,
+    line: usize,
+    error_handling: &'static str,
+    issues: Vec<IssueJson<'a>>,
+}
+
+#[derive(Serialize)]
+struct IssueJson<'a> {
+    kind: &'static str,
+    line: Option<usize>,
+    message: &'a str,
+}
+
+fn audits_json<'a>(audits: &'a [(&Sources, ProjectAudit)]) -> Vec<ProjectJson<'a>> {
+    audits
+        .iter()
+        .map(|(sources, files)| {
+            let procedures = files
+                .iter()
+                .flat_map(|(file, audits)| {
+                    audits.iter().map(|audit| ProcedureJson {
+                        module: &file.name,
+                        name: &audit.procedure.name,
+                        path: file.path.display().to_string(),
+                        line: audit.procedure.line,
+                        error_handling: audit.strategy.id(),
+                        issues: audit
+                            .diagnostics
+                            .iter()
+                            .map(|diagnostic| IssueJson {
+                                kind: diagnostic.kind.id(),
+                                line: diagnostic.region.map(|region| region.line),
+                                message: &diagnostic.message,
+                            })
+                            .collect(),
+                    })
+                })
+                .collect();
+
+            let all_audits = || files.iter().flat_map(|(_, audits)| audits);
+            let count = |strategy| {
+                all_audits()
+                    .filter(|audit| audit.strategy == strategy)
+                    .count()
+            };
+
+            ProjectJson {
+                project: sources.display_project(),
+                summary: SummaryJson {
+                    goto: count(Strategy::GoTo),
+                    resume_next: count(Strategy::ResumeNext),
+                    none: count(Strategy::None),
+                    issues: all_audits().map(|audit| audit.diagnostics.len()).sum(),
+                },
+                procedures,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::testing::messages;
+
+    #[test]
+    fn procedures_are_classified_and_their_handlers_checked() {
+        let class = SourceFile::new(
+            SourceFileKind::Class,
+            Path::new("Widget.cls"),
+            concat!(
+                "Attribute VB_Name = \"Widget\"\r\n",
+                "Public Sub Save()\r\n",
+                "  On Error GoTo Failed\r\n",
+                "  Kill \"widget.dat\"\r\n",
+                "Failed:\r\n",
+                "  Resume Next\r\n",
+                "End Sub\r\n",
+                "Public Function Load() As Boolean\r\n",
+                "  On Error GoTo Failed\r\n",
+                "  Load = True\r\n",
+                "  Exit Function\r\n",
+                "Failed: MsgBox Err.Description\r\n",
+                "End Function\r\n",
+                "Public Sub Reset()\r\n",
+                "  On Error GoTo Cleanup\r\n",
+                "  On Error Resume Next\r\n",
+                "  Kill \"widget.dat\"\r\n",
+                "End Sub\r\n",
+                "Public Sub Refresh()\r\n",
+                "  DoEvents: DoEvents\r\n",
+                "End Sub\r\n",
+                "Private Sub Class_Terminate()\r\n",
+                "  On Error Resume Next\r\n",
+                "  Kill \"widget.tmp\"\r\n",
+                "  If Err.Number <> 0 Then Debug.Print Err.Description\r\n",
+                "End Sub\r\n",
+            )
+            .as_bytes()
+            .to_vec(),
+        );
+
+        let audits = audit_error_handling(&class);
+
+        let strategies: Vec<(&str, Strategy)> = audits
+            .iter()
+            .map(|audit| (audit.procedure.name.as_str(), audit.strategy))
+            .collect();
+        assert_eq!(
+            strategies,
+            [
+                ("Save", Strategy::GoTo),
+                ("Load", Strategy::GoTo),
+                ("Reset", Strategy::GoTo),
+                ("Refresh", Strategy::None),
+                ("Class_Terminate", Strategy::ResumeNext),
+            ]
+        );
+
+        assert_eq!(
+            messages(audits.into_iter().flat_map(|audit| audit.diagnostics)),
+            [
+                "5 Error handler 'Failed' in 'Save' also runs without an error, as there is no 'Exit Sub' before it",
+                "5 Error handler 'Failed' in 'Save' resumes with the next statement without checking Err",
+                "15 'On Error GoTo Cleanup' in 'Reset' names a label that doesn't exist",
+                "16 'On Error Resume Next' in 'Reset' ignores every error, as nothing checks Err afterwards",
+                "19 Public member 'Refresh' of class 'Widget' has no error handling",
+            ]
+        );
+    }
+
+    #[test]
+    fn disabled_handlers_line_numbers_and_properties_are_understood() {
+        let class = SourceFile::new(
+            SourceFileKind::Class,
+            Path::new("Widget.cls"),
+            concat!(
+                "Attribute VB_Name = \"Widget\"\r\n",
+                "Private m_Name As String\r\n",
+                "Public Property Get Name() As String\r\n",
+                "  On Error GoTo 100\r\n",
+                "  Name = m_Name\r\n",
+                "  Exit Property\r\n",
+                "100 Name = \"\"\r\n",
+                "End Property\r\n",
+                "Public Property Let Name(ByVal Value As String)\r\n",
+                "  On Error GoTo Failed\r\n",
+                "  m_Name = Value\r\n",
+                "Failed:\r\n",
+                "  Err.Raise Err.Number\r\n",
+                "End Property\r\n",
+                "Public Sub Scoped()\r\n",
+                "  On Error Resume Next\r\n",
+                "  Kill \"widget.tmp\"\r\n",
+                "  On Error GoTo 0\r\n",
+                "  Kill \"widget.dat\"\r\n",
+                "End Sub\r\n",
+                "Public Sub Reset()\r\n",
+                "  On Error GoTo -1\r\n",
+                "  On Error GoTo 0\r\n",
+                "  Kill \"widget.dat\"\r\n",
+                "End Sub\r\n",
+                "Public Sub Retry()\r\n",
+                "  On Error GoTo 200\r\n",
+                "  Exit Sub\r\n",
+                "100 Resume\r\n",
+                "End Sub\r\n",
+            )
+            .as_bytes()
+            .to_vec(),
+        );
+
+        let audits = audit_error_handling(&class);
+
+        let strategies: Vec<(&str, Strategy)> = audits
+            .iter()
+            .map(|audit| (audit.procedure.name.as_str(), audit.strategy))
+            .collect();
+        assert_eq!(
+            strategies,
+            [
+                ("Name", Strategy::GoTo),
+                ("Name", Strategy::GoTo),
+                ("Scoped", Strategy::ResumeNext),
+                ("Reset", Strategy::None),
+                ("Retry", Strategy::GoTo),
+            ]
+        );
+
+        assert_eq!(
+            messages(audits.into_iter().flat_map(|audit| audit.diagnostics)),
+            [
+                "12 Error handler 'Failed' in 'Name' also runs without an error, as there is no 'Exit Property' before it",
+                "21 Public member 'Reset' of class 'Widget' has no error handling",
+                "27 'On Error GoTo 200' in 'Retry' names a label that doesn't exist",
+            ]
+        );
+    }
+}
@@ -0,0 +1,358 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::code::{suffix_type, CodeModule, Parameter, Procedure, ProcedureKind};
+use crate::control_events::{event_parameters, has_known_events, is_event_name, EventParameter};
+use crate::designer::{parse_designer, Control};
+use crate::diagnostic::{Diagnostic, DiagnosticKind, Region};
+
+/// Checks the `Control_Event` procedures in a form's code against the
+/// controls on the form.
+///
+/// VB6 only connects a handler to an event by its name, so a handler left
+/// behind when a control is deleted or renamed is silently never called, and
+/// one with the wrong parameters fails only when the form is compiled.
+pub fn lint_event_handlers(path: &Path, contents: &[u8]) -> Vec<Diagnostic> {
+    let Ok(form) = parse_designer(contents) else {
+        return Vec::new();
+    };
+    let code_module = CodeModule::parse(contents);
+
+    let event_sources = EventSource::on_form(&form);
+
+    // `WithEvents` variables and implemented interfaces are handled the same
+    // way as controls, but aren't on the form.
+    let other_sources: HashSet<String> = code_module
+        .variables
+        .iter()
+        .map(|variable| &variable.name)
+        .chain(&code_module.interfaces)
+        .map(|name| name.to_ascii_lowercase())
+        .collect();
+
+    let mut diagnostics = Vec::new();
+
+    for procedure in &code_module.procedures {
+        if procedure.kind != ProcedureKind::Sub {
+            continue;
+        }
+
+        // Control names may contain underscores, but event names don't.
+        let Some((source_name, event)) = procedure.name.rsplit_once('_') else {
+            continue;
+        };
+        let source_name = source_name.to_ascii_lowercase();
+        if other_sources.contains(&source_name) {
+            continue;
+        }
+
+        let message = match event_sources.get(&source_name) {
+            None if is_event_name(event) => format!(
+                "'{}' handles the {} event of '{}', which is not on the form",
+                procedure.name,
+                event,
+                &procedure.name[..source_name.len()]
+            ),
+            None => continue,
+            Some(source) if !has_known_events(&source.type_name) => continue,
+            Some(source) => match event_parameters(&source.type_name, event) {
+                Some(parameters) => {
+                    if let Some(diagnostic) =
+                        check_parameters(path, procedure, source, event, parameters)
+                    {
+                        diagnostics.push(diagnostic);
+                    }
+                    continue;
+                }
+                None if is_event_name(event) => format!(
+                    "'{}' handles the {} event, which {} '{}' does not raise",
+                    procedure.name,
+                    event,
+                    short_type_name(&source.type_name),
+                    source.name
+                ),
+                None => continue,
+            },
+        };
+
+        diagnostics.push(handler_diagnostic(
+            DiagnosticKind::OrphanedEventHandler,
+            path,
+            procedure,
+            message,
+        ));
+    }
+
+    diagnostics
+}
+
+/// Something on a form whose events can be handled.
+struct EventSource {
+    /// The name handlers use, which is `Form` or `MDIForm` for the form.
+    name: String,
+    type_name: String,
+    is_array: bool,
+}
+
+impl EventSource {
+    /// The form and every control on it, by the lower case name their
+    /// handlers use.
+    fn on_form(form: &Control) -> HashMap<String, EventSource> {
+        let mut sources = HashMap::new();
+
+        let form_name = form.short_type_name();
+        sources.insert(
+            form_name.to_ascii_lowercase(),
+            EventSource {
+                name: form_name.to_owned(),
+                type_name: form.type_name.clone(),
+                is_array: false,
+            },
+        );
+
+        add_controls(form, &mut sources);
+
+        sources
+    }
+}
+
+fn add_controls(container: &Control, sources: &mut HashMap<String, EventSource>) {
+    for control in &container.children {
+        let is_array = control.index().is_some();
+
+        sources
+            .entry(control.name.to_ascii_lowercase())
+            .and_modify(|source| source.is_array |= is_array)
+            .or_insert_with(|| EventSource {
+                name: control.name.clone(),
+                type_name: control.type_name.clone(),
+                is_array,
+            });
+
+        add_controls(control, sources);
+    }
+}
+
+fn check_parameters(
+    path: &Path,
+    procedure: &Procedure,
+    source: &EventSource,
+    event: &str,
+    event_parameters: &[EventParameter],
+) -> Option<Diagnostic> {
+    let declared = &procedure.parameters;
+
+    let declares_index = declared.len() == event_parameters.len() + 1
+        && declared[0].name.eq_ignore_ascii_case("Index");
+    if !source.is_array && declares_index {
+        return Some(handler_diagnostic(
+            DiagnosticKind::EventHandlerMismatch,
+            path,
+            procedure,
+            format!(
+                "'{}' declares an Index parameter, but '{}' is not a control array",
+                procedure.name, source.name
+            ),
+        ));
+    }
+
+    // Handlers of control arrays without an Index are reported with the
+    // other control array problems.
+    let declares_leading_index = declared
+        .first()
+        .is_some_and(|parameter| parameter.name.eq_ignore_ascii_case("Index"));
+    if source.is_array && !declares_leading_index {
+        return None;
+    }
+
+    let expected: Vec<EventParameter> = source
+        .is_array
+        .then_some(("Index", "Integer"))
+        .into_iter()
+        .chain(event_parameters.iter().copied())
+        .collect();
+
+    let matches = declared.len() == expected.len()
+        && declared
+            .iter()
+            .zip(&expected)
+            .all(|(parameter, (_, type_name))| is_declared_as(parameter, type_name));
+    if matches {
+        return None;
+    }
+
+    let expected_parameters: Vec<String> = expected
+        .iter()
+        .map(|(name, type_name)| format!("{} As {}", name, type_name))
+        .collect();
+
+    Some(handler_diagnostic(
+        DiagnosticKind::EventHandlerMismatch,
+        path,
+        procedure,
+        format!(
+            "'{}' does not match the {} event of {} '{}', which is handled by {}_{}({})",
+            procedure.name,
+            event,
+            short_type_name(&source.type_name),
+            source.name,
+            source.name,
+            event,
+            expected_parameters.join(", ")
+        ),
+    ))
+}
+
+fn is_declared_as(parameter: &Parameter, type_name: &str) -> bool {
+    if parameter.param_array || parameter.optional || parameter.is_array {
+        return false;
+    }
+
+    parameter
+        .type_name
+        .as_deref()
+        .or_else(|| suffix_type(&parameter.name))
+        .is_some_and(|declared| declared.eq_ignore_ascii_case(type_name))
+}
+
+fn short_type_name(type_name: &str) -> &str {
+    type_name.strip_prefix("VB.").unwrap_or(type_name)
+}
+
+fn handler_diagnostic(
+    kind: DiagnosticKind,
+    path: &Path,
+    procedure: &Procedure,
+    message: String,
+) -> Diagnostic {
+    Diagnostic::new(kind, path, message).with_region(Some(Region {
+        line: procedure.line,
+        column: 1,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handlers_must_match_the_controls_on_the_form() {
+        let source = concat!(
+            "VERSION 5.00\r\n",
+            "Begin VB.Form frmMain \r\n",
+            "   Begin VB.CommandButton cmdOK \r\n",
+            "      Index           =   0\r\n",
+            "   End\r\n",
+            "   Begin VB.TextBox txtName \r\n",
+            "   End\r\n",
+            "   Begin MSComctlLib.ListView lvwItems \r\n",
+            "   End\r\n",
+            "End\r\n",
+            "Attribute VB_Name = \"frmMain\"\r\n",
+            "Private WithEvents m_Timer As Ticker\r\n",
+            "Private Sub Form_Load()\r\n",
+            "End Sub\r\n",
+            "Private Sub cmdOK_Click(Index As Integer)\r\n",
+            "End Sub\r\n",
+            "Private Sub cmdOK_KeyPress(Index As Integer, KeyAscii As Long)\r\n",
+            "End Sub\r\n",
+            "Private Sub txtName_KeyDown(Index As Integer, KeyCode%, Shift As Integer)\r\n",
+            "End Sub\r\n",
+            "Private Sub txtName_KeyPress(KeyAscii%)\r\n",
+            "End Sub\r\n",
+            "Private Sub txtName_Timer()\r\n",
+            "End Sub\r\n",
+            "Private Sub cmdCancel_Click()\r\n",
+            "End Sub\r\n",
+            "Private Sub lvwItems_ItemClick(ByVal Item As ListItem)\r\n",
+            "End Sub\r\n",
+            "Private Sub m_Timer_Click()\r\n",
+            "End Sub\r\n",
+            "Private Sub Save_File()\r\n",
+            "End Sub\r\n",
+        );
+
+        let messages: Vec<String> = lint_event_handlers(Path::new("Main.frm"), source.as_bytes())
+            .into_iter()
+            .map(|diagnostic| {
+                format!(
+                    "{}:{} {}",
+                    diagnostic.region.unwrap().line,
+                    diagnostic.kind.id(),
+                    diagnostic.message
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            messages,
+            [
+                "17:event-handler-mismatch 'cmdOK_KeyPress' does not match the KeyPress event of CommandButton 'cmdOK', which is handled by cmdOK_KeyPress(Index As Integer, KeyAscii As Integer)",
+                "19:event-handler-mismatch 'txtName_KeyDown' declares an Index parameter, but 'txtName' is not a control array",
+                "23:orphaned-event-handler 'txtName_Timer' handles the Timer event, which TextBox 'txtName' does not raise",
+                "25:orphaned-event-handler 'cmdCancel_Click' handles the Click event of 'cmdCancel', which is not on the form",
+            ]
+        );
+    }
+
+    #[test]
+    fn control_array_handlers_take_a_leading_index() {
+        let source = concat!(
+            "VERSION 5.00\r\n",
+            "Begin VB.Form frmMain \r\n",
+            "   Begin VB.Frame fraOptions \r\n",
+            "      Begin VB.OptionButton optChoice \r\n",
+            "         Index           =   0\r\n",
+            "      End\r\n",
+            "      Begin VB.OptionButton optChoice \r\n",
+            "         Index           =   1\r\n",
+            "      End\r\n",
+            "   End\r\n",
+            "   Begin VB.TextBox txtField \r\n",
+            "      Index           =   0\r\n",
+            "   End\r\n",
+            "   Begin VB.Timer tmrPoll \r\n",
+            "      Index           =   0\r\n",
+            "   End\r\n",
+            "   Begin VB.Menu mnuRecent \r\n",
+            "      Index           =   0\r\n",
+            "   End\r\n",
+            "End\r\n",
+            "Attribute VB_Name = \"frmMain\"\r\n",
+            "Private Sub optChoice_Click(Index As Integer)\r\n",
+            "End Sub\r\n",
+            "Private Sub txtField_KeyDown(Index%, KeyCode As Integer, Shift As Integer)\r\n",
+            "End Sub\r\n",
+            "Private Sub txtField_Validate(Index As Integer, Cancel As Boolean)\r\n",
+            "End Sub\r\n",
+            "Private Sub tmrPoll_Timer(Index As Long)\r\n",
+            "End Sub\r\n",
+            "Private Sub mnuRecent_Click(Index As Integer, Extra As Integer)\r\n",
+            "End Sub\r\n",
+            "Private Sub txtField_Change()\r\n",
+            "End Sub\r\n",
+        );
+
+        let messages: Vec<String> = lint_event_handlers(Path::new("Main.frm"), source.as_bytes())
+            .into_iter()
+            .map(|diagnostic| {
+                format!(
+                    "{}:{} {}",
+                    diagnostic.region.unwrap().line,
+                    diagnostic.kind.id(),
+                    diagnostic.message
+                )
+            })
+            .collect();
+
+        // txtField_Change is missing its Index, which is reported with the
+        // other control array problems rather than here.
+        assert_eq!(
+            messages,
+            [
+                "28:event-handler-mismatch 'tmrPoll_Timer' does not match the Timer event of Timer 'tmrPoll', which is handled by tmrPoll_Timer(Index As Integer)",
+                "30:event-handler-mismatch 'mnuRecent_Click' does not match the Click event of Menu 'mnuRecent', which is handled by mnuRecent_Click(Index As Integer)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,393 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::designer::{parse_designer, Bounds, Control, Units};
+use crate::diagnostic::{Diagnostic, DiagnosticKind, Region};
+
+/// Controls that are drawn rather than interacted with, and so are often
+/// deliberately placed over or under other controls.
+const DECORATIVE_CONTROLS: [&str; 2] = ["VB.Line", "VB.Shape"];
+
+/// Checks the layout of a form's controls for the defects that usually only
+/// show up when someone clicks through the form: controls outside their
+/// container, overlapping controls, a broken tab order, clashing access
+/// keys, and controls with no size.
+pub fn lint_form_layout(path: &Path, contents: &[u8]) -> Vec<Diagnostic> {
+    // Forms vb6parse accepted but this can't read are left to it.
+    let Ok(form) = parse_designer(contents) else {
+        return Vec::new();
+    };
+
+    let mut linter = FormLinter {
+        path,
+        diagnostics: Vec::new(),
+    };
+
+    linter.check_container(&form, Some(Units::TWIPS));
+    linter.check_tab_order(&form);
+    linter.check_access_keys(&form);
+
+    linter.diagnostics
+}
+
+struct FormLinter<'a> {
+    path: &'a Path,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl FormLinter<'_> {
+    fn report(&mut self, kind: DiagnosticKind, control: &Control, message: String) {
+        self.diagnostics
+            .push(
+                Diagnostic::new(kind, self.path, message).with_region(Some(Region {
+                    line: control.line,
+                    column: 1,
+                })),
+            );
+    }
+
+    /// Checks the children of `container`, whose own position is given in
+    /// `units`, or unknown units when `None`.
+    fn check_container(&mut self, container: &Control, units: Option<Units>) {
+        // Custom controls, such as tab strips, position their children in
+        // ways that can't be known from the form alone.
+        let is_custom_container = !container.type_name.starts_with("VB.");
+
+        let child_units = container.child_units(units);
+
+        let interior = if is_custom_container {
+            None
+        } else {
+            interior_size(container, units, child_units)
+        };
+
+        let laid_out: Vec<(&Control, Bounds)> = container
+            .children
+            .iter()
+            .filter(|child| !DECORATIVE_CONTROLS.contains(&child.type_name.as_str()))
+            .filter_map(|child| Some((child, child.bounds()?)))
+            .collect();
+
+        for (child, bounds) in &laid_out {
+            if bounds.width == 0 || bounds.height == 0 {
+                self.report(
+                    DiagnosticKind::ZeroSizeControl,
+                    child,
+                    format!(
+                        "'{}' is {}x{} and can't be seen",
+                        child.display_name(),
+                        bounds.width,
+                        bounds.height
+                    ),
+                );
+            }
+
+            if let Some((width, height)) = interior {
+                let is_outside = bounds.left < 0
+                    || bounds.top < 0
+                    || f64::from(bounds.left) + f64::from(bounds.width) > width
+                    || f64::from(bounds.top) + f64::from(bounds.height) > height;
+
+                if is_outside {
+                    self.report(
+                        DiagnosticKind::ControlOutsideContainer,
+                        child,
+                        format!(
+                            "'{}' at {},{} size {}x{} is outside the {:.0}x{:.0} client area of '{}'",
+                            child.display_name(),
+                            bounds.left,
+                            bounds.top,
+                            bounds.width,
+                            bounds.height,
+                            width,
+                            height,
+                            container.display_name()
+                        ),
+                    );
+                }
+            }
+        }
+
+        // Controls hidden at design time are usually shown in place of one
+        // another, so only visible ones can clash.
+        let visible: Vec<&(&Control, Bounds)> = laid_out
+            .iter()
+            .filter(|(child, _)| child.integer_property("Visible") != Some(0))
+            .collect();
+
+        for (position, (child, bounds)) in visible.iter().enumerate() {
+            if let Some((other, _)) = visible[..position]
+                .iter()
+                .find(|(_, other_bounds)| overlaps(bounds, other_bounds))
+            {
+                self.report(
+                    DiagnosticKind::OverlappingControls,
+                    child,
+                    format!(
+                        "'{}' overlaps '{}' in '{}'",
+                        child.display_name(),
+                        other.display_name(),
+                        container.display_name()
+                    ),
+                );
+            }
+        }
+
+        if !is_custom_container {
+            for child in &container.children {
+                self.check_container(child, child_units);
+            }
+        }
+    }
+
+    /// Checks `TabIndex` values, which are numbered across the whole form,
+    /// are unique and run from 0 without gaps.
+    fn check_tab_order(&mut self, form: &Control) {
+        let mut tab_stops: Vec<(i32, &Control)> = Vec::new();
+        collect_tab_stops(form, &mut tab_stops);
+        tab_stops.sort_by_key(|(tab_index, control)| (*tab_index, control.line));
+
+        let mut expected = 0;
+        for (position, (tab_index, control)) in tab_stops.iter().enumerate() {
+            if position > 0 && tab_stops[position - 1].0 == *tab_index {
+                self.report(
+                    DiagnosticKind::InvalidTabOrder,
+                    control,
+                    format!(
+                        "'{}' has the same TabIndex {} as '{}'",
+                        control.display_name(),
+                        tab_index,
+                        tab_stops[position - 1].1.display_name()
+                    ),
+                );
+                continue;
+            }
+
+            if *tab_index != expected {
+                self.report(
+                    DiagnosticKind::InvalidTabOrder,
+                    control,
+                    format!(
+                        "'{}' has TabIndex {}, but the tab order has no {}",
+                        control.display_name(),
+                        tab_index,
+                        expected
+                    ),
+                );
+            }
+            expected = tab_index + 1;
+        }
+    }
+
+    /// Checks that no two controls on the form, or items on the same menu,
+    /// use the same `&` access key.
+    fn check_access_keys(&mut self, form: &Control) {
+        let mut controls = Vec::new();
+        collect_non_menu_controls(form, &mut controls);
+        self.check_access_key_group(&controls, "the form");
+
+        self.check_menu_access_keys(form);
+    }
+
+    fn check_menu_access_keys(&mut self, parent: &Control) {
+        let menus: Vec<&Control> = parent
+            .children
+            .iter()
+            .filter(|child| child.type_name == "VB.Menu")
+            .collect();
+
+        let scope = if parent.is_form() {
+            "the menu bar".to_owned()
+        } else {
+            format!("menu '{}'", parent.display_name())
+        };
+        self.check_access_key_group(&menus, &scope);
+
+        for menu in menus {
+            self.check_menu_access_keys(menu);
+        }
+    }
+
+    fn check_access_key_group(&mut self, controls: &[&Control], scope: &str) {
+        let mut first_uses: HashMap<char, &Control> = HashMap::new();
+
+        for control in controls {
+            let Some(access_key) = control.property("Caption").and_then(access_key) else {
+                continue;
+            };
+
+            match first_uses.get(&access_key) {
+                Some(first) => {
+                    let message = format!(
+                        "'{}' uses the access key '{}' already used by '{}' in {}",
+                        control.display_name(),
+                        access_key,
+                        first.display_name(),
+                        scope
+                    );
+                    self.report(DiagnosticKind::DuplicateAccessKey, control, message);
+                }
+                None => {
+                    first_uses.insert(access_key, control);
+                }
+            }
+        }
+    }
+}
+
+/// The size of a container's client area in the units its children are
+/// positioned in, when both are known.
+fn interior_size(
+    container: &Control,
+    units: Option<Units>,
+    child_units: Option<Units>,
+) -> Option<(f64, f64)> {
+    let child_units = child_units?;
+
+    if container.is_form() {
+        let width = f64::from(container.integer_property("ClientWidth")?);
+        let height = f64::from(container.integer_property("ClientHeight")?);
+        return Some((
+            width / child_units.twips_per_unit,
+            height / child_units.twips_per_unit,
+        ));
+    }
+
+    // VB6 writes the scaled interior size of controls that have one.
+    if let (Some(width), Some(height)) = (
+        container.property("ScaleWidth"),
+        container.property("ScaleHeight"),
+    ) {
+        return Some((width.trim().parse().ok()?, height.trim().parse().ok()?));
+    }
+
+    let units = units?;
+    let bounds = container.bounds()?;
+    let scale = units.twips_per_unit / child_units.twips_per_unit;
+
+    Some((
+        f64::from(bounds.width) * scale,
+        f64::from(bounds.height) * scale,
+    ))
+}
+
+fn overlaps(left: &Bounds, right: &Bounds) -> bool {
+    left.left < right.left + right.width
+        && right.left < left.left + left.width
+        && left.top < right.top + right.height
+        && right.top < left.top + left.height
+}
+
+fn collect_tab_stops<'a>(control: &'a Control, tab_stops: &mut Vec<(i32, &'a Control)>) {
+    for child in &control.children {
+        if let Some(tab_index) = child.integer_property("TabIndex") {
+            tab_stops.push((tab_index, child));
+        }
+        collect_tab_stops(child, tab_stops);
+    }
+}
+
+fn collect_non_menu_controls<'a>(control: &'a Control, controls: &mut Vec<&'a Control>) {
+    for child in control
+        .children
+        .iter()
+        .filter(|child| child.type_name != "VB.Menu")
+    {
+        controls.push(child);
+        collect_non_menu_controls(child, controls);
+    }
+}
+
+/// The character after the first single `&` in a caption, in lower case.
+fn access_key(caption: &str) -> Option<char> {
+    let mut characters = caption.chars();
+
+    while let Some(character) = characters.next() {
+        if character == '&' {
+            match characters.next() {
+                // `&&` is a literal ampersand.
+                Some('&') => continue,
+                Some(key) if !key.is_whitespace() => return key.to_lowercase().next(),
+                _ => return None,
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_defects_are_reported() {
+        let source = concat!(
+            "VERSION 5.00\r\n",
+            "Begin VB.Form frmMain \r\n",
+            "   ClientHeight    =   3000\r\n",
+            "   ClientWidth     =   4000\r\n",
+            "   Begin VB.CommandButton cmdOK \r\n",
+            "      Caption         =   \"&OK\"\r\n",
+            "      Height          =   375\r\n",
+            "      Left            =   100\r\n",
+            "      TabIndex        =   0\r\n",
+            "      Top             =   100\r\n",
+            "      Width           =   1000\r\n",
+            "   End\r\n",
+            "   Begin VB.CommandButton cmdOpen \r\n",
+            "      Caption         =   \"&Open\"\r\n",
+            "      Height          =   375\r\n",
+            "      Left            =   500\r\n",
+            "      TabIndex        =   2\r\n",
+            "      Top             =   200\r\n",
+            "      Width           =   1000\r\n",
+            "   End\r\n",
+            "   Begin VB.PictureBox picPanel \r\n",
+            "      Height          =   100\r\n",
+            "      Left            =   3500\r\n",
+            "      ScaleHeight     =   100\r\n",
+            "      ScaleMode       =   3  'Pixel\r\n",
+            "      ScaleWidth      =   1000\r\n",
+            "      TabIndex        =   2\r\n",
+            "      Top             =   2000\r\n",
+            "      Width           =   1000\r\n",
+            "      Begin VB.Label lblHint \r\n",
+            "         Caption         =   \"Tom && Jerry\"\r\n",
+            "         Height          =   0\r\n",
+            "         Left            =   10\r\n",
+            "         Top             =   10\r\n",
+            "         Width           =   200\r\n",
+            "      End\r\n",
+            "   End\r\n",
+            "   Begin VB.Menu mnuFile \r\n",
+            "      Caption         =   \"&File\"\r\n",
+            "   End\r\n",
+            "End\r\n",
+        );
+
+        let messages: Vec<String> = lint_form_layout(Path::new("Main.frm"), source.as_bytes())
+            .into_iter()
+            .map(|diagnostic| {
+                format!(
+                    "{}:{} {}",
+                    diagnostic.region.unwrap().line,
+                    diagnostic.kind.id(),
+                    diagnostic.message
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            messages,
+            [
+                "21:control-outside-container 'picPanel' at 3500,2000 size 1000x100 is outside the 4000x3000 client area of 'frmMain'",
+                "13:overlapping-controls 'cmdOpen' overlaps 'cmdOK' in 'frmMain'",
+                "30:zero-size-control 'lblHint' is 200x0 and can't be seen",
+                "13:invalid-tab-order 'cmdOpen' has TabIndex 2, but the tab order has no 1",
+                "21:invalid-tab-order 'picPanel' has the same TabIndex 2 as 'cmdOpen'",
+                "13:duplicate-access-key 'cmdOpen' uses the access key 'o' already used by 'cmdOK' in the form",
+            ]
+        );
+    }
+}
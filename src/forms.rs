@@ -0,0 +1,299 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+use crate::check::{join_parent_project_path, project_directory};
+use crate::designer::{parse_designer, Control};
+use crate::info::{parse_project, read_project, InfoFormat};
+
+/// A form and the controls on it.
+pub struct Form {
+    pub path: PathBuf,
+    pub control: Control,
+}
+
+/// The forms listed in a project, resolved against its directory.
+pub fn project_form_paths(project_path: &Path) -> Result<Vec<PathBuf>> {
+    let project_contents = read_project(project_path)?;
+    let project = parse_project(project_path, &project_contents)?;
+
+    Ok(project
+        .forms
+        .iter()
+        .map(|form| join_parent_project_path(project_directory(project_path), &form.to_string()))
+        .collect())
+}
+
+/// Expands `.vbp` projects among `paths` into the forms they list.
+pub fn form_paths(paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut form_paths = Vec::new();
+
+    for path in paths {
+        let is_project = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("vbp"));
+
+        if is_project {
+            form_paths.extend(project_form_paths(path)?);
+        } else {
+            form_paths.push(path.clone());
+        }
+    }
+
+    Ok(form_paths)
+}
+
+/// Reads the control tree of the form at `path`.
+pub fn read_form(path: &Path) -> Result<Form> {
+    let contents = std::fs::read(path)
+        .map_err(|e| anyhow!("Unable to read form '{}': {}", path.display(), e))?;
+
+    let control = parse_designer(&contents)
+        .map_err(|e| anyhow!("Unable to read form '{}': {}", path.display(), e))?;
+
+    Ok(Form {
+        path: path.to_path_buf(),
+        control,
+    })
+}
+
+/// Prints the control tree of each form in `paths`, which may be `.frm`
+/// files or `.vbp` projects whose forms are all shown.
+///
+/// Returns `false` if any form couldn't be read.
+pub fn forms_subcommand(paths: &[PathBuf], format: InfoFormat) -> Result<bool> {
+    let mut forms = Vec::new();
+    let mut all_read = true;
+
+    for path in form_paths(paths)? {
+        match read_form(&path) {
+            Ok(form) => forms.push(form),
+            Err(e) => {
+                eprintln!("{}", e);
+                all_read = false;
+            }
+        }
+    }
+
+    match format {
+        InfoFormat::Text => print!("{}", forms_text(&forms)),
+        InfoFormat::Json => println!("{}", serde_json::to_string_pretty(&forms_json(&forms))?),
+        InfoFormat::Yaml => print!("{}", serde_yaml::to_string(&forms_json(&forms))?),
+    }
+
+    Ok(all_read)
+}
+
+/// Renders each form's path followed by its control tree, one control per
+/// line, indented by how deeply it is nested.
+fn forms_text(forms: &[Form]) -> String {
+    let mut text = String::new();
+
+    for form in forms {
+        let _ = writeln!(text, "{}", form.path.display());
+        write_control(&mut text, &form.control, 1);
+    }
+
+    text
+}
+
+fn write_control(text: &mut String, control: &Control, depth: usize) {
+    let mut line = format!(
+        "{}{} ({})",
+        "  ".repeat(depth),
+        control.display_name(),
+        control.short_type_name()
+    );
+
+    if let Some(caption) = control.property("Caption") {
+        line.push_str(&format!(" {:?}", caption));
+    }
+    if let (false, Some(left), Some(top)) = (
+        control.is_form(),
+        control.integer_property("Left"),
+        control.integer_property("Top"),
+    ) {
+        line.push_str(&format!(" at {},{}", left, top));
+    }
+    if let Some(bounds) = control.bounds() {
+        line.push_str(&format!(" size {}x{}", bounds.width, bounds.height));
+    }
+    if let Some(tab_index) = control.integer_property("TabIndex") {
+        line.push_str(&format!(" tab {}", tab_index));
+    }
+
+    let _ = writeln!(text, "{}", line);
+
+    for child in &control.children {
+        write_control(text, child, depth + 1);
+    }
+}
+
+#[derive(Serialize)]
+struct FormJson<'a> {
+    path: String,
+    form: ControlJson<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ControlJson<'a> {
+    #[serde(rename = "type")]
+    type_name: &'a str,
+    name: &'a str,
+    index: Option<i32>,
+    caption: Option<&'a str>,
+    left: Option<i32>,
+    top: Option<i32>,
+    width: Option<i32>,
+    height: Option<i32>,
+    tab_index: Option<i32>,
+    controls: Vec<ControlJson<'a>>,
+}
+
+fn forms_json(forms: &[Form]) -> Vec<FormJson<'_>> {
+    forms
+        .iter()
+        .map(|form| FormJson {
+            path: form.path.display().to_string(),
+            form: control_json(&form.control),
+        })
+        .collect()
+}
+
+fn control_json(control: &Control) -> ControlJson<'_> {
+    let (width, height) = if control.is_form() {
+        ("ClientWidth", "ClientHeight")
+    } else {
+        ("Width", "Height")
+    };
+
+    ControlJson {
+        type_name: &control.type_name,
+        name: &control.name,
+        index: control.index(),
+        caption: control.property("Caption"),
+        left: control.integer_property("Left"),
+        top: control.integer_property("Top"),
+        width: control.integer_property(width),
+        height: control.integer_property(height),
+        tab_index: control.integer_property("TabIndex"),
+        controls: control.children.iter().map(control_json).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORM: &str = concat!(
+        "VERSION 5.00\r\n",
+        "Begin VB.Form frmOrders \r\n",
+        "   Caption         =   \"Orders\"\r\n",
+        "   ClientHeight    =   3000\r\n",
+        "   ClientWidth     =   4000\r\n",
+        "   Begin VB.Frame fraActions \r\n",
+        "      Caption         =   \"Actions\"\r\n",
+        "      Height          =   2000\r\n",
+        "      Left            =   120\r\n",
+        "      TabIndex        =   0\r\n",
+        "      Top             =   120\r\n",
+        "      Width           =   3000\r\n",
+        "      Begin VB.PictureBox picButtons \r\n",
+        "         Height          =   1500\r\n",
+        "         Left            =   60\r\n",
+        "         Top             =   240\r\n",
+        "         Width           =   2800\r\n",
+        "         Begin VB.CommandButton cmdAction \r\n",
+        "            Caption         =   \"&Save\"\r\n",
+        "            Height          =   375\r\n",
+        "            Index           =   0\r\n",
+        "            Left            =   0\r\n",
+        "            TabIndex        =   1\r\n",
+        "            Top             =   0\r\n",
+        "            Width           =   1200\r\n",
+        "         End\r\n",
+        "         Begin VB.CommandButton cmdAction \r\n",
+        "            Caption         =   \"&Cancel\"\r\n",
+        "            Height          =   375\r\n",
+        "            Index           =   1\r\n",
+        "            Left            =   1320\r\n",
+        "            TabIndex        =   2\r\n",
+        "            Top             =   0\r\n",
+        "            Width           =   1200\r\n",
+        "         End\r\n",
+        "      End\r\n",
+        "   End\r\n",
+        "End\r\n",
+        "Attribute VB_Name = \"frmOrders\"\r\n",
+    );
+
+    fn form() -> Form {
+        Form {
+            path: PathBuf::from("frmOrders.frm"),
+            control: parse_designer(FORM.as_bytes()).unwrap(),
+        }
+    }
+
+    #[test]
+    fn text_output_nests_containers_and_indexes_control_arrays() {
+        assert_eq!(
+            forms_text(&[form()]),
+            concat!(
+                "frmOrders.frm\n",
+                "  frmOrders (Form) \"Orders\" size 4000x3000\n",
+                "    fraActions (Frame) \"Actions\" at 120,120 size 3000x2000 tab 0\n",
+                "      picButtons (PictureBox) at 60,240 size 2800x1500\n",
+                "        cmdAction(0) (CommandButton) \"&Save\" at 0,0 size 1200x375 tab 1\n",
+                "        cmdAction(1) (CommandButton) \"&Cancel\" at 1320,0 size 1200x375 tab 2\n",
+            )
+        );
+    }
+
+    #[test]
+    fn json_output_nests_containers_and_indexes_control_arrays() {
+        let json = serde_json::to_value(forms_json(&[form()])).unwrap();
+
+        assert_eq!(json[0]["path"], "frmOrders.frm");
+
+        let form = &json[0]["form"];
+        assert_eq!(form["type"], "VB.Form");
+        assert_eq!(form["width"], 4000);
+        assert_eq!(form["left"], serde_json::Value::Null);
+
+        let picture_box = &form["controls"][0]["controls"][0];
+        assert_eq!(picture_box["name"], "picButtons");
+        assert_eq!(picture_box["index"], serde_json::Value::Null);
+        assert_eq!(
+            picture_box["controls"],
+            serde_json::json!([
+                {
+                    "type": "VB.CommandButton",
+                    "name": "cmdAction",
+                    "index": 0,
+                    "caption": "&Save",
+                    "left": 0,
+                    "top": 0,
+                    "width": 1200,
+                    "height": 375,
+                    "tabIndex": 1,
+                    "controls": [],
+                },
+                {
+                    "type": "VB.CommandButton",
+                    "name": "cmdAction",
+                    "index": 1,
+                    "caption": "&Cancel",
+                    "left": 1320,
+                    "top": 0,
+                    "width": 1200,
+                    "height": 375,
+                    "tabIndex": 2,
+                    "controls": [],
+                },
+            ])
+        );
+    }
+}
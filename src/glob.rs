@@ -0,0 +1,219 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use globset::{GlobBuilder, GlobMatcher};
+use walkdir::WalkDir;
+
+use crate::walk::relative_path;
+
+/// A gitignore-style glob pattern used to filter the paths aspen walks.
+///
+/// Patterns containing a `/` anywhere other than the end are anchored and
+/// matched against the whole path relative to the directory they apply to.
+/// Patterns without one match the last component of a path at any depth, so
+/// `Old` matches `Old`, `src/Old` and `src/Legacy/Old` alike. A trailing `/`
+/// restricts the pattern to directories.
+///
+/// Matching is case-insensitive since VB6 projects come from a case-insensitive
+/// file system and are frequently inconsistent about casing.
+#[derive(Debug, Clone)]
+pub struct Glob {
+    matcher: GlobMatcher,
+    anchored: bool,
+    directory_only: bool,
+}
+
+impl Glob {
+    /// Compiles a glob pattern.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the pattern is empty or is not a valid glob,
+    /// such as one with an unterminated character class.
+    pub fn new(pattern: &str) -> Result<Self> {
+        Glob::compile(pattern, false)
+    }
+
+    /// Compiles a glob pattern that is always matched against the whole
+    /// relative path, the way a shell expands it.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the pattern is empty or is not a valid glob,
+    /// such as one with an unterminated character class.
+    pub fn new_anchored(pattern: &str) -> Result<Self> {
+        Glob::compile(pattern, true)
+    }
+
+    fn compile(pattern: &str, always_anchored: bool) -> Result<Self> {
+        let mut body = pattern.replace('\\', "/");
+
+        let directory_only = body.ends_with('/');
+        while body.ends_with('/') {
+            body.pop();
+        }
+
+        let anchored = always_anchored || body.contains('/');
+        let body = body.trim_start_matches('/');
+
+        if body.is_empty() {
+            return Err(anyhow!(
+                "Invalid glob pattern '{}': pattern is empty",
+                pattern
+            ));
+        }
+
+        // `*` and `?` never match a separator, and backslashes were already
+        // turned into separators rather than escapes.
+        let matcher = GlobBuilder::new(body)
+            .case_insensitive(true)
+            .literal_separator(true)
+            .backslash_escape(false)
+            .build()
+            .map_err(|e| anyhow!("Invalid glob pattern '{}': {}", pattern, e.kind()))?
+            .compile_matcher();
+
+        Ok(Glob {
+            matcher,
+            anchored,
+            directory_only,
+        })
+    }
+
+    /// Checks if `relative_path` matches this pattern.
+    ///
+    /// `relative_path` uses `/` as its separator and is relative to the
+    /// directory the pattern applies to.
+    pub fn matches(&self, relative_path: &str, is_dir: bool) -> bool {
+        if self.directory_only && !is_dir {
+            return false;
+        }
+
+        let relative_path = relative_path.trim_start_matches('/');
+
+        let candidate = if self.anchored {
+            relative_path
+        } else {
+            relative_path.rsplit('/').next().unwrap_or(relative_path)
+        };
+
+        self.matcher.is_match(candidate)
+    }
+}
+
+/// Checks if `pattern` contains any glob metacharacters.
+pub fn is_glob_pattern(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+/// Expands a glob pattern into the files it matches, sorted by path.
+///
+/// The leading components without any metacharacters are used as the
+/// directory to search, so `src/**/*.bas` only walks `src`. Relative
+/// patterns are expanded from the current directory.
+///
+/// # Errors
+///
+/// An error is returned if the pattern is not a valid glob.
+pub fn expand_glob(pattern: &str) -> Result<Vec<PathBuf>> {
+    let pattern = pattern.replace('\\', "/");
+    let components: Vec<&str> = pattern.split('/').collect();
+
+    let literal_count = components
+        .iter()
+        .take_while(|component| !is_glob_pattern(component))
+        .count();
+
+    let base = match components[..literal_count].join("/") {
+        base if base.is_empty() && literal_count > 0 => PathBuf::from("/"),
+        base if base.is_empty() => PathBuf::from("."),
+        base => PathBuf::from(base),
+    };
+
+    let remainder = components[literal_count..].join("/");
+    let glob = Glob::new_anchored(&remainder)?;
+
+    let max_depth = if remainder.contains("**") {
+        usize::MAX
+    } else {
+        components.len() - literal_count
+    };
+
+    let mut matched: Vec<PathBuf> = WalkDir::new(&base)
+        .min_depth(1)
+        .max_depth(max_depth)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| glob.matches(&relative_path(&base, entry.path()), false))
+        .map(|entry| entry.into_path())
+        .collect();
+
+    matched.sort();
+
+    Ok(matched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unanchored_pattern_matches_file_name_at_any_depth() {
+        let glob = Glob::new("*.vbp").unwrap();
+
+        assert!(glob.matches("Project1.vbp", false));
+        assert!(glob.matches("src/Legacy/Project1.VBP", false));
+        assert!(!glob.matches("src/Project1.vbw", false));
+    }
+
+    #[test]
+    fn anchored_pattern_matches_whole_relative_path() {
+        let glob = Glob::new("src/*.vbp").unwrap();
+
+        assert!(glob.matches("src/Project1.vbp", false));
+        assert!(!glob.matches("other/src/Project1.vbp", false));
+        assert!(!glob.matches("src/nested/Project1.vbp", false));
+    }
+
+    #[test]
+    fn double_star_crosses_directories() {
+        let glob = Glob::new("**/Old/**").unwrap();
+
+        assert!(glob.matches("Old/Project1.vbp", false));
+        assert!(glob.matches("a/b/Old/c/Project1.vbp", false));
+        assert!(!glob.matches("a/Older/Project1.vbp", false));
+    }
+
+    #[test]
+    fn trailing_slash_only_matches_directories() {
+        let glob = Glob::new("bin/").unwrap();
+
+        assert!(glob.matches("src/bin", true));
+        assert!(!glob.matches("src/bin", false));
+    }
+
+    #[test]
+    fn character_classes() {
+        let glob = Glob::new("Backup[0-9]").unwrap();
+        assert!(glob.matches("Backup3", true));
+        assert!(!glob.matches("BackupA", true));
+
+        let glob = Glob::new("[!~]*.vbp").unwrap();
+        assert!(glob.matches("Project1.vbp", false));
+        assert!(!glob.matches("~Project1.vbp", false));
+
+        assert!(Glob::new("[abc").is_err());
+        assert!(Glob::new("/").is_err());
+    }
+
+    #[test]
+    fn matching_time_does_not_grow_exponentially() {
+        let glob = Glob::new("src/**/a*a*a*a*a*a*a*a*a*a*a*a*b").unwrap();
+        let path = format!("src/{}", vec!["a".repeat(40); 6].join("/"));
+
+        let started = std::time::Instant::now();
+        assert!(!glob.matches(&path, false));
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+    }
+}
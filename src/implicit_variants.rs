@@ -0,0 +1,271 @@
+use std::path::Path;
+
+use crate::code::{suffix_type, CodeModule, Parameter, ProcedureKind, Variable, DEF_TYPE_KEYWORDS};
+use crate::diagnostic::{Diagnostic, DiagnosticKind, Region};
+
+/// The type each `DefType` keyword gives, in the order of
+/// `DEF_TYPE_KEYWORDS`.
+const DEF_TYPES: [&str; 12] = [
+    "Boolean", "Byte", "Integer", "Long", "Currency", "Single", "Double", "Decimal", "Date",
+    "String", "Object", "Variant",
+];
+
+/// The default types a module's `DefType` statements give names by their
+/// first letter.
+struct DefaultTypes {
+    types: [Option<&'static str>; 26],
+}
+
+impl DefaultTypes {
+    fn of(code_module: &CodeModule) -> DefaultTypes {
+        let mut types = [None; 26];
+
+        for statement in &code_module.module_statements {
+            let Some(keyword) = statement.tokens.first() else {
+                continue;
+            };
+            let Some(position) = DEF_TYPE_KEYWORDS
+                .iter()
+                .position(|def_type| keyword.eq_ignore_ascii_case(def_type))
+            else {
+                continue;
+            };
+
+            // A list of letters and letter ranges, such as `A-C, X`.
+            let letters = &statement.tokens[1..];
+            let mut index = 0;
+            while index < letters.len() {
+                let first = letter_index(&letters[index]);
+                let last = match letters.get(index + 1).map(String::as_str) {
+                    Some("-") => {
+                        index += 2;
+                        letters.get(index).and_then(|letter| letter_index(letter))
+                    }
+                    _ => first,
+                };
+
+                if let (Some(first), Some(last)) = (first, last) {
+                    for default_type in &mut types[first.min(last)..=first.max(last)] {
+                        *default_type = Some(DEF_TYPES[position]);
+                    }
+                }
+                index += 1;
+                if letters.get(index).map(String::as_str) == Some(",") {
+                    index += 1;
+                }
+            }
+        }
+
+        DefaultTypes { types }
+    }
+
+    /// The type a name declared without `As` gets: the type of its suffix,
+    /// otherwise the `DefType` default for its first letter. `None` means the
+    /// name is a `Variant` without saying so.
+    fn implicit_type(&self, name: &str) -> Option<&'static str> {
+        suffix_type(name).or_else(|| {
+            name.chars()
+                .next()
+                .and_then(|first| letter_index(&first.to_string()))
+                .and_then(|letter| self.types[letter])
+        })
+    }
+}
+
+/// The position in the alphabet of a single letter.
+fn letter_index(letter: &str) -> Option<usize> {
+    match letter.as_bytes() {
+        [letter] if letter.is_ascii_alphabetic() => {
+            Some((letter.to_ascii_uppercase() - b'A') as usize)
+        }
+        _ => None,
+    }
+}
+
+/// Reports the variables, parameters and function return values that are
+/// `Variant`s only because they don't declare a type.
+///
+/// A name without an `As` clause gets the type of its suffix, such as
+/// `String` for `Name$`, otherwise the type the module's `DefType`
+/// statements give its first letter, and only then `Variant`.
+pub fn lint_implicit_variants(path: &Path, contents: &[u8]) -> Vec<Diagnostic> {
+    let code_module = CodeModule::parse(contents);
+    let default_types = DefaultTypes::of(&code_module);
+
+    let is_implicit_variant = |name: &str, type_name: &Option<String>| {
+        type_name.is_none() && default_types.implicit_type(name).is_none()
+    };
+
+    let mut diagnostics = Vec::new();
+    let mut report = |line: usize, message: String| {
+        diagnostics.push(
+            Diagnostic::new(DiagnosticKind::ImplicitVariant, path, message)
+                .with_region(Some(Region { line, column: 1 })),
+        );
+    };
+
+    let mut report_parameters = |parameters: &[Parameter], owner: &str, line: usize| {
+        for parameter in parameters {
+            // A ParamArray is always an array of Variants.
+            if !parameter.param_array && is_implicit_variant(&parameter.name, &parameter.type_name)
+            {
+                report(
+                    line,
+                    format!(
+                        "Parameter '{}' of '{}' is implicitly a Variant",
+                        parameter.name, owner
+                    ),
+                );
+            }
+        }
+    };
+
+    for declare in &code_module.declares {
+        report_parameters(&declare.parameters, &declare.name, declare.line);
+    }
+
+    for procedure in &code_module.procedures {
+        report_parameters(&procedure.parameters, &procedure.name, procedure.line);
+    }
+
+    let variables: Vec<Variable> = code_module
+        .variables
+        .iter()
+        .cloned()
+        .chain(
+            code_module
+                .procedures
+                .iter()
+                .flat_map(|procedure| procedure.local_variables()),
+        )
+        .collect();
+
+    for (position, variable) in variables.iter().enumerate() {
+        if !is_implicit_variant(&variable.name, &variable.type_name) {
+            continue;
+        }
+
+        // `Dim A, B As Long` only makes `B` a Long.
+        let typed_neighbour = variables[position + 1..]
+            .iter()
+            .take_while(|other| other.line == variable.line)
+            .find_map(|other| Some((&other.name, other.type_name.as_ref()?)));
+
+        let message = match typed_neighbour {
+            Some((name, type_name)) => format!(
+                "Variable '{}' is implicitly a Variant, as 'As {}' only applies to '{}'",
+                variable.name, type_name, name
+            ),
+            None => format!("Variable '{}' is implicitly a Variant", variable.name),
+        };
+        report(variable.line, message);
+    }
+
+    let returns = code_module
+        .declares
+        .iter()
+        .map(|declare| {
+            (
+                &declare.name,
+                &declare.return_type,
+                declare.kind,
+                declare.line,
+            )
+        })
+        .chain(code_module.procedures.iter().map(|procedure| {
+            (
+                &procedure.name,
+                &procedure.return_type,
+                procedure.kind,
+                procedure.line,
+            )
+        }));
+
+    for (name, return_type, kind, line) in returns {
+        let noun = match kind {
+            ProcedureKind::Function => "Function",
+            ProcedureKind::PropertyGet => "Property",
+            _ => continue,
+        };
+
+        if is_implicit_variant(name, return_type) {
+            report(
+                line,
+                format!("{} '{}' implicitly returns a Variant", noun, name),
+            );
+        }
+    }
+
+    diagnostics.sort_by_key(|diagnostic| diagnostic.region.map(|region| region.line));
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::testing::messages;
+
+    #[test]
+    fn undeclared_types_are_variants_unless_defaulted() {
+        let contents = concat!(
+            "Attribute VB_Name = \"Module1\"\r\n",
+            "DefLng I-N\r\n",
+            "DefStr S\r\n",
+            "Private Total, Count As Long\r\n",
+            "Private Items As Variant, Index, Name$\r\n",
+            "Private Declare Function GetTickCount Lib \"kernel32\" ()\r\n",
+            "Public Function Sum(Values, ByVal Scale As Double, ParamArray Rest())\r\n",
+            "  Dim Result, Text\r\n",
+            "  Static Calls%\r\n",
+            "End Function\r\n",
+            "Public Property Get Title$()\r\n",
+            "End Property\r\n",
+        );
+
+        assert_eq!(
+            messages(lint_implicit_variants(
+                Path::new("Module1.bas"),
+                contents.as_bytes()
+            )),
+            [
+                "4 Variable 'Total' is implicitly a Variant, as 'As Long' only applies to 'Count'",
+                "6 Function 'GetTickCount' implicitly returns a Variant",
+                "7 Parameter 'Values' of 'Sum' is implicitly a Variant",
+                "8 Variable 'Result' is implicitly a Variant",
+                "8 Variable 'Text' is implicitly a Variant",
+            ]
+        );
+    }
+
+    #[test]
+    fn def_types_and_suffixes_both_give_types() {
+        let contents = concat!(
+            "Attribute VB_Name = \"Module1\"\r\n",
+            "defint a-z\r\n",
+            "DefStr S\r\n",
+            "DefVar V\r\n",
+            "Private Count, Name$, Total#, Price@\r\n",
+            "Private Value, Text\r\n",
+            "Public Function Sum(Values, ByVal Scale!)\r\n",
+            "  Dim Result, Amount&\r\n",
+            "End Function\r\n",
+        );
+
+        // Variant is only reported when nothing gives a type, and DefVar
+        // does so explicitly.
+        assert!(lint_implicit_variants(Path::new("Module1.bas"), contents.as_bytes()).is_empty());
+
+        let without_defaults = contents.replace("defint a-z\r\n", "");
+        assert_eq!(
+            messages(lint_implicit_variants(
+                Path::new("Module1.bas"),
+                without_defaults.as_bytes()
+            )),
+            [
+                "4 Variable 'Count' is implicitly a Variant",
+                "5 Variable 'Text' is implicitly a Variant",
+                "7 Variable 'Result' is implicitly a Variant",
+            ]
+        );
+    }
+}
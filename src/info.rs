@@ -0,0 +1,677 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use clap::builder::PossibleValue;
+use clap::ValueEnum;
+use serde::{Serialize, Serializer};
+use vb6parse::parsers::project::CompatibilityMode;
+use vb6parse::parsers::{VB6ObjectReference, VB6Project, VB6ProjectReference};
+
+use crate::check::{join_parent_project_path, project_directory};
+use crate::project_settings::project_type_name;
+use crate::suppression::strip_project_comments;
+
+/// How `aspen info` and `aspen forms` write what they found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InfoFormat {
+    /// Human readable text.
+    #[default]
+    Text,
+    /// A JSON document for scripts.
+    Json,
+    /// A YAML document for scripts.
+    Yaml,
+}
+
+impl ValueEnum for InfoFormat {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[InfoFormat::Text, InfoFormat::Json, InfoFormat::Yaml]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            InfoFormat::Text => PossibleValue::new("text").help("human readable text"),
+            InfoFormat::Json => PossibleValue::new("json").help("a JSON document for scripts"),
+            InfoFormat::Yaml => PossibleValue::new("yaml").help("a YAML document for scripts"),
+        })
+    }
+}
+
+/// A file listed in a project, resolved against the project's directory.
+struct MemberInfo {
+    kind: &'static str,
+    name: Option<String>,
+    listed_path: String,
+    resolved_path: PathBuf,
+}
+
+impl MemberInfo {
+    fn new(
+        kind: &'static str,
+        name: Option<String>,
+        listed_path: String,
+        project_path: &Path,
+    ) -> Self {
+        let resolved_path = join_parent_project_path(project_directory(project_path), &listed_path);
+
+        MemberInfo {
+            kind,
+            name,
+            listed_path,
+            resolved_path,
+        }
+    }
+
+    fn exists(&self) -> bool {
+        self.resolved_path.exists()
+    }
+}
+
+/// Reads a project file, without the comments vb6parse doesn't accept.
+pub fn read_project(project_path: &Path) -> Result<Vec<u8>> {
+    let project_contents = std::fs::read(project_path)
+        .map_err(|e| anyhow!("Unable to read project '{}': {}", project_path.display(), e))?;
+
+    Ok(strip_project_comments(&project_contents))
+}
+
+/// Parses the contents of a project read with [`read_project`].
+pub fn parse_project<'a>(
+    project_path: &Path,
+    project_contents: &'a [u8],
+) -> Result<VB6Project<'a>> {
+    let file_name = project_path
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| project_path.display().to_string());
+
+    VB6Project::parse(file_name, project_contents).map_err(|e| {
+        anyhow!(
+            "Unable to parse project '{}' at {}:{}: {}",
+            project_path.display(),
+            e.line_number,
+            e.column,
+            e.kind
+        )
+    })
+}
+
+/// Prints what was parsed from the project at `project_path`.
+pub fn info_subcommand(project_path: &Path, format: InfoFormat) -> Result<()> {
+    let project_contents = read_project(project_path)?;
+    let project = parse_project(project_path, &project_contents)?;
+
+    let members = project_members(&project, project_path);
+
+    match format {
+        InfoFormat::Text => print!("{}", project_text(project_path, &project, &members)),
+        InfoFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&project_json(project_path, &project, &members))?
+        ),
+        InfoFormat::Yaml => print!(
+            "{}",
+            serde_yaml::to_string(&project_json(project_path, &project, &members))?
+        ),
+    }
+
+    Ok(())
+}
+
+fn project_members(project: &VB6Project, project_path: &Path) -> Vec<MemberInfo> {
+    let named =
+        |kind, name: String, path: String| MemberInfo::new(kind, Some(name), path, project_path);
+    let unnamed = |kind, path: String| MemberInfo::new(kind, None, path, project_path);
+
+    let modules = project
+        .modules
+        .iter()
+        .map(|module| named("module", module.name.to_string(), module.path.to_string()));
+    let classes = project
+        .classes
+        .iter()
+        .map(|class| named("class", class.name.to_string(), class.path.to_string()));
+    let forms = project
+        .forms
+        .iter()
+        .map(|form| unnamed("form", form.to_string()));
+    let user_controls = project
+        .user_controls
+        .iter()
+        .map(|user_control| unnamed("user control", user_control.to_string()));
+    let user_documents = project
+        .user_documents
+        .iter()
+        .map(|user_document| unnamed("user document", user_document.to_string()));
+    let designers = project
+        .designers
+        .iter()
+        .map(|designer| unnamed("designer", designer.to_string()));
+    let related_documents = project
+        .related_documents
+        .iter()
+        .map(|document| unnamed("related document", document.to_string()));
+
+    modules
+        .chain(classes)
+        .chain(forms)
+        .chain(user_controls)
+        .chain(user_documents)
+        .chain(designers)
+        .chain(related_documents)
+        .collect()
+}
+
+/// Formats a GUID the way VB6 writes it, braced and in upper case.
+pub fn format_guid(guid: &impl std::fmt::Display) -> String {
+    format!("{{{}}}", guid).to_uppercase()
+}
+
+fn compatibility_name(compatibility_mode: &CompatibilityMode) -> &'static str {
+    match compatibility_mode {
+        CompatibilityMode::NoCompatibility => "none",
+        CompatibilityMode::Project => "project",
+        CompatibilityMode::CompatibleExe => "binary",
+    }
+}
+
+/// Settings vb6parse leaves unset are empty strings.
+fn optional_setting<T: ToString + ?Sized>(setting: Option<&T>) -> Option<String> {
+    setting
+        .map(|setting| setting.to_string())
+        .filter(|setting| !setting.is_empty())
+}
+
+fn version_string(project: &VB6Project) -> String {
+    format!(
+        "{}.{}.{}",
+        project.version_info.major, project.version_info.minor, project.version_info.revision
+    )
+}
+
+fn compile_options(project: &VB6Project) -> Vec<(&'static str, String)> {
+    vec![
+        ("compilationType", format!("{:?}", project.compilation_type)),
+        (
+            "optimizationType",
+            format!("{:?}", project.optimization_type),
+        ),
+        (
+            "favorPentiumPro",
+            format!("{:?}", project.favor_pentium_pro),
+        ),
+        (
+            "codeViewDebugInfo",
+            format!("{:?}", project.code_view_debug_info),
+        ),
+        ("aliasing", format!("{:?}", project.aliasing)),
+        ("boundsCheck", format!("{:?}", project.bounds_check)),
+        ("overflowCheck", format!("{:?}", project.overflow_check)),
+        (
+            "floatingPointCheck",
+            format!("{:?}", project.floating_point_check),
+        ),
+        (
+            "pentiumFDivBugCheck",
+            format!("{:?}", project.pentium_fdiv_bug_check),
+        ),
+        (
+            "unroundedFloatingPoint",
+            format!("{:?}", project.unrounded_floating_point),
+        ),
+        ("threadingModel", format!("{:?}", project.threading_model)),
+        ("startMode", format!("{:?}", project.start_mode)),
+    ]
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProjectJson<'a> {
+    path: String,
+    #[serde(rename = "type")]
+    project_type: &'static str,
+    name: Option<String>,
+    title: Option<String>,
+    description: Option<String>,
+    startup: Option<String>,
+    version: VersionJson,
+    output: OutputJson,
+    #[serde(serialize_with = "serialize_pairs")]
+    compile_options: Vec<(&'static str, String)>,
+    references: Vec<ReferenceJson>,
+    objects: Vec<ObjectJson>,
+    members: Vec<MemberJson<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VersionJson {
+    major: u16,
+    minor: u16,
+    revision: u16,
+    auto_increment: bool,
+    company_name: Option<String>,
+    product_name: Option<String>,
+    file_description: Option<String>,
+    copyright: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputJson {
+    exe_name32: Option<String>,
+    path32: Option<String>,
+    compatible_mode: &'static str,
+    compatible_exe32: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+enum ReferenceJson {
+    Compiled {
+        guid: String,
+        version: String,
+        lcid: String,
+        path: String,
+        description: String,
+    },
+    SubProject {
+        path: String,
+    },
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+enum ObjectJson {
+    Compiled {
+        guid: String,
+        version: String,
+        #[serde(rename = "fileName")]
+        file_name: String,
+    },
+    Project {
+        path: String,
+    },
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MemberJson<'a> {
+    kind: &'static str,
+    name: &'a Option<String>,
+    path: &'a str,
+    resolved_path: String,
+    exists: bool,
+}
+
+/// Writes `(name, value)` pairs as an object, keeping their order.
+fn serialize_pairs<S: Serializer>(
+    pairs: &[(&'static str, String)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(pairs.iter().map(|(name, value)| (name, value)))
+}
+
+fn project_json<'a>(
+    project_path: &Path,
+    project: &VB6Project,
+    members: &'a [MemberInfo],
+) -> ProjectJson<'a> {
+    let references = project
+        .references
+        .iter()
+        .map(|reference| match reference {
+            VB6ProjectReference::Compiled {
+                uuid,
+                unknown1,
+                unknown2,
+                path,
+                description,
+            } => ReferenceJson::Compiled {
+                guid: format_guid(uuid),
+                version: unknown1.to_string(),
+                lcid: unknown2.to_string(),
+                path: path.to_string(),
+                description: description.to_string(),
+            },
+            VB6ProjectReference::SubProject { path } => ReferenceJson::SubProject {
+                path: path.to_string(),
+            },
+        })
+        .collect();
+
+    let objects = project
+        .objects
+        .iter()
+        .map(|object| match object {
+            VB6ObjectReference::Compiled {
+                uuid,
+                version,
+                file_name,
+                ..
+            } => ObjectJson::Compiled {
+                guid: format_guid(uuid),
+                version: version.to_string(),
+                file_name: file_name.to_string(),
+            },
+            VB6ObjectReference::Project { path } => ObjectJson::Project {
+                path: path.to_string(),
+            },
+        })
+        .collect();
+
+    let members = members
+        .iter()
+        .map(|member| MemberJson {
+            kind: member.kind,
+            name: &member.name,
+            path: &member.listed_path,
+            resolved_path: member.resolved_path.display().to_string(),
+            exists: member.exists(),
+        })
+        .collect();
+
+    let version_info = &project.version_info;
+
+    ProjectJson {
+        path: project_path.display().to_string(),
+        project_type: project_type_name(&project.project_type),
+        name: optional_setting(project.name),
+        title: optional_setting(project.title),
+        description: optional_setting(project.description),
+        startup: project.startup.map(|startup| startup.to_string()),
+        version: VersionJson {
+            major: version_info.major,
+            minor: version_info.minor,
+            revision: version_info.revision,
+            auto_increment: version_info.auto_increment_revision != 0,
+            company_name: optional_setting(version_info.company_name),
+            product_name: optional_setting(version_info.product_name),
+            file_description: optional_setting(version_info.file_description),
+            copyright: optional_setting(version_info.copyright),
+        },
+        output: OutputJson {
+            exe_name32: optional_setting(project.exe_32_file_name),
+            path32: optional_setting(project.path_32),
+            compatible_mode: compatibility_name(&project.compatibility_mode),
+            compatible_exe32: optional_setting(project.exe_32_compatible),
+        },
+        compile_options: compile_options(project),
+        references,
+        objects,
+        members,
+    }
+}
+
+/// Renders the project as the human readable text of `aspen info`.
+fn project_text(project_path: &Path, project: &VB6Project, members: &[MemberInfo]) -> String {
+    let mut text = String::new();
+    let not_set = || "(not set)".to_owned();
+
+    let _ = writeln!(text, "{}", project_path.display());
+    let _ = writeln!(
+        text,
+        "  Type:            {}",
+        project_type_name(&project.project_type)
+    );
+    let _ = writeln!(
+        text,
+        "  Name:            {}",
+        optional_setting(project.name).unwrap_or_else(not_set)
+    );
+    let _ = writeln!(
+        text,
+        "  Startup:         {}",
+        match project.startup {
+            Some(startup) if startup.is_empty() => not_set(),
+            Some(startup) => startup.to_string(),
+            None => "(None)".to_owned(),
+        }
+    );
+    let _ = writeln!(
+        text,
+        "  Version:         {}{}",
+        version_string(project),
+        if project.version_info.auto_increment_revision != 0 {
+            " (auto increment)"
+        } else {
+            ""
+        }
+    );
+    let _ = writeln!(
+        text,
+        "  ExeName32:       {}",
+        optional_setting(project.exe_32_file_name).unwrap_or_else(not_set)
+    );
+    let _ = writeln!(
+        text,
+        "  Path32:          {}",
+        optional_setting(project.path_32).unwrap_or_else(not_set)
+    );
+    let _ = writeln!(
+        text,
+        "  Compatibility:   {}{}",
+        compatibility_name(&project.compatibility_mode),
+        optional_setting(project.exe_32_compatible)
+            .map(|compatible_exe| format!(" ({})", compatible_exe))
+            .unwrap_or_default()
+    );
+
+    let _ = writeln!(text, "Compile Options:");
+    for (name, value) in compile_options(project) {
+        let _ = writeln!(text, "  {:<24} {}", name, value);
+    }
+
+    let _ = writeln!(text, "References:");
+    if project.references.is_empty() {
+        let _ = writeln!(text, "  (none)");
+    }
+    for reference in &project.references {
+        let _ = match reference {
+            VB6ProjectReference::Compiled {
+                uuid,
+                unknown1,
+                path,
+                description,
+                ..
+            } => writeln!(
+                text,
+                "  {} {} {} ({})",
+                format_guid(uuid),
+                unknown1,
+                description,
+                path
+            ),
+            VB6ProjectReference::SubProject { path } => writeln!(text, "  sub-project {}", path),
+        };
+    }
+
+    let _ = writeln!(text, "Objects:");
+    if project.objects.is_empty() {
+        let _ = writeln!(text, "  (none)");
+    }
+    for object in &project.objects {
+        let _ = match object {
+            VB6ObjectReference::Compiled {
+                uuid,
+                version,
+                file_name,
+                ..
+            } => writeln!(text, "  {} {} {}", format_guid(uuid), version, file_name),
+            VB6ObjectReference::Project { path } => writeln!(text, "  project {}", path),
+        };
+    }
+
+    let _ = writeln!(text, "Members:");
+    if members.is_empty() {
+        let _ = writeln!(text, "  (none)");
+    }
+    for member in members {
+        let _ = writeln!(
+            text,
+            "  {:<16} {:<24} {} [{}]",
+            member.kind,
+            member.name.as_deref().unwrap_or("-"),
+            member.resolved_path.display(),
+            if member.exists() { "found" } else { "missing" }
+        );
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROJECT: &str = concat!(
+        "Type=Exe\r\n",
+        "Reference=*\\G{00020430-0000-0000-C000-000000000046}#2.0#0#C:\\Windows\\System32\\stdole2.tlb#OLE Automation\r\n",
+        "Object={831FDD16-0C5C-11D2-A9FC-0000F8754DA1}#2.0#0; MSCOMCTL.OCX\r\n",
+        "Module=Module1; Module1.bas\r\n",
+        "Form=frmMain.frm\r\n",
+        "Startup=\"frmMain\"\r\n",
+        "Name=\"Inventory\"\r\n",
+        "ExeName32=\"Inventory.exe\"\r\n",
+        "MajorVer=1\r\n",
+        "MinorVer=2\r\n",
+        "RevisionVer=3\r\n",
+        "AutoIncrementVer=1\r\n",
+        "VersionCompanyName=\"Acme & Sons\"\r\n",
+    );
+
+    fn with_project(test_name: &str, test: impl FnOnce(&Path, &VB6Project, &[MemberInfo])) {
+        let directory = std::env::temp_dir()
+            .join(format!("aspen-info-{}", std::process::id()))
+            .join(test_name);
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(
+            directory.join("Module1.bas"),
+            "Attribute VB_Name = \"Module1\"\r\n",
+        )
+        .unwrap();
+
+        let project_path = directory.join("Inventory.vbp");
+        let project = parse_project(&project_path, PROJECT.as_bytes()).unwrap();
+        let members = project_members(&project, &project_path);
+
+        test(&project_path, &project, &members);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn text_output_lists_settings_references_and_members() {
+        with_project("text", |project_path, project, members| {
+            let directory = project_path.parent().unwrap();
+            let module_path = directory.join("Module1.bas").display().to_string();
+            let form_path = directory.join("frmMain.frm").display().to_string();
+
+            assert_eq!(
+                project_text(project_path, project, members),
+                [
+                    project_path.display().to_string(),
+                    "  Type:            Standard EXE".to_owned(),
+                    "  Name:            Inventory".to_owned(),
+                    "  Startup:         frmMain".to_owned(),
+                    "  Version:         1.2.3 (auto increment)".to_owned(),
+                    "  ExeName32:       Inventory.exe".to_owned(),
+                    "  Path32:          (not set)".to_owned(),
+                    "  Compatibility:   project".to_owned(),
+                    "Compile Options:".to_owned(),
+                    "  compilationType          PCode".to_owned(),
+                    "  optimizationType         FavorFastCode".to_owned(),
+                    "  favorPentiumPro          False".to_owned(),
+                    "  codeViewDebugInfo        NotCreated".to_owned(),
+                    "  aliasing                 AssumeAliasing".to_owned(),
+                    "  boundsCheck              CheckBounds".to_owned(),
+                    "  overflowCheck            CheckOverflow".to_owned(),
+                    "  floatingPointCheck       CheckFloatingPointError".to_owned(),
+                    "  pentiumFDivBugCheck      NoPentiumFDivBugCheck".to_owned(),
+                    "  unroundedFloatingPoint   DoNotAllow".to_owned(),
+                    "  threadingModel           ApartmentThreaded".to_owned(),
+                    "  startMode                StandAlone".to_owned(),
+                    "References:".to_owned(),
+                    "  {00020430-0000-0000-C000-000000000046} 2.0 OLE Automation (C:\\Windows\\System32\\stdole2.tlb)".to_owned(),
+                    "Objects:".to_owned(),
+                    "  {831FDD16-0C5C-11D2-A9FC-0000F8754DA1} 2.0 MSCOMCTL.OCX".to_owned(),
+                    "Members:".to_owned(),
+                    format!("  module           Module1                  {} [found]", module_path),
+                    format!("  form             -                        {} [missing]", form_path),
+                    String::new(),
+                ]
+                .join("\n")
+            );
+        });
+    }
+
+    #[test]
+    fn json_output_uses_nulls_for_missing_settings() {
+        with_project("json", |project_path, project, members| {
+            let directory = project_path.parent().unwrap();
+            let module_path = directory.join("Module1.bas").display().to_string();
+            let form_path = directory.join("frmMain.frm").display().to_string();
+            let json = serde_json::to_value(project_json(project_path, project, members)).unwrap();
+
+            assert_eq!(json["type"], "Standard EXE");
+            assert_eq!(json["name"], "Inventory");
+            assert_eq!(json["title"], serde_json::Value::Null);
+            assert_eq!(json["startup"], "frmMain");
+            assert_eq!(
+                json["version"],
+                serde_json::json!({
+                    "major": 1,
+                    "minor": 2,
+                    "revision": 3,
+                    "autoIncrement": true,
+                    "companyName": "Acme & Sons",
+                    "productName": null,
+                    "fileDescription": null,
+                    "copyright": null,
+                })
+            );
+            assert_eq!(json["output"]["exeName32"], "Inventory.exe");
+            assert_eq!(json["output"]["path32"], serde_json::Value::Null);
+            assert_eq!(json["compileOptions"]["compilationType"], "PCode");
+            assert_eq!(
+                json["references"],
+                serde_json::json!([{
+                    "kind": "compiled",
+                    "guid": "{00020430-0000-0000-C000-000000000046}",
+                    "version": "2.0",
+                    "lcid": "0",
+                    "path": "C:\\Windows\\System32\\stdole2.tlb",
+                    "description": "OLE Automation",
+                }])
+            );
+            assert_eq!(
+                json["objects"],
+                serde_json::json!([{
+                    "kind": "compiled",
+                    "guid": "{831FDD16-0C5C-11D2-A9FC-0000F8754DA1}",
+                    "version": "2.0",
+                    "fileName": "MSCOMCTL.OCX",
+                }])
+            );
+            assert_eq!(
+                json["members"],
+                serde_json::json!([
+                    {
+                        "kind": "module",
+                        "name": "Module1",
+                        "path": "Module1.bas",
+                        "resolvedPath": module_path,
+                        "exists": true,
+                    },
+                    {
+                        "kind": "form",
+                        "name": null,
+                        "path": "frmMain.frm",
+                        "resolvedPath": form_path,
+                        "exists": false,
+                    },
+                ])
+            );
+        });
+    }
+}
@@ -0,0 +1,210 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::check::CheckResults;
+use crate::diagnostic::Diagnostic;
+use crate::walk::relative_path;
+use crate::xml::escape;
+
+/// Renders the check results as a JUnit XML report.
+///
+/// Each project becomes a `<testsuite>` and each file checked for it a
+/// `<testcase>`, failing with every diagnostic about that file. This gives CI
+/// dashboards a per-file history across builds.
+pub fn junit_report(check_summary: &[CheckResults]) -> String {
+    let mut suites = String::new();
+    let mut total_tests = 0;
+    let mut total_failures = 0;
+
+    for check_results in check_summary {
+        let project_path = Path::new(&check_results.project_path);
+        let project_directory = project_path.parent().unwrap_or(Path::new(""));
+        let class_name = project_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| check_results.project_path.clone());
+
+        // Results that failed before any file could be checked (such as an
+        // unreadable directory) still get a test case to carry the failure.
+        let checked_files = if check_results.checked_files.is_empty() {
+            vec![project_path.to_path_buf()]
+        } else {
+            check_results.checked_files.clone()
+        };
+
+        let mut cases = String::new();
+        let mut failures = 0;
+
+        for checked_file in &checked_files {
+            let diagnostics: Vec<&Diagnostic> = check_results
+                .diagnostics()
+                .filter(|diagnostic| diagnostic.subject_path() == checked_file)
+                .collect();
+
+            let name = match relative_path(project_directory, checked_file) {
+                name if name.is_empty() => checked_file.display().to_string(),
+                name => name,
+            };
+
+            if diagnostics.is_empty() {
+                let _ = writeln!(
+                    cases,
+                    "    <testcase name=\"{}\" classname=\"{}\" file=\"{}\"/>",
+                    escape(&name),
+                    escape(&class_name),
+                    escape(&checked_file.display().to_string())
+                );
+                continue;
+            }
+
+            failures += 1;
+
+            let _ = writeln!(
+                cases,
+                "    <testcase name=\"{}\" classname=\"{}\" file=\"{}\">",
+                escape(&name),
+                escape(&class_name),
+                escape(&checked_file.display().to_string())
+            );
+            for diagnostic in diagnostics {
+                let _ = writeln!(
+                    cases,
+                    "      <failure type=\"{}\" message=\"{}\">{}</failure>",
+                    diagnostic.kind.id(),
+                    escape(&diagnostic.message),
+                    escape(&location(diagnostic))
+                );
+            }
+            cases.push_str("    </testcase>\n");
+        }
+
+        total_tests += checked_files.len();
+        total_failures += failures;
+
+        let _ = writeln!(
+            suites,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\">",
+            escape(&check_results.project_path),
+            checked_files.len(),
+            failures
+        );
+        suites.push_str(&cases);
+        suites.push_str("  </testsuite>\n");
+    }
+
+    let mut report = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        report,
+        "<testsuites name=\"aspen check\" tests=\"{}\" failures=\"{}\" errors=\"0\">",
+        total_tests, total_failures
+    );
+    report.push_str(&suites);
+    report.push_str("</testsuites>");
+
+    report
+}
+
+fn location(diagnostic: &Diagnostic) -> String {
+    match diagnostic.region {
+        Some(region) => format!(
+            "{}:{}:{}: {}",
+            diagnostic.path.display(),
+            region.line,
+            region.column,
+            diagnostic.message
+        ),
+        None => format!("{}: {}", diagnostic.path.display(), diagnostic.message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::{DiagnosticKind, Region};
+    use std::path::PathBuf;
+
+    #[test]
+    fn projects_are_suites_and_files_are_cases() {
+        let project_path = PathBuf::from("/src/R&D <tools>/App.vbp");
+        let module_path = PathBuf::from("/src/R&D <tools>/Module1.bas");
+        let missing_path = PathBuf::from("/src/R&D <tools>/\"Old\".bas");
+        let form_path = PathBuf::from("/src/R&D <tools>/frmMain.frm");
+
+        let app = CheckResults {
+            project_path: project_path.display().to_string(),
+            checked_files: vec![project_path.clone(), module_path.clone(), form_path],
+            parsing_errors: Vec::new(),
+            non_english_files: Vec::new(),
+            missing_files: vec![Diagnostic::new(
+                DiagnosticKind::MissingFile,
+                &project_path,
+                format!("Module not found: {}", missing_path.display()),
+            )
+            .with_region(Some(Region { line: 3, column: 1 }))],
+            lint_findings: vec![
+                Diagnostic::new(
+                    DiagnosticKind::ImplicitVariant,
+                    &module_path,
+                    "Variable 'Total' is implicitly Variant; use \"As Long\" & <friends>",
+                )
+                .with_region(Some(Region { line: 2, column: 5 })),
+                Diagnostic::new(
+                    DiagnosticKind::UnusedVariable,
+                    &module_path,
+                    "Variable 'Spare' is never used",
+                ),
+            ],
+            // Notes don't fail the check, so they aren't failures either.
+            notes: vec![Diagnostic::new(
+                DiagnosticKind::UndescribedReference,
+                &project_path,
+                "No type library description for 'OLE Automation'",
+            )],
+        };
+
+        let unreadable_path = PathBuf::from("/src/Broken.vbp");
+        let broken = CheckResults {
+            project_path: unreadable_path.display().to_string(),
+            checked_files: Vec::new(),
+            parsing_errors: vec![Diagnostic::new(
+                DiagnosticKind::UnreadableFile,
+                &unreadable_path,
+                "Unable to read project",
+            )],
+            non_english_files: Vec::new(),
+            missing_files: Vec::new(),
+            lint_findings: Vec::new(),
+            notes: Vec::new(),
+        };
+
+        let report = junit_report(&[app, broken]);
+
+        assert_eq!(
+            report,
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+                "<testsuites name=\"aspen check\" tests=\"4\" failures=\"3\" errors=\"0\">\n",
+                "  <testsuite name=\"/src/R&amp;D &lt;tools&gt;/App.vbp\" tests=\"3\" failures=\"2\" errors=\"0\" skipped=\"0\">\n",
+                "    <testcase name=\"App.vbp\" classname=\"App\" file=\"/src/R&amp;D &lt;tools&gt;/App.vbp\">\n",
+                "      <failure type=\"missing-file\" message=\"Module not found: /src/R&amp;D &lt;tools&gt;/&quot;Old&quot;.bas\">",
+                "/src/R&amp;D &lt;tools&gt;/App.vbp:3:1: Module not found: /src/R&amp;D &lt;tools&gt;/&quot;Old&quot;.bas</failure>\n",
+                "    </testcase>\n",
+                "    <testcase name=\"Module1.bas\" classname=\"App\" file=\"/src/R&amp;D &lt;tools&gt;/Module1.bas\">\n",
+                "      <failure type=\"implicit-variant\" message=\"Variable &apos;Total&apos; is implicitly Variant; use &quot;As Long&quot; &amp; &lt;friends&gt;\">",
+                "/src/R&amp;D &lt;tools&gt;/Module1.bas:2:5: Variable &apos;Total&apos; is implicitly Variant; use &quot;As Long&quot; &amp; &lt;friends&gt;</failure>\n",
+                "      <failure type=\"unused-variable\" message=\"Variable &apos;Spare&apos; is never used\">",
+                "/src/R&amp;D &lt;tools&gt;/Module1.bas: Variable &apos;Spare&apos; is never used</failure>\n",
+                "    </testcase>\n",
+                "    <testcase name=\"frmMain.frm\" classname=\"App\" file=\"/src/R&amp;D &lt;tools&gt;/frmMain.frm\"/>\n",
+                "  </testsuite>\n",
+                "  <testsuite name=\"/src/Broken.vbp\" tests=\"1\" failures=\"1\" errors=\"0\" skipped=\"0\">\n",
+                "    <testcase name=\"Broken.vbp\" classname=\"Broken\" file=\"/src/Broken.vbp\">\n",
+                "      <failure type=\"unreadable-file\" message=\"Unable to read project\">",
+                "/src/Broken.vbp: Unable to read project</failure>\n",
+                "    </testcase>\n",
+                "  </testsuite>\n",
+                "</testsuites>",
+            )
+        );
+    }
+}
@@ -1,12 +1,56 @@
+mod baseline;
+mod callgraph;
+mod changes;
 mod check;
+mod code;
+mod compatibility;
+mod control_arrays;
+mod control_events;
+mod dead_code;
+mod declares;
+mod designer;
+mod diagnostic;
+mod error_handling;
+mod event_handlers;
+mod form_layout;
+mod forms;
+mod glob;
+mod implicit_variants;
+mod info;
+mod junit;
+mod member_access;
+mod project_settings;
+mod reachability;
+mod render;
+mod runtime;
+mod sarif;
+mod sources;
+mod suppression;
+mod tokens;
+mod typelib;
+mod undeclared;
+mod walk;
+mod xml;
 
+use callgraph::{callgraph_subcommand, GraphFormat};
+use changes::ChangedFiles;
 use check::check_subcommand;
+use declares::declares_subcommand;
+use diagnostic::OutputFormat;
+use error_handling::error_handling_subcommand;
+use forms::forms_subcommand;
+use glob::Glob;
+use info::{info_subcommand, InfoFormat};
+use render::render_form_subcommand;
+use runtime::builtins_subcommand;
+use typelib::TypeLibraries;
+use walk::WalkFilter;
 
 use anyhow::Result;
 
 use std::{env::current_dir, path::PathBuf};
 
-use clap::{command, value_parser, Arg, Command};
+use clap::{command, value_parser, Arg, ArgMatches, Command};
 
 fn main() -> Result<()> {
     let matches = command!()
@@ -53,22 +97,239 @@ fn main() -> Result<()> {
                         .action(clap::ArgAction::SetFalse)
                         .help("skip checking the references listed in the project"),
                 )
+                .arg(
+                    Arg::new("exclude")
+                        .short('e')
+                        .long("exclude")
+                        .required(false)
+                        .action(clap::ArgAction::Append)
+                        .value_name("GLOB")
+                        .help("skip files and directories matching this glob when searching a directory"),
+                )
+                .arg(
+                    Arg::new("include")
+                        .short('i')
+                        .long("include")
+                        .required(false)
+                        .action(clap::ArgAction::Append)
+                        .value_name("GLOB")
+                        .help("only check project files matching this glob when searching a directory"),
+                )
+                .arg(
+                    Arg::new("no ignore")
+                        .long("no-ignore")
+                        .required(false)
+                        .action(clap::ArgAction::SetTrue)
+                        .help("don't honour .gitignore and .aspenignore files when searching a directory"),
+                )
+                .arg(
+                    Arg::new("changed since")
+                        .long("changed-since")
+                        .required(false)
+                        .value_name("REV")
+                        .help("only check projects and files changed relative to this git revision"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .required(false)
+                        .value_name("FORMAT")
+                        .value_parser(value_parser!(OutputFormat))
+                        .default_value("text")
+                        .help("the format to report the results in"),
+                )
+                .arg(
+                    Arg::new("baseline")
+                        .long("baseline")
+                        .required(false)
+                        .value_name("FILE")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("only report and fail on findings not recorded in this baseline file"),
+                )
+                .arg(
+                    Arg::new("write baseline")
+                        .long("write-baseline")
+                        .required(false)
+                        .value_name("FILE")
+                        .value_parser(value_parser!(PathBuf))
+                        .conflicts_with("baseline")
+                        .help("record every current finding in this baseline file"),
+                )
+                .arg(
+                    Arg::new("type library")
+                        .short('t')
+                        .long("type-library")
+                        .required(false)
+                        .action(clap::ArgAction::Append)
+                        .value_name("PATH")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("a description of a referenced COM library, or a directory of them"),
+                )
                 .arg(
                     Arg::new("project path")
                         .required(false)
-                        .value_parser(value_parser!(PathBuf)),
+                        .num_args(0..)
+                        .value_parser(value_parser!(PathBuf))
+                        .help("projects, directories, .bas/.cls/.frm files, or globs to check [default: current directory]"),
+                ),
+        )
+        .subcommand(
+            Command::new("info")
+                .about("Show what was parsed from a project")
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .required(false)
+                        .value_name("FORMAT")
+                        .value_parser(value_parser!(InfoFormat))
+                        .default_value("text")
+                        .help("the format to show the project in"),
+                )
+                .arg(
+                    Arg::new("project path")
+                        .required(true)
+                        .value_parser(value_parser!(PathBuf))
+                        .help("the .vbp project file to show"),
+                ),
+        )
+        .subcommand(
+            Command::new("forms")
+                .about("Show the controls on forms")
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .required(false)
+                        .value_name("FORMAT")
+                        .value_parser(value_parser!(InfoFormat))
+                        .default_value("text")
+                        .help("the format to show the forms in"),
+                )
+                .arg(
+                    Arg::new("form path")
+                        .required(true)
+                        .num_args(1..)
+                        .value_parser(value_parser!(PathBuf))
+                        .help(".frm files, or .vbp projects to show every form of"),
+                ),
+        )
+        .subcommand(
+            Command::new("declares")
+                .about("List the DLL procedures declared with Declare statements")
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .required(false)
+                        .value_name("FORMAT")
+                        .value_parser(value_parser!(InfoFormat))
+                        .default_value("text")
+                        .help("the format to show the declarations in"),
+                )
+                .arg(
+                    Arg::new("path")
+                        .required(true)
+                        .num_args(1..)
+                        .value_parser(value_parser!(PathBuf))
+                        .help(".vbp projects, or .bas, .cls and .frm files"),
+                ),
+        )
+        .subcommand(
+            Command::new("render-form")
+                .about("Draw a form as an SVG wireframe")
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .required(false)
+                        .value_name("FILE")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("the SVG file to write [default: standard output]"),
+                )
+                .arg(
+                    Arg::new("form path")
+                        .required(true)
+                        .value_parser(value_parser!(PathBuf))
+                        .help("the .frm file to draw"),
+                ),
+        )
+        .subcommand(
+            Command::new("callgraph")
+                .about("Show which procedures call which across a project")
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .required(false)
+                        .value_name("FORMAT")
+                        .value_parser(value_parser!(GraphFormat))
+                        .default_value("dot")
+                        .help("the format to write the call graph in"),
+                )
+                .arg(
+                    Arg::new("path")
+                        .required(true)
+                        .num_args(1..)
+                        .value_parser(value_parser!(PathBuf))
+                        .help(".vbp projects, or .bas, .cls and .frm files"),
+                ),
+        )
+        .subcommand(
+            Command::new("error-handling")
+                .about("Show how each procedure handles errors, and what is wrong with it")
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .required(false)
+                        .value_name("FORMAT")
+                        .value_parser(value_parser!(InfoFormat))
+                        .default_value("text")
+                        .help("the format to show the audit in"),
+                )
+                .arg(
+                    Arg::new("path")
+                        .required(true)
+                        .num_args(1..)
+                        .value_parser(value_parser!(PathBuf))
+                        .help(".vbp projects, or .bas, .cls and .frm files"),
+                ),
+        )
+        .subcommand(
+            Command::new("builtins")
+                .about("Look up the functions, constants and objects VB6 provides")
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .required(false)
+                        .value_name("FORMAT")
+                        .value_parser(value_parser!(InfoFormat))
+                        .default_value("text")
+                        .help("the format to show the entries in"),
+                )
+                .arg(
+                    Arg::new("name")
+                        .required(false)
+                        .num_args(1..)
+                        .help("names such as Left$, VBA.Left or App.Path [default: everything]"),
                 ),
         )
         .arg_required_else_help(true)
         .get_matches();
 
     if let Some(matches) = matches.subcommand_matches("check") {
-        let current_dir = current_dir()?;
+        let mut check_paths: Vec<PathBuf> = matches
+            .get_many::<PathBuf>("project path")
+            .unwrap_or_default()
+            .cloned()
+            .collect();
 
-        let project_path = matches
-            .get_one::<PathBuf>("project path")
-            .unwrap_or(&current_dir)
-            .to_path_buf();
+        if check_paths.is_empty() {
+            check_paths.push(current_dir()?);
+        }
 
         let check_forms = *matches.get_one::<bool>("ignore forms").unwrap_or(&false);
         let check_modules = *matches.get_one::<bool>("ignore modules").unwrap_or(&false);
@@ -77,15 +338,147 @@ fn main() -> Result<()> {
             .get_one::<bool>("ignore references")
             .unwrap_or(&false);
 
+        let walk_filter = WalkFilter {
+            include: globs(matches, "include")?,
+            exclude: globs(matches, "exclude")?,
+            use_ignore_files: !matches.get_flag("no ignore"),
+        };
+
+        let type_library_paths: Vec<PathBuf> = matches
+            .get_many::<PathBuf>("type library")
+            .unwrap_or_default()
+            .cloned()
+            .collect();
+
+        let changed_files = match matches.get_one::<String>("changed since") {
+            Some(revision) => Some(ChangedFiles::since(&check_paths, revision)?),
+            None => None,
+        };
+
         let check_settings = check::CheckSettings {
-            project_path,
             check_forms,
             check_modules,
             check_classes,
             check_references,
+            walk_filter,
+            changed_files,
+            output_format: *matches
+                .get_one::<OutputFormat>("output")
+                .unwrap_or(&OutputFormat::Text),
+            baseline: matches.get_one::<PathBuf>("baseline").cloned(),
+            write_baseline: matches.get_one::<PathBuf>("write baseline").cloned(),
+            type_libraries: TypeLibraries::load(&type_library_paths)?,
+            repository_root: None,
         };
 
-        check_subcommand(check_settings)?;
+        if !check_subcommand(check_settings, check_paths)? {
+            std::process::exit(1);
+        }
+
+        return Ok(());
+    }
+
+    if let Some(matches) = matches.subcommand_matches("info") {
+        let project_path = matches
+            .get_one::<PathBuf>("project path")
+            .expect("project path is required");
+        let format = *matches
+            .get_one::<InfoFormat>("output")
+            .unwrap_or(&InfoFormat::Text);
+
+        return info_subcommand(project_path, format);
+    }
+
+    if let Some(matches) = matches.subcommand_matches("forms") {
+        let form_paths: Vec<PathBuf> = matches
+            .get_many::<PathBuf>("form path")
+            .unwrap_or_default()
+            .cloned()
+            .collect();
+        let format = *matches
+            .get_one::<InfoFormat>("output")
+            .unwrap_or(&InfoFormat::Text);
+
+        if !forms_subcommand(&form_paths, format)? {
+            std::process::exit(1);
+        }
+
+        return Ok(());
+    }
+
+    if let Some(matches) = matches.subcommand_matches("declares") {
+        let paths: Vec<PathBuf> = matches
+            .get_many::<PathBuf>("path")
+            .unwrap_or_default()
+            .cloned()
+            .collect();
+        let format = *matches
+            .get_one::<InfoFormat>("output")
+            .unwrap_or(&InfoFormat::Text);
+
+        if !declares_subcommand(&paths, format)? {
+            std::process::exit(1);
+        }
+
+        return Ok(());
+    }
+
+    if let Some(matches) = matches.subcommand_matches("render-form") {
+        let form_path = matches
+            .get_one::<PathBuf>("form path")
+            .expect("form path is required");
+        let output_path = matches.get_one::<PathBuf>("output");
+
+        return render_form_subcommand(form_path, output_path.map(PathBuf::as_path));
+    }
+
+    if let Some(matches) = matches.subcommand_matches("callgraph") {
+        let paths: Vec<PathBuf> = matches
+            .get_many::<PathBuf>("path")
+            .unwrap_or_default()
+            .cloned()
+            .collect();
+        let format = *matches
+            .get_one::<GraphFormat>("output")
+            .unwrap_or(&GraphFormat::Dot);
+
+        if !callgraph_subcommand(&paths, format)? {
+            std::process::exit(1);
+        }
+
+        return Ok(());
+    }
+
+    if let Some(matches) = matches.subcommand_matches("error-handling") {
+        let paths: Vec<PathBuf> = matches
+            .get_many::<PathBuf>("path")
+            .unwrap_or_default()
+            .cloned()
+            .collect();
+        let format = *matches
+            .get_one::<InfoFormat>("output")
+            .unwrap_or(&InfoFormat::Text);
+
+        if !error_handling_subcommand(&paths, format)? {
+            std::process::exit(1);
+        }
+
+        return Ok(());
+    }
+
+    if let Some(matches) = matches.subcommand_matches("builtins") {
+        let names: Vec<String> = matches
+            .get_many::<String>("name")
+            .unwrap_or_default()
+            .cloned()
+            .collect();
+        let format = *matches
+            .get_one::<InfoFormat>("output")
+            .unwrap_or(&InfoFormat::Text);
+
+        if !builtins_subcommand(&names, format)? {
+            std::process::exit(1);
+        }
 
         return Ok(());
     }
@@ -94,3 +487,11 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+fn globs(matches: &ArgMatches, id: &str) -> Result<Vec<Glob>> {
+    matches
+        .get_many::<String>(id)
+        .unwrap_or_default()
+        .map(|pattern| Glob::new(pattern))
+        .collect()
+}
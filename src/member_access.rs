@@ -0,0 +1,262 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::check::SourceFileKind;
+use crate::code::identifier_key;
+use crate::designer::{parse_designer, Control};
+use crate::diagnostic::{Diagnostic, DiagnosticKind, Region};
+use crate::runtime::class_members;
+use crate::sources::{SourceFile, Sources};
+use crate::typelib::{find_class, Interface, MemberKind, TypeLibrary};
+
+/// The class of a variable or control from a referenced library.
+#[derive(Clone, Copy)]
+struct ObjectType<'a> {
+    library: &'a TypeLibrary,
+    class: &'a Interface,
+    /// Controls also have the properties and methods VB6 adds to every
+    /// control, such as `Left` and `SetFocus`.
+    is_control: bool,
+}
+
+impl ObjectType<'_> {
+    fn name(&self) -> String {
+        match &self.library.name {
+            Some(library) => format!("{}.{}", library, self.class.name),
+            None => self.class.name.clone(),
+        }
+    }
+
+    fn has_member(&self, name: &str) -> bool {
+        let is_class_member = self.class.members.iter().any(|member| {
+            member.kind != MemberKind::Event && member.name.eq_ignore_ascii_case(name)
+        });
+        let is_extender_member = self.is_control
+            && class_members("VBControlExtender")
+                .iter()
+                .any(|member| member.name.eq_ignore_ascii_case(name));
+
+        is_class_member || is_extender_member
+    }
+}
+
+/// Names by their key, and their class if it comes from a referenced
+/// library. `None` hides a name of the same key from an outer scope.
+type Types<'a> = HashMap<String, Option<ObjectType<'a>>>;
+
+/// Finds properties and methods used on variables and controls whose class
+/// comes from a referenced COM library, but which that class doesn't have.
+///
+/// Only the first member after a variable or control is checked, as in
+/// `rs.Fields`, since the type of anything further along depends on what
+/// that member returns.
+pub fn find_unknown_members(sources: &Sources, libraries: &[&TypeLibrary]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    if libraries.is_empty() {
+        return diagnostics;
+    }
+
+    // Classes of the project hide library classes of the same name.
+    let resolve = |type_name: &str, is_control: bool| {
+        if sources
+            .files
+            .iter()
+            .any(|file| file.name.eq_ignore_ascii_case(type_name))
+        {
+            return None;
+        }
+
+        find_class(libraries, type_name).map(|(library, class)| ObjectType {
+            library,
+            class,
+            is_control,
+        })
+    };
+
+    for file in &sources.files {
+        let file_types = file_types(file, &resolve);
+
+        for procedure in &file.code.procedures {
+            let mut types = file_types.clone();
+
+            let parameters = procedure.parameters.iter().map(|parameter| {
+                let is_object = !parameter.is_array && !parameter.param_array;
+                (
+                    &parameter.name,
+                    parameter.type_name.as_deref().filter(|_| is_object),
+                )
+            });
+            let local_variables = procedure.local_variables();
+            let variables = local_variables.iter().map(|variable| {
+                let type_name = variable.type_name.as_deref();
+                (&variable.name, type_name.filter(|_| !variable.is_array))
+            });
+
+            for (name, type_name) in parameters.chain(variables) {
+                types.insert(
+                    identifier_key(name),
+                    type_name.and_then(|type_name| resolve(type_name, false)),
+                );
+            }
+
+            let mut reported = HashSet::new();
+
+            for statement in &procedure.body {
+                let tokens = &statement.tokens;
+                for (position, window) in tokens.windows(3).enumerate() {
+                    let [object, dot, member] = window else {
+                        continue;
+                    };
+
+                    let previous = position.checked_sub(1).map(|position| &tokens[position]);
+                    let is_member =
+                        previous.is_some_and(|previous| previous == "." || previous == "!");
+                    if dot != "." || is_member {
+                        continue;
+                    }
+
+                    let Some(Some(object_type)) = types.get(&identifier_key(object)) else {
+                        continue;
+                    };
+                    if object_type.has_member(member)
+                        || !reported.insert((identifier_key(object), identifier_key(member)))
+                    {
+                        continue;
+                    }
+
+                    diagnostics.push(
+                        Diagnostic::new(
+                            DiagnosticKind::UnknownMember,
+                            &file.path,
+                            format!(
+                                "'{}' is not a member of '{}' in '{}.{}'",
+                                member,
+                                object_type.name(),
+                                object,
+                                member
+                            ),
+                        )
+                        .with_region(Some(Region {
+                            line: statement.line,
+                            column: 1,
+                        })),
+                    );
+                }
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// The classes of a file's module level variables, and of the controls on
+/// forms.
+fn file_types<'a>(
+    file: &SourceFile,
+    resolve: &impl Fn(&str, bool) -> Option<ObjectType<'a>>,
+) -> Types<'a> {
+    let mut types = Types::new();
+
+    for variable in &file.code.variables {
+        let type_name = variable.type_name.as_deref().filter(|_| !variable.is_array);
+        types.insert(
+            identifier_key(&variable.name),
+            type_name.and_then(|type_name| resolve(type_name, false)),
+        );
+    }
+
+    if file.kind == SourceFileKind::Form {
+        if let Ok(form) = parse_designer(&file.contents) {
+            insert_controls(&mut types, &form, resolve);
+        }
+    }
+
+    types
+}
+
+fn insert_controls<'a>(
+    types: &mut Types<'a>,
+    container: &Control,
+    resolve: &impl Fn(&str, bool) -> Option<ObjectType<'a>>,
+) {
+    for control in &container.children {
+        // Members of control arrays are only reached through an index.
+        let object_type = match control.property("Index") {
+            Some(_) => None,
+            None => resolve(&control.type_name, true),
+        };
+        types.insert(identifier_key(&control.name), object_type);
+        insert_controls(types, control, resolve);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::typelib::{LibraryReference, TypeLibraries};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn members_missing_from_referenced_classes_are_found() {
+        let form = SourceFile::new(
+            SourceFileKind::Form,
+            Path::new("frmMain.frm"),
+            concat!(
+                "VERSION 5.00\r\n",
+                "Begin VB.Form frmMain \r\n",
+                "   Begin MSComctlLib.ListView lvwItems \r\n",
+                "   End\r\n",
+                "End\r\n",
+                "Attribute VB_Name = \"frmMain\"\r\n",
+                "Private Connection As ADODB.Connection\r\n",
+                "Private Sub Form_Load()\r\n",
+                "  Dim rs As New ADODB.Recordset, Items As Object\r\n",
+                "  rs.Open \"Items\", Connection\r\n",
+                "  Debug.Print rs.Fileds(0).Value, rs.Fields.Count, Items.Anything\r\n",
+                "  Connection.Exceute \"DELETE FROM Items\"\r\n",
+                "  lvwItems.ListItems.Clear\r\n",
+                "  lvwItems.Move 0, 0\r\n",
+                "  lvwItems.Colums.Clear\r\n",
+                "End Sub\r\n",
+            )
+            .as_bytes()
+            .to_vec(),
+        );
+
+        let sources = Sources {
+            project_path: Some(PathBuf::from("App.vbp")),
+            startup: None,
+            is_activex: false,
+            references: Vec::new(),
+            files: vec![form],
+        };
+
+        let type_libraries = TypeLibraries::load(&[]).unwrap();
+        let libraries = type_libraries.referenced(&[
+            LibraryReference {
+                guid: "{2A75196C-D9EB-4129-B803-931327F72D5C}".to_owned(),
+                version: "2.8".to_owned(),
+                name: "Microsoft ActiveX Data Objects 2.8 Library".to_owned(),
+            },
+            LibraryReference {
+                guid: "{831fdd16-0c5c-11d2-a9fc-0000f8754da1}".to_owned(),
+                version: "2.0".to_owned(),
+                name: "MSCOMCTL.OCX".to_owned(),
+            },
+        ]);
+        assert_eq!(libraries.len(), 2);
+
+        let messages: Vec<String> = find_unknown_members(&sources, &libraries)
+            .into_iter()
+            .map(|diagnostic| format!("{} {}", diagnostic.region.unwrap().line, diagnostic.message))
+            .collect();
+
+        assert_eq!(
+            messages,
+            [
+                "11 'Fileds' is not a member of 'ADODB.Recordset' in 'rs.Fileds'",
+                "12 'Exceute' is not a member of 'ADODB.Connection' in 'Connection.Exceute'",
+                "15 'Colums' is not a member of 'MSComctlLib.ListView' in 'lvwItems.Colums'",
+            ]
+        );
+    }
+}
@@ -0,0 +1,703 @@
+use std::path::Path;
+
+use vb6parse::parsers::project::CompatibilityMode;
+use vb6parse::parsers::{CompileTargetType, VB6Project};
+
+use crate::check::{join_parent_project_path, project_directory};
+use crate::compatibility::check_binary_compatibility;
+use crate::diagnostic::{Diagnostic, DiagnosticKind, Region};
+use crate::walk::absolute_path;
+
+/// The largest major, minor, or revision number VB6 accepts.
+const MAX_VERSION_NUMBER: u16 = 9999;
+
+const VERSION_SETTINGS: [&str; 3] = ["MajorVer", "MinorVer", "RevisionVer"];
+
+/// Characters Windows doesn't allow in file names.
+const INVALID_PATH_CHARACTERS: [char; 6] = ['<', '>', '"', '|', '?', '*'];
+
+/// Checks the settings of a parsed project for misconfigurations the parser
+/// accepts but that break the build, such as a startup object that doesn't
+/// exist.
+///
+/// `project_contents` is the text the project was parsed from and is only
+/// used to point findings at the line of the setting they are about.
+pub fn validate_project(
+    project_path: &Path,
+    project_contents: &[u8],
+    project: &VB6Project,
+    repository_root: Option<&Path>,
+) -> Vec<Diagnostic> {
+    let settings = ProjectSettings {
+        project_path,
+        project_contents,
+        project,
+        repository_root,
+    };
+
+    let mut diagnostics = Vec::new();
+    settings.validate_startup(&mut diagnostics);
+    settings.validate_output_paths(&mut diagnostics);
+    settings.validate_project_type(&mut diagnostics);
+    settings.validate_version(&mut diagnostics);
+    settings.validate_compatibility(&mut diagnostics);
+
+    diagnostics
+}
+
+struct ProjectSettings<'a> {
+    project_path: &'a Path,
+    project_contents: &'a [u8],
+    project: &'a VB6Project<'a>,
+    /// The root of the git repository the project is in, if it's in one.
+    repository_root: Option<&'a Path>,
+}
+
+impl ProjectSettings<'_> {
+    fn diagnostic(&self, kind: DiagnosticKind, setting: &str, message: String) -> Diagnostic {
+        Diagnostic::new(kind, self.project_path, message)
+            .with_region(find_setting_region(self.project_contents, setting))
+    }
+
+    fn validate_startup(&self, diagnostics: &mut Vec<Diagnostic>) {
+        // vb6parse leaves an absent Startup line as an empty string, which VB6
+        // resolves itself, while "(None)" explicitly has no startup object.
+        let Some(startup) = self.project.startup.map(|startup| startup.to_string()) else {
+            if self.project.project_type == CompileTargetType::Exe {
+                diagnostics.push(
+                    self.diagnostic(
+                        DiagnosticKind::InvalidStartup,
+                        "Type",
+                        "A Standard EXE needs a startup form or Sub Main, but Startup is not set"
+                            .to_owned(),
+                    ),
+                );
+            }
+            return;
+        };
+
+        if startup.is_empty() {
+            return;
+        }
+
+        let project_directory = project_directory(self.project_path);
+
+        if startup.eq_ignore_ascii_case("Sub Main") {
+            let has_sub_main = self.project.modules.iter().any(|module| {
+                let module_path =
+                    join_parent_project_path(project_directory, &module.path.to_string());
+
+                std::fs::read(module_path)
+                    .map(|contents| declares_sub_main(&contents))
+                    .unwrap_or(false)
+            });
+
+            if !has_sub_main {
+                diagnostics.push(
+                    self.diagnostic(
+                        DiagnosticKind::InvalidStartup,
+                        "Startup",
+                        "Startup is Sub Main, but no module in the project declares Sub Main"
+                            .to_owned(),
+                    ),
+                );
+            }
+
+            return;
+        }
+
+        let form_names: Vec<String> = self
+            .project
+            .forms
+            .iter()
+            .filter_map(|form| {
+                let form_path = join_parent_project_path(project_directory, &form.to_string());
+                std::fs::read(form_path)
+                    .ok()
+                    .and_then(|contents| attribute_value(&contents, "VB_Name"))
+            })
+            .collect();
+
+        // Forms that couldn't be read are reported as missing or unreadable,
+        // and the startup form may well be one of them.
+        if form_names.len() != self.project.forms.len() {
+            return;
+        }
+
+        if !form_names
+            .iter()
+            .any(|form_name| form_name.eq_ignore_ascii_case(&startup))
+        {
+            diagnostics.push(self.diagnostic(
+                DiagnosticKind::InvalidStartup,
+                "Startup",
+                format!(
+                    "Startup is '{}', but the project has no form of that name",
+                    startup
+                ),
+            ));
+        }
+    }
+
+    fn validate_output_paths(&self, diagnostics: &mut Vec<Diagnostic>) {
+        // Unset names are empty, and VB6 names the output after the project.
+        let exe_name = self
+            .project
+            .exe_32_file_name
+            .map(|name| name.to_string())
+            .filter(|name| !name.is_empty());
+
+        if let Some(exe_name) = exe_name {
+            let expected_extension = match self.project.project_type {
+                CompileTargetType::Exe | CompileTargetType::OleExe => "exe",
+                CompileTargetType::OleDll => "dll",
+                CompileTargetType::Control => "ocx",
+            };
+
+            let extension = Path::new(&exe_name)
+                .extension()
+                .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+
+            let problem = if exe_name.contains(['\\', '/', ':']) {
+                Some(format!(
+                    "ExeName32 '{}' should be a file name, with its directory set by Path32",
+                    exe_name
+                ))
+            } else if exe_name.contains(INVALID_PATH_CHARACTERS) {
+                Some(format!(
+                    "ExeName32 '{}' contains characters that aren't allowed in file names",
+                    exe_name
+                ))
+            } else if extension.as_deref() != Some(expected_extension) {
+                Some(format!(
+                    "ExeName32 '{}' should have a .{} extension for a project of type {}",
+                    exe_name,
+                    expected_extension,
+                    project_type_name(&self.project.project_type)
+                ))
+            } else {
+                None
+            };
+
+            if let Some(problem) = problem {
+                diagnostics.push(self.diagnostic(
+                    DiagnosticKind::InvalidOutputPath,
+                    "ExeName32",
+                    problem,
+                ));
+            }
+        }
+
+        if let Some(path_32) = self.project.path_32.map(|path| path.to_string()) {
+            let problem = if path_32.contains(INVALID_PATH_CHARACTERS) {
+                Some(format!(
+                    "Path32 '{}' contains characters that aren't allowed in paths",
+                    path_32
+                ))
+            } else if has_misplaced_colon(&path_32) {
+                Some(format!(
+                    "Path32 '{}' has a ':' outside of a drive letter",
+                    path_32
+                ))
+            } else if is_absolute_windows_path(&path_32) {
+                // Absolute paths are fine, and can only be checked on the
+                // machine that builds the project.
+                None
+            } else {
+                let directory =
+                    join_parent_project_path(project_directory(self.project_path), &path_32);
+
+                // A missing directory may well be created by the build, but
+                // one that is a file can never hold the output.
+                if directory.is_file() {
+                    Some(format!(
+                        "Path32 '{}' is a file, not a directory: {}",
+                        path_32,
+                        directory.display()
+                    ))
+                } else {
+                    None
+                }
+            };
+
+            if let Some(problem) = problem {
+                diagnostics.push(self.diagnostic(
+                    DiagnosticKind::InvalidOutputPath,
+                    "Path32",
+                    problem,
+                ));
+            }
+        }
+    }
+
+    fn validate_project_type(&self, diagnostics: &mut Vec<Diagnostic>) {
+        let project_directory = project_directory(self.project_path);
+
+        let exposed_classes: Vec<Option<(String, bool)>> = self
+            .project
+            .classes
+            .iter()
+            .map(|class| {
+                let class_path =
+                    join_parent_project_path(project_directory, &class.path.to_string());
+                let contents = std::fs::read(class_path).ok()?;
+                let is_exposed = attribute_value(&contents, "VB_Exposed")
+                    .is_some_and(|value| value.eq_ignore_ascii_case("True"));
+
+                Some((class.path.to_string(), is_exposed))
+            })
+            .collect();
+
+        match self.project.project_type {
+            CompileTargetType::OleDll | CompileTargetType::OleExe => {
+                // Classes that couldn't be read may be the public ones.
+                let all_read = exposed_classes.iter().all(Option::is_some);
+                let any_exposed = exposed_classes
+                    .iter()
+                    .flatten()
+                    .any(|(_, is_exposed)| *is_exposed);
+
+                if all_read && !any_exposed {
+                    diagnostics.push(self.diagnostic(
+                        DiagnosticKind::ProjectTypeMismatch,
+                        "Type",
+                        format!(
+                            "An {} project needs at least one public class, but has none",
+                            project_type_name(&self.project.project_type)
+                        ),
+                    ));
+                }
+            }
+            CompileTargetType::Control => {
+                if self.project.user_controls.is_empty() {
+                    diagnostics.push(self.diagnostic(
+                        DiagnosticKind::ProjectTypeMismatch,
+                        "Type",
+                        "An ActiveX Control project needs at least one user control, but has none"
+                            .to_owned(),
+                    ));
+                }
+            }
+            CompileTargetType::Exe => {
+                for (class_path, _) in exposed_classes
+                    .into_iter()
+                    .flatten()
+                    .filter(|(_, is_exposed)| *is_exposed)
+                {
+                    diagnostics.push(
+                        Diagnostic::new(
+                            DiagnosticKind::ProjectTypeMismatch,
+                            self.project_path,
+                            format!(
+                                "Class '{}' is public, but a Standard EXE can't expose public classes",
+                                class_path
+                            ),
+                        )
+                        .with_region(find_member_region(self.project_contents, &class_path)),
+                    );
+                }
+            }
+        }
+    }
+
+    fn validate_version(&self, diagnostics: &mut Vec<Diagnostic>) {
+        let version_info = &self.project.version_info;
+
+        for (setting, value) in VERSION_SETTINGS.iter().zip([
+            version_info.major,
+            version_info.minor,
+            version_info.revision,
+        ]) {
+            if value > MAX_VERSION_NUMBER {
+                diagnostics.push(self.diagnostic(
+                    DiagnosticKind::InvalidVersion,
+                    setting,
+                    format!(
+                        "{} is {}, but version numbers can't be greater than {}",
+                        setting, value, MAX_VERSION_NUMBER
+                    ),
+                ));
+            }
+        }
+
+        let counts = VERSION_SETTINGS.map(|setting| {
+            setting_lines(self.project_contents)
+                .filter(|(_, key, _)| key.eq_ignore_ascii_case(setting))
+                .count()
+        });
+
+        for (setting, count) in VERSION_SETTINGS.iter().zip(counts) {
+            if count > 1 {
+                diagnostics.push(self.diagnostic(
+                    DiagnosticKind::InvalidVersion,
+                    setting,
+                    format!("{} is set {} times", setting, count),
+                ));
+            }
+        }
+
+        // VB6 always writes all three together, so one going missing means
+        // the file was edited by hand and the version is probably wrong.
+        if counts.contains(&0) && counts.iter().any(|&count| count > 0) {
+            let missing: Vec<&str> = VERSION_SETTINGS
+                .iter()
+                .zip(counts)
+                .filter(|(_, count)| *count == 0)
+                .map(|(setting, _)| *setting)
+                .collect();
+
+            let present = VERSION_SETTINGS
+                .iter()
+                .zip(counts)
+                .find(|(_, count)| *count > 0)
+                .map(|(setting, _)| *setting)
+                .unwrap_or(VERSION_SETTINGS[0]);
+
+            diagnostics.push(self.diagnostic(
+                DiagnosticKind::InvalidVersion,
+                present,
+                format!(
+                    "The version is missing {}, which defaults to 0",
+                    missing.join(" and ")
+                ),
+            ));
+        }
+    }
+
+    fn validate_compatibility(&self, diagnostics: &mut Vec<Diagnostic>) {
+        // Only ActiveX projects have an interface to keep compatible.
+        if self.project.project_type == CompileTargetType::Exe
+            || self.project.compatibility_mode == CompatibilityMode::NoCompatibility
+        {
+            return;
+        }
+
+        let compatible_exe = self
+            .project
+            .exe_32_compatible
+            .map(|path| path.to_string())
+            .filter(|path| !path.trim().is_empty());
+
+        let Some(compatible_exe) = compatible_exe else {
+            if self.project.compatibility_mode == CompatibilityMode::CompatibleExe {
+                diagnostics.push(self.diagnostic(
+                    DiagnosticKind::MissingCompatibleBinary,
+                    "CompatibleMode",
+                    "Binary compatibility is enabled, but CompatibleEXE32 is not set".to_owned(),
+                ));
+            }
+            return;
+        };
+
+        let compatible_path =
+            join_parent_project_path(project_directory(self.project_path), &compatible_exe);
+
+        if std::fs::metadata(&compatible_path).is_err() {
+            diagnostics.push(self.diagnostic(
+                DiagnosticKind::MissingCompatibleBinary,
+                "CompatibleEXE32",
+                format!("CompatibleEXE32 not found: {}", compatible_path.display()),
+            ));
+        } else if let Some(repository_root) = self.repository_root {
+            // The binary has to be committed for every checkout to build
+            // against it, not just sit on the machine that set it. Both
+            // paths are canonicalized so symlinks, `..` and the `\\?\`
+            // prefix Windows adds compare alike.
+            let canonical =
+                |path: &Path| path.canonicalize().unwrap_or_else(|_| absolute_path(path));
+
+            if !canonical(&compatible_path).starts_with(canonical(repository_root)) {
+                diagnostics.push(self.diagnostic(
+                    DiagnosticKind::MissingCompatibleBinary,
+                    "CompatibleEXE32",
+                    format!(
+                        "CompatibleEXE32 '{}' is outside the repository at '{}'",
+                        compatible_path.display(),
+                        repository_root.display()
+                    ),
+                ));
+            }
+        }
+
+        let is_activex_binary = matches!(
+            self.project.project_type,
+            CompileTargetType::OleDll | CompileTargetType::OleExe
+        );
+
+        if is_activex_binary && self.project.compatibility_mode == CompatibilityMode::CompatibleExe
+        {
+            diagnostics.extend(check_binary_compatibility(
+                self.project_path,
+                self.project,
+                &compatible_path,
+                find_setting_region(self.project_contents, "CompatibleEXE32"),
+            ));
+        }
+    }
+}
+
+/// The name VB6 shows for each project type.
+pub fn project_type_name(project_type: &CompileTargetType) -> &'static str {
+    match project_type {
+        CompileTargetType::Exe => "Standard EXE",
+        CompileTargetType::OleExe => "ActiveX EXE",
+        CompileTargetType::OleDll => "ActiveX DLL",
+        CompileTargetType::Control => "ActiveX Control",
+    }
+}
+
+/// Iterates over the `Key=Value` lines of a project file, with their 1-based
+/// line numbers.
+fn setting_lines(project_contents: &[u8]) -> impl Iterator<Item = (usize, String, String)> + '_ {
+    project_contents
+        .split(|&byte| byte == b'\n')
+        .enumerate()
+        .filter_map(|(line_index, line)| {
+            let line = String::from_utf8_lossy(line);
+            let (key, value) = line.split_once('=')?;
+
+            Some((
+                line_index + 1,
+                key.trim().to_owned(),
+                value.trim().to_owned(),
+            ))
+        })
+}
+
+/// Finds the line a setting is on, so findings about it point there.
+fn find_setting_region(project_contents: &[u8], setting: &str) -> Option<Region> {
+    setting_lines(project_contents)
+        .find(|(_, key, _)| key.eq_ignore_ascii_case(setting))
+        .map(|(line, _, _)| Region { line, column: 1 })
+}
+
+/// Finds the line listing a project member by its path.
+fn find_member_region(project_contents: &[u8], member_path: &str) -> Option<Region> {
+    setting_lines(project_contents)
+        .find(|(_, _, value)| {
+            value
+                .to_ascii_lowercase()
+                .ends_with(&member_path.to_ascii_lowercase())
+        })
+        .map(|(line, _, _)| Region { line, column: 1 })
+}
+
+/// Reads the value of an `Attribute <name> = <value>` line from the header of
+/// a class, module, or form, without the quotes around strings.
+pub fn attribute_value(contents: &[u8], name: &str) -> Option<String> {
+    String::from_utf8_lossy(contents).lines().find_map(|line| {
+        let mut words = line.trim().splitn(2, char::is_whitespace);
+
+        if !words.next()?.eq_ignore_ascii_case("Attribute") {
+            return None;
+        }
+
+        let (key, value) = words.next()?.split_once('=')?;
+        if !key.trim().eq_ignore_ascii_case(name) {
+            return None;
+        }
+
+        Some(value.trim().trim_matches('"').to_owned())
+    })
+}
+
+/// Checks if a module declares a `Sub Main` procedure.
+fn declares_sub_main(contents: &[u8]) -> bool {
+    String::from_utf8_lossy(contents).lines().any(|line| {
+        let words: Vec<String> = line
+            .split(|character: char| character.is_whitespace() || character == '(')
+            .filter(|word| !word.is_empty())
+            .map(str::to_ascii_lowercase)
+            .skip_while(|word| matches!(word.as_str(), "public" | "private" | "friend" | "static"))
+            .take(2)
+            .collect();
+
+        words == ["sub", "main"]
+    })
+}
+
+/// Checks for a ':' anywhere but straight after a leading drive letter.
+fn has_misplaced_colon(path: &str) -> bool {
+    let drive_colon = is_absolute_windows_path(path) && !path.starts_with("\\\\");
+
+    path.char_indices()
+        .any(|(position, character)| character == ':' && !(drive_colon && position == 1))
+}
+
+/// Checks for a drive letter (`C:`) or UNC (`\\server`) path.
+fn is_absolute_windows_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+
+    path.starts_with("\\\\")
+        || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Validates `project_text` as `Project1.vbp` in a scratch directory
+    /// holding `files`, returning each finding as "rule-id: message".
+    fn validate(test_name: &str, project_text: &str, files: &[(&str, &str)]) -> Vec<String> {
+        let directory = std::env::temp_dir()
+            .join(format!("aspen-project-settings-{}", std::process::id()))
+            .join(test_name);
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+
+        for (name, contents) in files {
+            std::fs::write(directory.join(name), contents).unwrap();
+        }
+
+        let project_path = directory.join("Project1.vbp");
+        let project =
+            VB6Project::parse("Project1.vbp".to_owned(), project_text.as_bytes()).unwrap();
+
+        validate_project(&project_path, project_text.as_bytes(), &project, None)
+            .into_iter()
+            .map(|diagnostic| format!("{}: {}", diagnostic.kind.id(), diagnostic.message))
+            .collect()
+    }
+
+    #[test]
+    fn startup_must_exist() {
+        let findings = validate(
+            "startup_must_exist",
+            "Type=Exe\r\nModule=Module1; Module1.bas\r\nStartup=\"Sub Main\"\r\n",
+            &[(
+                "Module1.bas",
+                "Attribute VB_Name = \"Module1\"\r\nPrivate Sub Helper()\r\nEnd Sub\r\n",
+            )],
+        );
+        assert_eq!(
+            findings,
+            ["invalid-startup: Startup is Sub Main, but no module in the project declares Sub Main"]
+        );
+
+        let findings = validate(
+            "startup_form_exists",
+            "Type=Exe\r\nForm=Main.frm\r\nModule=Module1; Module1.bas\r\nStartup=\"frmMain\"\r\n",
+            &[
+                (
+                    "Main.frm",
+                    "VERSION 5.00\r\nAttribute VB_Name = \"frmMain\"\r\n",
+                ),
+                ("Module1.bas", "Public Sub Main()\r\nEnd Sub\r\n"),
+            ],
+        );
+        assert!(findings.is_empty(), "{:?}", findings);
+    }
+
+    #[test]
+    fn settings_must_suit_the_project_type() {
+        let findings = validate(
+            "settings_must_suit_the_project_type",
+            concat!(
+                "Type=OleDll\r\n",
+                "Class=Widget; Widget.cls\r\n",
+                "ExeName32=\"Widgets.exe\"\r\n",
+                "Path32=\"C:\\Build\"\r\n",
+                "CompatibleMode=\"2\"\r\n",
+                "CompatibleEXE32=\"Compat\\Widgets.dll\"\r\n",
+                "MajorVer=1\r\n",
+                "MinorVer=12000\r\n",
+            ),
+            &[(
+                "Widget.cls",
+                "Attribute VB_Name = \"Widget\"\r\nAttribute VB_Exposed = False\r\n",
+            )],
+        );
+
+        assert_eq!(findings.len(), 5, "{:?}", findings);
+        assert!(findings[0]
+            .starts_with("invalid-output-path: ExeName32 'Widgets.exe' should have a .dll"));
+        assert!(findings[1].starts_with(
+            "project-type-mismatch: An ActiveX DLL project needs at least one public class"
+        ));
+        assert!(findings[2].starts_with("invalid-version: MinorVer is 12000"));
+        assert!(findings[3].starts_with("invalid-version: The version is missing RevisionVer"));
+        assert!(findings[4].starts_with("missing-compatible-binary: CompatibleEXE32 not found"));
+    }
+
+    #[test]
+    fn only_unusable_output_paths_are_reported() {
+        let path_32_findings = |test_name: &str, path_32: &str| {
+            let project_text = format!("Type=Exe\r\nPath32=\"{}\"\r\n", path_32);
+            validate(test_name, &project_text, &[("Build.txt", "")])
+                .into_iter()
+                .filter(|finding| finding.starts_with("invalid-output-path"))
+                .collect::<Vec<_>>()
+        };
+
+        for (test_name, path_32) in [
+            ("path_32_drive", "C:\\Build\\Release"),
+            ("path_32_unc", "\\\\server\\builds"),
+            ("path_32_relative", "..\\Build"),
+            ("path_32_missing", "bin"),
+        ] {
+            let findings = path_32_findings(test_name, path_32);
+            assert!(findings.is_empty(), "{}: {:?}", path_32, findings);
+        }
+
+        assert_eq!(
+            path_32_findings("path_32_colon", "Build:Release"),
+            ["invalid-output-path: Path32 'Build:Release' has a ':' outside of a drive letter"]
+        );
+        assert_eq!(
+            path_32_findings("path_32_characters", "Build|Release"),
+            ["invalid-output-path: Path32 'Build|Release' contains characters that aren't allowed in paths"]
+        );
+
+        let findings = path_32_findings("path_32_file", "Build.txt");
+        assert_eq!(findings.len(), 1, "{:?}", findings);
+        assert!(findings[0]
+            .starts_with("invalid-output-path: Path32 'Build.txt' is a file, not a directory"));
+    }
+
+    #[test]
+    fn compatible_binaries_must_be_in_the_repository() {
+        let directory = std::env::temp_dir()
+            .join(format!("aspen-project-settings-{}", std::process::id()))
+            .join("compatible_binaries_must_be_in_the_repository");
+        let _ = std::fs::remove_dir_all(&directory);
+
+        let repository = directory.join("repository");
+        std::fs::create_dir_all(repository.join("Compat")).unwrap();
+        std::fs::create_dir_all(directory.join("Elsewhere")).unwrap();
+        std::fs::write(repository.join("Compat").join("Widgets.dll"), "").unwrap();
+        std::fs::write(directory.join("Elsewhere").join("Widgets.dll"), "").unwrap();
+
+        let project_path = repository.join("Widgets.vbp");
+        // Spelled differently from the project's path, as git may report it.
+        let repository_root = directory.join("Elsewhere").join("..").join("repository");
+
+        let findings = |compatible_exe: &str| {
+            let project_text = format!(
+                "Type=OleDll\r\nCompatibleMode=\"1\"\r\nCompatibleEXE32=\"{}\"\r\n",
+                compatible_exe
+            );
+            let project =
+                VB6Project::parse("Widgets.vbp".to_owned(), project_text.as_bytes()).unwrap();
+
+            validate_project(
+                &project_path,
+                project_text.as_bytes(),
+                &project,
+                Some(&repository_root),
+            )
+            .into_iter()
+            .filter(|diagnostic| diagnostic.kind == DiagnosticKind::MissingCompatibleBinary)
+            .map(|diagnostic| diagnostic.message)
+            .collect::<Vec<_>>()
+        };
+
+        assert!(findings("Compat\\Widgets.dll").is_empty());
+
+        let outside = findings("..\\Elsewhere\\Widgets.dll");
+        assert_eq!(outside.len(), 1, "{:?}", outside);
+        assert!(outside[0].starts_with("CompatibleEXE32 '"));
+        assert!(outside[0].contains("is outside the repository at"));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,200 @@
+use crate::callgraph::CallGraph;
+use crate::check::SourceFileKind;
+use crate::code::Visibility;
+use crate::diagnostic::{Diagnostic, DiagnosticKind, Region};
+use crate::project_settings::attribute_value;
+use crate::sources::Sources;
+
+/// Finds procedures that can't run, because they aren't called, even
+/// indirectly, from anything the program starts from.
+///
+/// The roots are the startup form or `Sub Main`, every event handler, as VB6
+/// calls those whenever their object raises the event, and in ActiveX
+/// projects the public members of the classes other programs can use.
+///
+/// Unlike unused procedures, unreachable ones may be called, but only by each
+/// other or by other unreachable procedures.
+pub fn find_unreachable_code(sources: &Sources) -> Vec<Diagnostic> {
+    // Without a startup object there's nothing to start from, which is
+    // reported as an invalid startup.
+    if sources.project_path.is_none() || (sources.startup.is_none() && !sources.is_activex) {
+        return Vec::new();
+    }
+
+    let graph = CallGraph::build(sources);
+
+    let roots = graph
+        .nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, node)| {
+            let is_public_member = sources.is_activex
+                && node.file.kind == SourceFileKind::Class
+                && node.procedure.visibility == Visibility::Public
+                && attribute_value(&node.file.contents, "VB_Exposed")
+                    .is_some_and(|value| value.eq_ignore_ascii_case("True"));
+
+            node.is_entry_point || node.is_event_handler || is_public_member
+        })
+        .map(|(index, _)| index);
+
+    let reachable = graph.reachable_from(roots);
+
+    graph
+        .nodes
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !reachable[*index])
+        .map(|(index, node)| {
+            let callers: Vec<String> = graph
+                .edges
+                .iter()
+                .filter(|edge| edge.to == index && edge.from != index)
+                .map(|edge| format!("'{}'", graph.nodes[edge.from].id()))
+                .collect();
+
+            let message = if callers.is_empty() {
+                format!(
+                    "Procedure '{}' is never reached from an entry point",
+                    node.procedure.name
+                )
+            } else {
+                format!(
+                    "Procedure '{}' is only called from unreachable procedures {}",
+                    node.procedure.name,
+                    callers.join(", ")
+                )
+            };
+
+            Diagnostic::new(
+                DiagnosticKind::UnreachableProcedure,
+                &node.file.path,
+                message,
+            )
+            .with_region(Some(Region {
+                line: node.procedure.line,
+                column: 1,
+            }))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::testing::{messages, sources};
+    use crate::sources::SourceFile;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn procedures_only_called_by_dead_code_are_unreachable() {
+        let module = SourceFile::new(
+            SourceFileKind::Module,
+            Path::new("Module1.bas"),
+            concat!(
+                "Attribute VB_Name = \"Module1\"\r\n",
+                "Public Sub Main()\r\n",
+                "  Start\r\n",
+                "End Sub\r\n",
+                "Private Sub Start()\r\n",
+                "End Sub\r\n",
+                "Public Sub Ping()\r\n",
+                "  Pong\r\n",
+                "End Sub\r\n",
+                "Public Sub Pong()\r\n",
+                "  Ping\r\n",
+                "End Sub\r\n",
+            )
+            .as_bytes()
+            .to_vec(),
+        );
+        let class = SourceFile::new(
+            SourceFileKind::Class,
+            Path::new("Widget.cls"),
+            concat!(
+                "Attribute VB_Name = \"Widget\"\r\n",
+                "Attribute VB_Exposed = True\r\n",
+                "Public Sub Refresh()\r\n",
+                "End Sub\r\n",
+            )
+            .as_bytes()
+            .to_vec(),
+        );
+
+        let sources = Sources {
+            project_path: Some(PathBuf::from("App.vbp")),
+            startup: Some("Sub Main".to_owned()),
+            is_activex: false,
+            references: Vec::new(),
+            files: vec![module, class],
+        };
+
+        let messages: Vec<String> = find_unreachable_code(&sources)
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect();
+
+        assert_eq!(
+            messages,
+            [
+                "Procedure 'Ping' is only called from unreachable procedures 'Module1.Pong'",
+                "Procedure 'Pong' is only called from unreachable procedures 'Module1.Ping'",
+                "Procedure 'Refresh' is never reached from an entry point",
+            ]
+        );
+    }
+
+    #[test]
+    fn exposed_classes_of_activex_projects_are_roots() {
+        let sources = Sources {
+            startup: None,
+            is_activex: true,
+            ..sources(&[
+                (
+                    SourceFileKind::Class,
+                    "Widget.cls",
+                    concat!(
+                        "Attribute VB_Name = \"Widget\"\r\n",
+                        "Attribute VB_Exposed = True\r\n",
+                        "Public Sub Refresh()\r\n",
+                        "  Redraw\r\n",
+                        "End Sub\r\n",
+                        "Private Sub Redraw()\r\n",
+                        "  Module1.Measure\r\n",
+                        "End Sub\r\n",
+                        "Private Sub Orphan()\r\n",
+                        "End Sub\r\n",
+                    ),
+                ),
+                (
+                    SourceFileKind::Class,
+                    "Helper.cls",
+                    concat!(
+                        "Attribute VB_Name = \"Helper\"\r\n",
+                        "Attribute VB_Exposed = False\r\n",
+                        "Public Sub Assist()\r\n",
+                        "End Sub\r\n",
+                    ),
+                ),
+                (
+                    SourceFileKind::Module,
+                    "Module1.bas",
+                    concat!(
+                        "Attribute VB_Name = \"Module1\"\r\n",
+                        "Public Sub Measure()\r\n",
+                        "End Sub\r\n",
+                    ),
+                ),
+            ])
+        };
+
+        // Private classes are only used from inside the project.
+        assert_eq!(
+            messages(find_unreachable_code(&sources)),
+            [
+                "9 Procedure 'Orphan' is never reached from an entry point",
+                "3 Procedure 'Assist' is never reached from an entry point",
+            ]
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! Draws forms as SVG wireframes, so their layout can be seen without
+//! opening them in the VB6 IDE.
+//!
+//! The output only depends on the form's source, which makes it suitable for
+//! committing next to the form or comparing in tests.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+
+use crate::designer::{Control, Units};
+use crate::forms::read_form;
+use crate::xml::escape;
+
+/// The conversion VB6 uses at the standard 96 DPI.
+const TWIPS_PER_PIXEL: f64 = 15.0;
+const TITLE_BAR_HEIGHT: f64 = 20.0;
+
+/// Writes an SVG wireframe of the form at `form_path` to `output_path`, or to
+/// standard output when it's not given.
+pub fn render_form_subcommand(form_path: &Path, output_path: Option<&Path>) -> Result<()> {
+    let form = read_form(form_path)?;
+    let svg = render_form(&form.control)
+        .map_err(|e| anyhow!("Unable to render form '{}': {}", form_path.display(), e))?;
+
+    match output_path {
+        Some(output_path) => std::fs::write(output_path, svg)
+            .map_err(|e| anyhow!("Unable to write '{}': {}", output_path.display(), e)),
+        None => {
+            print!("{}", svg);
+            Ok(())
+        }
+    }
+}
+
+/// Draws the form's title bar and client area, with each control as a
+/// rectangle labelled with its caption, nested inside its container.
+fn render_form(form: &Control) -> Result<String> {
+    let bounds = form
+        .bounds()
+        .ok_or_else(|| anyhow!("'{}' has no ClientWidth or ClientHeight", form.name))?;
+
+    let width = pixels(f64::from(bounds.width), Units::TWIPS);
+    let client_height = pixels(f64::from(bounds.height), Units::TWIPS);
+    let height = TITLE_BAR_HEIGHT + client_height;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="MS Sans Serif, Arial, sans-serif" font-size="11">"#,
+        w = number(width),
+        h = number(height)
+    );
+    let _ = writeln!(svg, "  <title>{}</title>", escape(&form.name));
+    let _ = writeln!(
+        svg,
+        r##"  <rect x="0.5" y="0.5" width="{}" height="{}" fill="#ffffff" stroke="#000000"/>"##,
+        number(width - 1.0),
+        number(height - 1.0)
+    );
+    let _ = writeln!(
+        svg,
+        r##"  <rect x="0.5" y="0.5" width="{}" height="{}" fill="#d4d0c8" stroke="#000000"/>"##,
+        number(width - 1.0),
+        number(TITLE_BAR_HEIGHT - 1.0)
+    );
+    if let Some(caption) = form.property("Caption") {
+        let _ = writeln!(
+            svg,
+            r#"  <text x="4" y="{}" dominant-baseline="middle">{}</text>"#,
+            number(TITLE_BAR_HEIGHT / 2.0),
+            escape(caption)
+        );
+    }
+
+    let _ = writeln!(
+        svg,
+        r#"  <svg x="0" y="{}" width="{}" height="{}">"#,
+        number(TITLE_BAR_HEIGHT),
+        number(width),
+        number(client_height)
+    );
+    render_children(&mut svg, form, Units::TWIPS, 2);
+    let _ = writeln!(svg, "  </svg>");
+    let _ = writeln!(svg, "</svg>");
+
+    Ok(svg)
+}
+
+/// Draws the children of `container`, which is positioned in `units`.
+fn render_children(svg: &mut String, container: &Control, units: Units, depth: usize) {
+    // Units that can't be converted, such as user defined scales, are drawn
+    // as twips rather than not at all.
+    let child_units = container.child_units(Some(units)).unwrap_or(Units::TWIPS);
+
+    for child in &container.children {
+        render_control(svg, child, child_units, depth);
+    }
+}
+
+fn render_control(svg: &mut String, control: &Control, units: Units, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let stroke = if control.integer_property("Visible") == Some(0) {
+        r##"stroke="#808080" stroke-dasharray="4 2""##
+    } else {
+        r##"stroke="#000000""##
+    };
+
+    if control.type_name == "VB.Line" {
+        let coordinate = |name| Some(pixels(f64::from(control.integer_property(name)?), units));
+        if let (Some(x1), Some(y1), Some(x2), Some(y2)) = (
+            coordinate("X1"),
+            coordinate("Y1"),
+            coordinate("X2"),
+            coordinate("Y2"),
+        ) {
+            let _ = writeln!(
+                svg,
+                r#"{}<line x1="{}" y1="{}" x2="{}" y2="{}" {}/>"#,
+                indent,
+                number(x1),
+                number(y1),
+                number(x2),
+                number(y2),
+                stroke
+            );
+        }
+        return;
+    }
+
+    // Menus, timers and other controls without a position aren't drawn.
+    let Some(bounds) = control.bounds() else {
+        return;
+    };
+
+    let x = pixels(f64::from(bounds.left), units);
+    let y = pixels(f64::from(bounds.top), units);
+    let width = pixels(f64::from(bounds.width), units);
+    let height = pixels(f64::from(bounds.height), units);
+
+    // A nested SVG clips the control's caption and children to its bounds,
+    // as VB6 does.
+    let _ = writeln!(
+        svg,
+        r#"{}<svg x="{}" y="{}" width="{}" height="{}">"#,
+        indent,
+        number(x),
+        number(y),
+        number(width),
+        number(height)
+    );
+    let _ = writeln!(
+        svg,
+        "{}  <title>{} ({})</title>",
+        indent,
+        escape(&control.display_name()),
+        escape(control.short_type_name())
+    );
+
+    let is_oval = control.type_name == "VB.Shape"
+        && matches!(control.integer_property("Shape"), Some(2) | Some(3));
+    if is_oval {
+        let _ = writeln!(
+            svg,
+            r#"{}  <ellipse cx="{}" cy="{}" rx="{}" ry="{}" fill="none" {}/>"#,
+            indent,
+            number(width / 2.0),
+            number(height / 2.0),
+            number((width - 1.0).max(0.0) / 2.0),
+            number((height - 1.0).max(0.0) / 2.0),
+            stroke
+        );
+    } else {
+        let _ = writeln!(
+            svg,
+            r#"{}  <rect x="0.5" y="0.5" width="{}" height="{}" fill="none" {}/>"#,
+            indent,
+            number((width - 1.0).max(0.0)),
+            number((height - 1.0).max(0.0)),
+            stroke
+        );
+    }
+
+    let caption = control
+        .property("Caption")
+        .map(strip_access_keys)
+        .or_else(|| control.property("Text").map(str::to_owned));
+
+    match caption {
+        Some(caption) if control.type_name == "VB.CommandButton" => {
+            let _ = writeln!(
+                svg,
+                r#"{}  <text x="{}" y="{}" text-anchor="middle" dominant-baseline="middle">{}</text>"#,
+                indent,
+                number(width / 2.0),
+                number(height / 2.0),
+                escape(&caption)
+            );
+        }
+        Some(caption) => {
+            let _ = writeln!(
+                svg,
+                r#"{}  <text x="3" y="3" dominant-baseline="hanging">{}</text>"#,
+                indent,
+                escape(&caption)
+            );
+        }
+        // Name controls without any text, so they can still be told apart.
+        None if control.type_name != "VB.Shape" => {
+            let _ = writeln!(
+                svg,
+                r##"{}  <text x="3" y="3" dominant-baseline="hanging" fill="#808080">{}</text>"##,
+                indent,
+                escape(&control.display_name())
+            );
+        }
+        None => {}
+    }
+
+    render_children(svg, control, units, depth + 1);
+
+    let _ = writeln!(svg, "{}</svg>", indent);
+}
+
+fn pixels(value: f64, units: Units) -> f64 {
+    value * units.twips_per_unit / TWIPS_PER_PIXEL
+}
+
+/// Formats a number with at most two decimals, so the output doesn't
+/// change with floating point noise.
+fn number(value: f64) -> String {
+    let formatted = format!("{:.2}", value);
+    let formatted = formatted.trim_end_matches('0').trim_end_matches('.');
+
+    match formatted {
+        "-0" => "0".to_owned(),
+        formatted => formatted.to_owned(),
+    }
+}
+
+/// Removes the `&` that marks an access key, keeping `&&` as a literal `&`.
+fn strip_access_keys(caption: &str) -> String {
+    let mut stripped = String::new();
+    let mut characters = caption.chars();
+
+    while let Some(character) = characters.next() {
+        if character == '&' {
+            if let Some(next) = characters.next() {
+                stripped.push(next);
+            }
+        } else {
+            stripped.push(character);
+        }
+    }
+
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::designer::parse_designer;
+
+    #[test]
+    fn forms_render_as_nested_wireframes() {
+        let source = concat!(
+            "VERSION 5.00\r\n",
+            "Begin VB.Form frmMain \r\n",
+            "   Caption         =   \"Main\"\r\n",
+            "   ClientHeight    =   1500\r\n",
+            "   ClientWidth     =   3000\r\n",
+            "   Begin VB.Frame fraOptions \r\n",
+            "      Caption         =   \"Options\"\r\n",
+            "      Height          =   900\r\n",
+            "      Left            =   150\r\n",
+            "      Top             =   150\r\n",
+            "      Width           =   1500\r\n",
+            "      Begin VB.CommandButton cmdOK \r\n",
+            "         Caption         =   \"&Save && Close\"\r\n",
+            "         Height          =   300\r\n",
+            "         Left            =   75\r\n",
+            "         Top             =   300\r\n",
+            "         Visible         =   0   'False\r\n",
+            "         Width           =   1200\r\n",
+            "      End\r\n",
+            "   End\r\n",
+            "   Begin VB.Timer tmrPoll \r\n",
+            "      Interval        =   100\r\n",
+            "   End\r\n",
+            "End\r\n",
+        );
+
+        let form = parse_designer(source.as_bytes()).unwrap();
+
+        assert_eq!(
+            render_form(&form).unwrap(),
+            concat!(
+                r##"<svg xmlns="http://www.w3.org/2000/svg" width="200" height="120" viewBox="0 0 200 120" font-family="MS Sans Serif, Arial, sans-serif" font-size="11">"##,
+                "\n",
+                r##"  <title>frmMain</title>"##,
+                "\n",
+                r##"  <rect x="0.5" y="0.5" width="199" height="119" fill="#ffffff" stroke="#000000"/>"##,
+                "\n",
+                r##"  <rect x="0.5" y="0.5" width="199" height="19" fill="#d4d0c8" stroke="#000000"/>"##,
+                "\n",
+                r##"  <text x="4" y="10" dominant-baseline="middle">Main</text>"##,
+                "\n",
+                r##"  <svg x="0" y="20" width="200" height="100">"##,
+                "\n",
+                r##"    <svg x="10" y="10" width="100" height="60">"##,
+                "\n",
+                r##"      <title>fraOptions (Frame)</title>"##,
+                "\n",
+                r##"      <rect x="0.5" y="0.5" width="99" height="59" fill="none" stroke="#000000"/>"##,
+                "\n",
+                r##"      <text x="3" y="3" dominant-baseline="hanging">Options</text>"##,
+                "\n",
+                r##"      <svg x="5" y="20" width="80" height="20">"##,
+                "\n",
+                r##"        <title>cmdOK (CommandButton)</title>"##,
+                "\n",
+                r##"        <rect x="0.5" y="0.5" width="79" height="19" fill="none" stroke="#808080" stroke-dasharray="4 2"/>"##,
+                "\n",
+                r##"        <text x="40" y="10" text-anchor="middle" dominant-baseline="middle">Save &amp; Close</text>"##,
+                "\n",
+                r##"      </svg>"##,
+                "\n",
+                r##"    </svg>"##,
+                "\n",
+                r##"  </svg>"##,
+                "\n",
+                r##"</svg>"##,
+                "\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,1840 @@
+//! A catalogue of what the VB6 language and runtime provide without a
+//! declaration: keywords, the functions and statements of the VBA library,
+//! the constants of VBA and VBRUN, and the object models of VB, VBA, VBRUN
+//! and stdole, with their signatures and return types.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::info::InfoFormat;
+use anyhow::Result;
+use serde::Serialize;
+
+/// The version of the catalogue, raised whenever its entries change so that
+/// tools storing what it says can tell when to look again.
+pub const CATALOGUE_VERSION: u32 = 2;
+
+/// A function, or a statement that is called like a `Sub`.
+pub struct Procedure {
+    pub name: &'static str,
+    pub parameters: &'static str,
+    /// `None` for statements.
+    pub return_type: Option<&'static str>,
+    /// Whether there is also a `$` form of the function returning a
+    /// `String` rather than a `Variant`, such as `Left$`.
+    pub has_string_form: bool,
+}
+
+/// Constants of one enum, or of the library's own `Constants` module.
+pub struct ConstantGroup {
+    pub type_name: &'static str,
+    pub names: &'static [&'static str],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemberKind {
+    Property,
+    Method,
+}
+
+/// A property or method of a class.
+pub struct Member {
+    pub name: &'static str,
+    pub kind: MemberKind,
+    pub parameters: &'static str,
+    /// The property's type or the method's return type, `None` for methods
+    /// that return nothing.
+    pub return_type: Option<&'static str>,
+}
+
+pub struct Class {
+    pub name: &'static str,
+    pub members: &'static [Member],
+}
+
+/// A type library every VB6 project can use.
+pub struct Library {
+    pub name: &'static str,
+    pub procedures: &'static [Procedure],
+    pub constants: &'static [ConstantGroup],
+    pub classes: &'static [Class],
+    /// The objects the library makes available by name, and their classes.
+    pub objects: &'static [(&'static str, &'static str)],
+}
+
+const fn function(
+    name: &'static str,
+    parameters: &'static str,
+    return_type: &'static str,
+) -> Procedure {
+    Procedure {
+        name,
+        parameters,
+        return_type: Some(return_type),
+        has_string_form: false,
+    }
+}
+
+/// A function returning a `Variant`, with a `$` form returning a `String`.
+const fn string_function(name: &'static str, parameters: &'static str) -> Procedure {
+    Procedure {
+        name,
+        parameters,
+        return_type: Some("Variant"),
+        has_string_form: true,
+    }
+}
+
+const fn statement(name: &'static str, parameters: &'static str) -> Procedure {
+    Procedure {
+        name,
+        parameters,
+        return_type: None,
+        has_string_form: false,
+    }
+}
+
+const fn property(name: &'static str, type_name: &'static str) -> Member {
+    Member {
+        name,
+        kind: MemberKind::Property,
+        parameters: "",
+        return_type: Some(type_name),
+    }
+}
+
+const fn method(name: &'static str, parameters: &'static str) -> Member {
+    Member {
+        name,
+        kind: MemberKind::Method,
+        parameters,
+        return_type: None,
+    }
+}
+
+const fn method_returning(
+    name: &'static str,
+    parameters: &'static str,
+    return_type: &'static str,
+) -> Member {
+    Member {
+        name,
+        kind: MemberKind::Method,
+        parameters,
+        return_type: Some(return_type),
+    }
+}
+
+/// Keywords of the language, which aren't part of any library.
+const KEYWORDS: &[&str] = &[
+    "AddressOf",
+    "Alias",
+    "And",
+    "Any",
+    "Append",
+    "As",
+    "Base",
+    "Binary",
+    "Boolean",
+    "ByRef",
+    "Byte",
+    "ByVal",
+    "Call",
+    "Case",
+    "Close",
+    "Compare",
+    "Const",
+    "Currency",
+    "Date",
+    "Decimal",
+    "Declare",
+    "DefBool",
+    "DefByte",
+    "DefCur",
+    "DefDate",
+    "DefDbl",
+    "DefDec",
+    "DefInt",
+    "DefLng",
+    "DefObj",
+    "DefSng",
+    "DefStr",
+    "DefVar",
+    "Dim",
+    "Do",
+    "Double",
+    "Each",
+    "Else",
+    "ElseIf",
+    "Empty",
+    "End",
+    "Enum",
+    "Eqv",
+    "Erase",
+    "Error",
+    "Event",
+    "Exit",
+    "Explicit",
+    "False",
+    "For",
+    "Friend",
+    "Function",
+    "Get",
+    "Global",
+    "GoSub",
+    "GoTo",
+    "If",
+    "Imp",
+    "Implements",
+    "In",
+    "Input",
+    "Integer",
+    "Is",
+    "Let",
+    "Lib",
+    "Like",
+    "Line",
+    "Lock",
+    "Long",
+    "Loop",
+    "LSet",
+    "Me",
+    "Mod",
+    "Name",
+    "New",
+    "Next",
+    "Not",
+    "Nothing",
+    "Null",
+    "Object",
+    "On",
+    "Open",
+    "Option",
+    "Optional",
+    "Or",
+    "Output",
+    "ParamArray",
+    "Preserve",
+    "Print",
+    "Private",
+    "Property",
+    "Public",
+    "Put",
+    "RaiseEvent",
+    "Random",
+    "Read",
+    "ReDim",
+    "Rem",
+    "Resume",
+    "Return",
+    "RSet",
+    "Seek",
+    "Select",
+    "Set",
+    "Shared",
+    "Single",
+    "Static",
+    "Step",
+    "Stop",
+    "String",
+    "Sub",
+    "Then",
+    "Time",
+    "To",
+    "True",
+    "Type",
+    "TypeOf",
+    "Unlock",
+    "Until",
+    "Variant",
+    "Wend",
+    "While",
+    "Width",
+    "With",
+    "WithEvents",
+    "Write",
+    "Xor",
+];
+
+const VBA_PROCEDURES: &[Procedure] = &[
+    function("Abs", "Number", "Variant"),
+    statement("AppActivate", "Title, [Wait]"),
+    function("Array", "ParamArray ArgList()", "Variant"),
+    function("Asc", "String As String", "Integer"),
+    function("AscB", "String As String", "Integer"),
+    function("AscW", "String As String", "Integer"),
+    function("Atn", "Number As Double", "Double"),
+    statement("Beep", ""),
+    function(
+        "CallByName",
+        "Object As Object, ProcName As String, CallType As VbCallType, ParamArray Args()",
+        "Variant",
+    ),
+    function("CBool", "Expression", "Boolean"),
+    function("CByte", "Expression", "Byte"),
+    function("CCur", "Expression", "Currency"),
+    function("CDate", "Expression", "Date"),
+    function("CDbl", "Expression", "Double"),
+    function("CDec", "Expression", "Variant"),
+    statement("ChDir", "Path As String"),
+    statement("ChDrive", "Drive As String"),
+    function("Choose", "Index As Single, ParamArray Choice()", "Variant"),
+    string_function("Chr", "CharCode As Long"),
+    string_function("ChrB", "CharCode As Byte"),
+    string_function("ChrW", "CharCode As Long"),
+    function("CInt", "Expression", "Integer"),
+    function("CLng", "Expression", "Long"),
+    string_function("Command", ""),
+    function("Cos", "Number As Double", "Double"),
+    function(
+        "CreateObject",
+        "Class As String, [ServerName As String]",
+        "Object",
+    ),
+    function("CSng", "Expression", "Single"),
+    function("CStr", "Expression", "String"),
+    string_function("CurDir", "[Drive]"),
+    function("CVar", "Expression", "Variant"),
+    function("CVDate", "Expression", "Variant"),
+    function("CVErr", "Expression", "Variant"),
+    string_function("Date", ""),
+    function(
+        "DateAdd",
+        "Interval As String, Number As Double, Date",
+        "Variant",
+    ),
+    function(
+        "DateDiff",
+        "Interval As String, Date1, Date2, [FirstDayOfWeek As VbDayOfWeek = vbSunday], \
+         [FirstWeekOfYear As VbFirstWeekOfYear = vbFirstJan1]",
+        "Variant",
+    ),
+    function(
+        "DatePart",
+        "Interval As String, Date, [FirstDayOfWeek As VbDayOfWeek = vbSunday], \
+         [FirstWeekOfYear As VbFirstWeekOfYear = vbFirstJan1]",
+        "Variant",
+    ),
+    function(
+        "DateSerial",
+        "Year As Integer, Month As Integer, Day As Integer",
+        "Variant",
+    ),
+    function("DateValue", "Date As String", "Variant"),
+    function("Day", "Date", "Variant"),
+    function(
+        "DDB",
+        "Cost As Double, Salvage As Double, Life As Double, Period As Double, [Factor]",
+        "Double",
+    ),
+    statement("DeleteSetting", "AppName As String, [Section], [Key]"),
+    string_function(
+        "Dir",
+        "[PathName], [Attributes As VbFileAttribute = vbNormal]",
+    ),
+    function("DoEvents", "", "Integer"),
+    string_function("Environ", "Expression"),
+    function("EOF", "FileNumber As Integer", "Boolean"),
+    string_function("Error", "[ErrorNumber]"),
+    function("Exp", "Number As Double", "Double"),
+    statement("FileCopy", "Source As String, Destination As String"),
+    function(
+        "FileAttr",
+        "FileNumber As Integer, [ReturnType As Integer = 1]",
+        "Long",
+    ),
+    function("FileDateTime", "PathName As String", "Variant"),
+    function("FileLen", "PathName As String", "Long"),
+    function(
+        "Filter",
+        "SourceArray, Match As String, [Include As Boolean = True], \
+         [Compare As VbCompareMethod = vbBinaryCompare]",
+        "Variant",
+    ),
+    function("Fix", "Number", "Variant"),
+    string_function(
+        "Format",
+        "Expression, [Format], [FirstDayOfWeek As VbDayOfWeek = vbSunday], \
+         [FirstWeekOfYear As VbFirstWeekOfYear = vbFirstJan1]",
+    ),
+    function(
+        "FormatCurrency",
+        "Expression, [NumDigitsAfterDecimal As Long = -1], \
+         [IncludeLeadingDigit As VbTriState = vbUseDefault], \
+         [UseParensForNegativeNumbers As VbTriState = vbUseDefault], \
+         [GroupDigits As VbTriState = vbUseDefault]",
+        "String",
+    ),
+    function(
+        "FormatDateTime",
+        "Expression, [NamedFormat As VbDateTimeFormat = vbGeneralDate]",
+        "String",
+    ),
+    function(
+        "FormatNumber",
+        "Expression, [NumDigitsAfterDecimal As Long = -1], \
+         [IncludeLeadingDigit As VbTriState = vbUseDefault], \
+         [UseParensForNegativeNumbers As VbTriState = vbUseDefault], \
+         [GroupDigits As VbTriState = vbUseDefault]",
+        "String",
+    ),
+    function(
+        "FormatPercent",
+        "Expression, [NumDigitsAfterDecimal As Long = -1], \
+         [IncludeLeadingDigit As VbTriState = vbUseDefault], \
+         [UseParensForNegativeNumbers As VbTriState = vbUseDefault], \
+         [GroupDigits As VbTriState = vbUseDefault]",
+        "String",
+    ),
+    function("FreeFile", "[RangeNumber]", "Integer"),
+    function(
+        "FV",
+        "Rate As Double, NPer As Double, Pmt As Double, [PV], [Due]",
+        "Double",
+    ),
+    function(
+        "GetAllSettings",
+        "AppName As String, Section As String",
+        "Variant",
+    ),
+    function("GetAttr", "PathName As String", "VbFileAttribute"),
+    function("GetObject", "[PathName], [Class]", "Object"),
+    function(
+        "GetSetting",
+        "AppName As String, Section As String, Key As String, [Default]",
+        "String",
+    ),
+    string_function("Hex", "Number"),
+    function("Hour", "Time", "Variant"),
+    function("IIf", "Expression, TruePart, FalsePart", "Variant"),
+    function("IMEStatus", "", "VbIMEStatus"),
+    string_function("Input", "Number As Long, FileNumber As Integer"),
+    string_function("InputB", "Number As Long, FileNumber As Integer"),
+    function(
+        "InputBox",
+        "Prompt, [Title], [Default], [XPos], [YPos], [HelpFile], [Context]",
+        "String",
+    ),
+    function(
+        "InStr",
+        "[Start], [String1], [String2], [Compare As VbCompareMethod = vbBinaryCompare]",
+        "Variant",
+    ),
+    function(
+        "InStrB",
+        "[Start], [String1], [String2], [Compare As VbCompareMethod = vbBinaryCompare]",
+        "Variant",
+    ),
+    function(
+        "InStrRev",
+        "StringCheck As String, StringMatch As String, [Start As Long = -1], \
+         [Compare As VbCompareMethod = vbBinaryCompare]",
+        "Long",
+    ),
+    function("Int", "Number", "Variant"),
+    function(
+        "IPmt",
+        "Rate As Double, Per As Double, NPer As Double, PV As Double, [FV], [Due]",
+        "Double",
+    ),
+    function("IRR", "ValueArray() As Double, [Guess]", "Double"),
+    function("IsArray", "VarName", "Boolean"),
+    function("IsDate", "Expression", "Boolean"),
+    function("IsEmpty", "Expression", "Boolean"),
+    function("IsError", "Expression", "Boolean"),
+    function("IsMissing", "ArgName", "Boolean"),
+    function("IsNull", "Expression", "Boolean"),
+    function("IsNumeric", "Expression", "Boolean"),
+    function("IsObject", "Expression", "Boolean"),
+    function("Join", "SourceArray, [Delimiter]", "String"),
+    statement("Kill", "PathName"),
+    function("LBound", "Arr, [Dimension As Long = 1]", "Long"),
+    string_function("LCase", "String"),
+    string_function("Left", "String, Length As Long"),
+    string_function("LeftB", "String, Length As Long"),
+    function("Len", "Expression", "Variant"),
+    function("LenB", "Expression", "Variant"),
+    function("Loc", "FileNumber As Integer", "Long"),
+    function("LOF", "FileNumber As Integer", "Long"),
+    function("Log", "Number As Double", "Double"),
+    string_function("LTrim", "String"),
+    string_function("Mid", "String, Start As Long, [Length]"),
+    string_function("MidB", "String, Start As Long, [Length]"),
+    function("Minute", "Time", "Variant"),
+    function(
+        "MIRR",
+        "ValueArray() As Double, FinanceRate As Double, ReinvestRate As Double",
+        "Double",
+    ),
+    statement("MkDir", "Path As String"),
+    function("Month", "Date", "Variant"),
+    function(
+        "MonthName",
+        "Month As Long, [Abbreviate As Boolean = False]",
+        "String",
+    ),
+    function(
+        "MsgBox",
+        "Prompt, [Buttons As VbMsgBoxStyle = vbOKOnly], [Title], [HelpFile], [Context]",
+        "VbMsgBoxResult",
+    ),
+    function("Now", "", "Variant"),
+    function(
+        "NPer",
+        "Rate As Double, Pmt As Double, PV As Double, [FV], [Due]",
+        "Double",
+    ),
+    function("NPV", "Rate As Double, ValueArray() As Double", "Double"),
+    function("ObjPtr", "Ptr", "Long"),
+    string_function("Oct", "Number"),
+    function("Partition", "Number, Start, Stop, Interval", "Variant"),
+    function(
+        "Pmt",
+        "Rate As Double, NPer As Double, PV As Double, [FV], [Due]",
+        "Double",
+    ),
+    function(
+        "PPmt",
+        "Rate As Double, Per As Double, NPer As Double, PV As Double, [FV], [Due]",
+        "Double",
+    ),
+    function(
+        "PV",
+        "Rate As Double, NPer As Double, Pmt As Double, [FV], [Due]",
+        "Double",
+    ),
+    function("QBColor", "Color As Integer", "Long"),
+    statement("Randomize", "[Number]"),
+    function(
+        "Rate",
+        "NPer As Double, Pmt As Double, PV As Double, [FV], [Due], [Guess]",
+        "Double",
+    ),
+    function(
+        "Replace",
+        "Expression As String, Find As String, Replace As String, [Start As Long = 1], \
+         [Count As Long = -1], [Compare As VbCompareMethod = vbBinaryCompare]",
+        "String",
+    ),
+    statement("Reset", ""),
+    function(
+        "RGB",
+        "Red As Integer, Green As Integer, Blue As Integer",
+        "Long",
+    ),
+    string_function("Right", "String, Length As Long"),
+    string_function("RightB", "String, Length As Long"),
+    statement("RmDir", "Path As String"),
+    function("Rnd", "[Number]", "Single"),
+    function(
+        "Round",
+        "Number, [NumDigitsAfterDecimal As Long]",
+        "Variant",
+    ),
+    string_function("RTrim", "String"),
+    statement(
+        "SaveSetting",
+        "AppName As String, Section As String, Key As String, Setting As String",
+    ),
+    function("Second", "Time", "Variant"),
+    statement("SendKeys", "String As String, [Wait]"),
+    statement(
+        "SetAttr",
+        "PathName As String, Attributes As VbFileAttribute",
+    ),
+    function("Sgn", "Number", "Variant"),
+    function(
+        "Shell",
+        "PathName, [WindowStyle As VbAppWinStyle = vbMinimizedFocus]",
+        "Double",
+    ),
+    function("Sin", "Number As Double", "Double"),
+    function(
+        "SLN",
+        "Cost As Double, Salvage As Double, Life As Double",
+        "Double",
+    ),
+    string_function("Space", "Number As Long"),
+    function("Spc", "N As Integer", "Variant"),
+    function(
+        "Split",
+        "Expression As String, [Delimiter], [Limit As Long = -1], \
+         [Compare As VbCompareMethod = vbBinaryCompare]",
+        "Variant",
+    ),
+    function("Sqr", "Number As Double", "Double"),
+    string_function("Str", "Number"),
+    function(
+        "StrComp",
+        "String1, String2, [Compare As VbCompareMethod = vbBinaryCompare]",
+        "Variant",
+    ),
+    function(
+        "StrConv",
+        "String, Conversion As VbStrConv, [LocaleID As Long]",
+        "Variant",
+    ),
+    string_function("String", "Number As Long, Character"),
+    function("StrPtr", "Ptr", "Long"),
+    function("StrReverse", "Expression As String", "String"),
+    function("Switch", "ParamArray VarExpr()", "Variant"),
+    function(
+        "SYD",
+        "Cost As Double, Salvage As Double, Life As Double, Period As Double",
+        "Double",
+    ),
+    function("Tab", "[N As Integer]", "Variant"),
+    function("Tan", "Number As Double", "Double"),
+    string_function("Time", ""),
+    function("Timer", "", "Single"),
+    function(
+        "TimeSerial",
+        "Hour As Integer, Minute As Integer, Second As Integer",
+        "Variant",
+    ),
+    function("TimeValue", "Time As String", "Variant"),
+    string_function("Trim", "String"),
+    function("TypeName", "VarName", "String"),
+    function("UBound", "Arr, [Dimension As Long = 1]", "Long"),
+    string_function("UCase", "String"),
+    function("Val", "String As String", "Double"),
+    function("VarPtr", "Ptr", "Long"),
+    function("VarType", "VarName", "VbVarType"),
+    function(
+        "Weekday",
+        "Date, [FirstDayOfWeek As VbDayOfWeek = vbSunday]",
+        "Variant",
+    ),
+    function(
+        "WeekdayName",
+        "Weekday As Long, [Abbreviate As Boolean = False], \
+         [FirstDayOfWeek As VbDayOfWeek = vbUseSystemDayOfWeek]",
+        "String",
+    ),
+    function("Year", "Date", "Variant"),
+];
+
+const VBA_CONSTANTS: &[ConstantGroup] = &[
+    ConstantGroup {
+        type_name: "String",
+        names: &[
+            "vbBack",
+            "vbCr",
+            "vbCrLf",
+            "vbFormFeed",
+            "vbLf",
+            "vbNewLine",
+            "vbNullChar",
+            "vbNullString",
+            "vbTab",
+            "vbVerticalTab",
+        ],
+    },
+    ConstantGroup {
+        type_name: "Long",
+        names: &["vbObjectError"],
+    },
+    ConstantGroup {
+        type_name: "VbAppWinStyle",
+        names: &[
+            "vbHide",
+            "vbMaximizedFocus",
+            "vbMinimizedFocus",
+            "vbMinimizedNoFocus",
+            "vbNormalFocus",
+            "vbNormalNoFocus",
+        ],
+    },
+    ConstantGroup {
+        type_name: "VbCallType",
+        names: &["vbGet", "vbLet", "vbMethod", "vbSet"],
+    },
+    ConstantGroup {
+        type_name: "VbCompareMethod",
+        names: &["vbBinaryCompare", "vbDatabaseCompare", "vbTextCompare"],
+    },
+    ConstantGroup {
+        type_name: "VbDateTimeFormat",
+        names: &[
+            "vbGeneralDate",
+            "vbLongDate",
+            "vbLongTime",
+            "vbShortDate",
+            "vbShortTime",
+        ],
+    },
+    ConstantGroup {
+        type_name: "VbDayOfWeek",
+        names: &[
+            "vbFriday",
+            "vbMonday",
+            "vbSaturday",
+            "vbSunday",
+            "vbThursday",
+            "vbTuesday",
+            "vbUseSystemDayOfWeek",
+            "vbWednesday",
+        ],
+    },
+    ConstantGroup {
+        type_name: "VbFileAttribute",
+        names: &[
+            "vbAlias",
+            "vbArchive",
+            "vbDirectory",
+            "vbHidden",
+            "vbNormal",
+            "vbReadOnly",
+            "vbSystem",
+            "vbVolume",
+        ],
+    },
+    ConstantGroup {
+        type_name: "VbFirstWeekOfYear",
+        names: &[
+            "vbFirstFourDays",
+            "vbFirstFullWeek",
+            "vbFirstJan1",
+            "vbUseSystem",
+        ],
+    },
+    ConstantGroup {
+        type_name: "VbIMEStatus",
+        names: &[
+            "vbIMEAlphaDbl",
+            "vbIMEAlphaSng",
+            "vbIMEDisable",
+            "vbIMEHiragana",
+            "vbIMEKatakanaDbl",
+            "vbIMEKatakanaSng",
+            "vbIMEModeAlpha",
+            "vbIMEModeAlphaFull",
+            "vbIMEModeDisable",
+            "vbIMEModeHangul",
+            "vbIMEModeHangulFull",
+            "vbIMEModeHiragana",
+            "vbIMEModeKatakana",
+            "vbIMEModeKatakanaHalf",
+            "vbIMEModeNoControl",
+            "vbIMEModeOff",
+            "vbIMEModeOn",
+            "vbIMENoOp",
+            "vbIMEOff",
+            "vbIMEOn",
+        ],
+    },
+    ConstantGroup {
+        type_name: "VbMsgBoxResult",
+        names: &[
+            "vbAbort", "vbCancel", "vbIgnore", "vbNo", "vbOK", "vbRetry", "vbYes",
+        ],
+    },
+    ConstantGroup {
+        type_name: "VbMsgBoxStyle",
+        names: &[
+            "vbAbortRetryIgnore",
+            "vbApplicationModal",
+            "vbCritical",
+            "vbDefaultButton1",
+            "vbDefaultButton2",
+            "vbDefaultButton3",
+            "vbDefaultButton4",
+            "vbExclamation",
+            "vbInformation",
+            "vbMsgBoxHelpButton",
+            "vbMsgBoxRight",
+            "vbMsgBoxRtlReading",
+            "vbMsgBoxSetForeground",
+            "vbOKCancel",
+            "vbOKOnly",
+            "vbQuestion",
+            "vbRetryCancel",
+            "vbSystemModal",
+            "vbYesNo",
+            "vbYesNoCancel",
+        ],
+    },
+    ConstantGroup {
+        type_name: "VbStrConv",
+        names: &[
+            "vbFromUnicode",
+            "vbHiragana",
+            "vbKatakana",
+            "vbLowerCase",
+            "vbNarrow",
+            "vbProperCase",
+            "vbUnicode",
+            "vbUpperCase",
+            "vbWide",
+        ],
+    },
+    ConstantGroup {
+        type_name: "VbTriState",
+        names: &["vbFalse", "vbTrue", "vbUseDefault"],
+    },
+    ConstantGroup {
+        type_name: "VbVarType",
+        names: &[
+            "vbArray",
+            "vbBoolean",
+            "vbByte",
+            "vbCurrency",
+            "vbDataObject",
+            "vbDate",
+            "vbDecimal",
+            "vbDouble",
+            "vbEmpty",
+            "vbError",
+            "vbInteger",
+            "vbLong",
+            "vbNull",
+            "vbObject",
+            "vbSingle",
+            "vbString",
+            "vbUserDefinedType",
+            "vbVariant",
+        ],
+    },
+];
+
+const VBA_CLASSES: &[Class] = &[
+    Class {
+        name: "Collection",
+        members: &[
+            method("Add", "Item, [Key], [Before], [After]"),
+            method_returning("Count", "", "Long"),
+            method_returning("Item", "Index", "Variant"),
+            method("Remove", "Index"),
+        ],
+    },
+    Class {
+        name: "ErrObject",
+        members: &[
+            method("Clear", ""),
+            property("Description", "String"),
+            property("HelpContext", "Long"),
+            property("HelpFile", "String"),
+            property("LastDllError", "Long"),
+            property("Number", "Long"),
+            method(
+                "Raise",
+                "Number As Long, [Source], [Description], [HelpFile], [HelpContext]",
+            ),
+            property("Source", "String"),
+        ],
+    },
+];
+
+/// The properties and methods shared by forms and the printer for drawing.
+const FORM_MEMBERS: &[Member] = &[
+    property("ActiveControl", "Control"),
+    property("Appearance", "Integer"),
+    property("AutoRedraw", "Boolean"),
+    property("BackColor", "Long"),
+    property("BorderStyle", "Integer"),
+    property("Caption", "String"),
+    method(
+        "Circle",
+        "Step As Integer, X As Single, Y As Single, Radius As Single, [Color], [Start], [End], \
+         [Aspect]",
+    ),
+    property("ClipControls", "Boolean"),
+    method("Cls", ""),
+    property("ControlBox", "Boolean"),
+    property("Controls", "Object"),
+    property("Count", "Integer"),
+    property("CurrentX", "Single"),
+    property("CurrentY", "Single"),
+    property("DrawMode", "Integer"),
+    property("DrawStyle", "Integer"),
+    property("DrawWidth", "Integer"),
+    property("Enabled", "Boolean"),
+    property("FillColor", "Long"),
+    property("FillStyle", "Integer"),
+    property("Font", "StdFont"),
+    property("FontBold", "Boolean"),
+    property("FontItalic", "Boolean"),
+    property("FontName", "String"),
+    property("FontSize", "Single"),
+    property("FontStrikethru", "Boolean"),
+    property("FontTransparent", "Boolean"),
+    property("FontUnderline", "Boolean"),
+    property("ForeColor", "Long"),
+    property("HasDC", "Boolean"),
+    property("hDC", "Long"),
+    property("Height", "Single"),
+    property("HelpContextID", "Long"),
+    method("Hide", ""),
+    property("hWnd", "Long"),
+    property("Icon", "IPictureDisp"),
+    property("Image", "IPictureDisp"),
+    property("KeyPreview", "Boolean"),
+    property("Left", "Single"),
+    property("LinkMode", "Integer"),
+    property("LinkTopic", "String"),
+    property("MaxButton", "Boolean"),
+    property("MDIChild", "Boolean"),
+    property("MinButton", "Boolean"),
+    property("MouseIcon", "IPictureDisp"),
+    property("MousePointer", "MousePointerConstants"),
+    method("Move", "Left As Single, [Top], [Width], [Height]"),
+    property("Moveable", "Boolean"),
+    property("Name", "String"),
+    method("OLEDrag", ""),
+    property("OLEDropMode", "Integer"),
+    method(
+        "PaintPicture",
+        "Picture As IPictureDisp, X1 As Single, Y1 As Single, [Width1], [Height1], [X2], [Y2], \
+         [Width2], [Height2], [Opcode]",
+    ),
+    property("Palette", "IPictureDisp"),
+    property("PaletteMode", "Integer"),
+    property("Picture", "IPictureDisp"),
+    method_returning("Point", "X As Single, Y As Single", "Long"),
+    method(
+        "PopupMenu",
+        "Menu As Object, [Flags], [X], [Y], [DefaultMenu]",
+    ),
+    method("PrintForm", ""),
+    method("PSet", "Step As Integer, X As Single, Y As Single, [Color]"),
+    method("Refresh", ""),
+    property("RightToLeft", "Boolean"),
+    method("Scale", "[X1], [Y1], [X2], [Y2]"),
+    property("ScaleHeight", "Single"),
+    property("ScaleLeft", "Single"),
+    property("ScaleMode", "ScaleModeConstants"),
+    property("ScaleTop", "Single"),
+    property("ScaleWidth", "Single"),
+    method_returning(
+        "ScaleX",
+        "Width As Single, [FromScale], [ToScale]",
+        "Single",
+    ),
+    method_returning(
+        "ScaleY",
+        "Height As Single, [FromScale], [ToScale]",
+        "Single",
+    ),
+    method("SetFocus", ""),
+    method("Show", "[Modal], [OwnerForm]"),
+    property("ShowInTaskbar", "Boolean"),
+    property("StartUpPosition", "Integer"),
+    property("Tag", "String"),
+    method_returning("TextHeight", "Str As String", "Single"),
+    method_returning("TextWidth", "Str As String", "Single"),
+    property("Top", "Single"),
+    method("ValidateControls", ""),
+    property("Visible", "Boolean"),
+    property("WhatsThisButton", "Boolean"),
+    property("WhatsThisHelp", "Boolean"),
+    method("WhatsThisMode", ""),
+    property("Width", "Single"),
+    property("WindowState", "FormWindowStateConstants"),
+    method("ZOrder", "[Position]"),
+];
+
+const VB_PROCEDURES: &[Procedure] = &[
+    statement("Load", "Object"),
+    function(
+        "LoadPicture",
+        "[FileName], [Size], [ColorDepth], [X], [Y]",
+        "IPictureDisp",
+    ),
+    function("LoadResData", "Id, Type", "Variant"),
+    function("LoadResPicture", "Id, ResType As Integer", "IPictureDisp"),
+    function("LoadResString", "Id As Long", "String"),
+    statement("SavePicture", "Picture As IPictureDisp, FileName As String"),
+    statement("Unload", "Object"),
+];
+
+const VB_CLASSES: &[Class] = &[
+    Class {
+        name: "App",
+        members: &[
+            property("Comments", "String"),
+            property("CompanyName", "String"),
+            property("EXEName", "String"),
+            property("FileDescription", "String"),
+            property("HelpFile", "String"),
+            property("hInstance", "Long"),
+            property("LegalCopyright", "String"),
+            property("LegalTrademarks", "String"),
+            method("LogEvent", "LogBuffer As String, [EventType]"),
+            property("LogMode", "Long"),
+            property("LogPath", "String"),
+            property("Major", "Integer"),
+            property("Minor", "Integer"),
+            property("NonModalAllowed", "Boolean"),
+            property("Path", "String"),
+            property("PrevInstance", "Boolean"),
+            property("ProductName", "String"),
+            property("Revision", "Integer"),
+            property("StartMode", "Integer"),
+            method("StartLogging", "LogTarget As String, LogModes As Long"),
+            property("TaskVisible", "Boolean"),
+            property("ThreadID", "Long"),
+            property("Title", "String"),
+            property("UnattendedApp", "Boolean"),
+        ],
+    },
+    Class {
+        name: "Clipboard",
+        members: &[
+            method("Clear", ""),
+            method_returning("GetData", "[Format]", "IPictureDisp"),
+            method_returning("GetFormat", "Format As Integer", "Boolean"),
+            method_returning("GetText", "[Format]", "String"),
+            method("SetData", "Picture As IPictureDisp, [Format]"),
+            method("SetText", "Str As String, [Format]"),
+        ],
+    },
+    Class {
+        name: "Debug",
+        members: &[
+            method("Assert", "Condition As Boolean"),
+            method("Print", "[OutputList]"),
+        ],
+    },
+    Class {
+        name: "Form",
+        members: FORM_MEMBERS,
+    },
+    Class {
+        name: "Forms",
+        members: &[
+            property("Count", "Integer"),
+            method_returning("Item", "Index", "Form"),
+        ],
+    },
+    Class {
+        name: "Licenses",
+        members: &[
+            method_returning("Add", "ProgId As String, [LicenseKey]", "String"),
+            property("Count", "Long"),
+            method_returning("Item", "Index", "String"),
+            method("Remove", "Index"),
+        ],
+    },
+    Class {
+        name: "Printer",
+        members: &[
+            property("ColorMode", "Integer"),
+            property("Copies", "Integer"),
+            property("CurrentX", "Single"),
+            property("CurrentY", "Single"),
+            property("DeviceName", "String"),
+            property("DrawWidth", "Integer"),
+            property("Duplex", "Integer"),
+            method("EndDoc", ""),
+            property("Font", "StdFont"),
+            property("FontName", "String"),
+            property("FontSize", "Single"),
+            property("hDC", "Long"),
+            property("Height", "Long"),
+            method("KillDoc", ""),
+            method("NewPage", ""),
+            property("Orientation", "Integer"),
+            property("Page", "Integer"),
+            property("PaperSize", "Integer"),
+            property("PrintQuality", "Integer"),
+            property("ScaleMode", "ScaleModeConstants"),
+            method_returning("TextHeight", "Str As String", "Single"),
+            method_returning("TextWidth", "Str As String", "Single"),
+            property("Width", "Long"),
+        ],
+    },
+    Class {
+        name: "Printers",
+        members: &[
+            property("Count", "Integer"),
+            method_returning("Item", "Index", "Printer"),
+        ],
+    },
+    Class {
+        name: "Screen",
+        members: &[
+            property("ActiveControl", "Control"),
+            property("ActiveForm", "Form"),
+            property("FontCount", "Integer"),
+            method_returning("Fonts", "Index As Integer", "String"),
+            property("Height", "Single"),
+            property("MouseIcon", "IPictureDisp"),
+            property("MousePointer", "MousePointerConstants"),
+            property("TwipsPerPixelX", "Single"),
+            property("TwipsPerPixelY", "Single"),
+            property("Width", "Single"),
+        ],
+    },
+    Class {
+        name: "VBControlExtender",
+        members: &[
+            property("CausesValidation", "Boolean"),
+            property("Container", "Object"),
+            method("Drag", "[Action]"),
+            property("DragIcon", "IPictureDisp"),
+            property("DragMode", "Integer"),
+            property("Enabled", "Boolean"),
+            property("Height", "Single"),
+            property("HelpContextID", "Long"),
+            property("Index", "Integer"),
+            property("Left", "Single"),
+            method("Move", "Left As Single, [Top], [Width], [Height]"),
+            property("Name", "String"),
+            property("Object", "Object"),
+            property("Parent", "Object"),
+            method("SetFocus", ""),
+            method("ShowWhatsThis", ""),
+            property("TabIndex", "Integer"),
+            property("TabStop", "Boolean"),
+            property("Tag", "String"),
+            property("ToolTipText", "String"),
+            property("Top", "Single"),
+            property("Visible", "Boolean"),
+            property("WhatsThisHelpID", "Long"),
+            property("Width", "Single"),
+            method("ZOrder", "[Position]"),
+        ],
+    },
+];
+
+const VBRUN_CONSTANTS: &[ConstantGroup] = &[
+    ConstantGroup {
+        type_name: "ColorConstants",
+        names: &[
+            "vbBlack",
+            "vbBlue",
+            "vbCyan",
+            "vbGreen",
+            "vbMagenta",
+            "vbRed",
+            "vbWhite",
+            "vbYellow",
+        ],
+    },
+    ConstantGroup {
+        type_name: "FormShowConstants",
+        names: &["vbModal", "vbModeless"],
+    },
+    ConstantGroup {
+        type_name: "FormWindowStateConstants",
+        names: &["vbMaximized", "vbMinimized", "vbNormal"],
+    },
+    ConstantGroup {
+        type_name: "KeyCodeConstants",
+        names: &[
+            "vbKey0",
+            "vbKey1",
+            "vbKey2",
+            "vbKey3",
+            "vbKey4",
+            "vbKey5",
+            "vbKey6",
+            "vbKey7",
+            "vbKey8",
+            "vbKey9",
+            "vbKeyA",
+            "vbKeyAdd",
+            "vbKeyB",
+            "vbKeyBack",
+            "vbKeyC",
+            "vbKeyCancel",
+            "vbKeyCapital",
+            "vbKeyClear",
+            "vbKeyControl",
+            "vbKeyD",
+            "vbKeyDecimal",
+            "vbKeyDelete",
+            "vbKeyDivide",
+            "vbKeyDown",
+            "vbKeyE",
+            "vbKeyEnd",
+            "vbKeyEscape",
+            "vbKeyExecute",
+            "vbKeyF",
+            "vbKeyF1",
+            "vbKeyF10",
+            "vbKeyF11",
+            "vbKeyF12",
+            "vbKeyF13",
+            "vbKeyF14",
+            "vbKeyF15",
+            "vbKeyF16",
+            "vbKeyF2",
+            "vbKeyF3",
+            "vbKeyF4",
+            "vbKeyF5",
+            "vbKeyF6",
+            "vbKeyF7",
+            "vbKeyF8",
+            "vbKeyF9",
+            "vbKeyG",
+            "vbKeyH",
+            "vbKeyHelp",
+            "vbKeyHome",
+            "vbKeyI",
+            "vbKeyInsert",
+            "vbKeyJ",
+            "vbKeyK",
+            "vbKeyL",
+            "vbKeyLButton",
+            "vbKeyLeft",
+            "vbKeyM",
+            "vbKeyMButton",
+            "vbKeyMenu",
+            "vbKeyMultiply",
+            "vbKeyN",
+            "vbKeyNumlock",
+            "vbKeyNumpad0",
+            "vbKeyNumpad1",
+            "vbKeyNumpad2",
+            "vbKeyNumpad3",
+            "vbKeyNumpad4",
+            "vbKeyNumpad5",
+            "vbKeyNumpad6",
+            "vbKeyNumpad7",
+            "vbKeyNumpad8",
+            "vbKeyNumpad9",
+            "vbKeyO",
+            "vbKeyP",
+            "vbKeyPageDown",
+            "vbKeyPageUp",
+            "vbKeyPause",
+            "vbKeyPrint",
+            "vbKeyQ",
+            "vbKeyR",
+            "vbKeyRButton",
+            "vbKeyReturn",
+            "vbKeyRight",
+            "vbKeyS",
+            "vbKeyScrollLock",
+            "vbKeySelect",
+            "vbKeySeparator",
+            "vbKeyShift",
+            "vbKeySnapshot",
+            "vbKeySpace",
+            "vbKeySubtract",
+            "vbKeyT",
+            "vbKeyTab",
+            "vbKeyU",
+            "vbKeyUp",
+            "vbKeyV",
+            "vbKeyW",
+            "vbKeyX",
+            "vbKeyY",
+            "vbKeyZ",
+        ],
+    },
+    ConstantGroup {
+        type_name: "LoadResConstants",
+        names: &["vbResBitmap", "vbResCursor", "vbResIcon"],
+    },
+    ConstantGroup {
+        type_name: "MouseButtonConstants",
+        names: &["vbLeftButton", "vbMiddleButton", "vbRightButton"],
+    },
+    ConstantGroup {
+        type_name: "MousePointerConstants",
+        names: &[
+            "vbArrow",
+            "vbArrowHourglass",
+            "vbArrowQuestion",
+            "vbCrosshair",
+            "vbCustom",
+            "vbDefault",
+            "vbHourglass",
+            "vbIbeam",
+            "vbIconPointer",
+            "vbNoDrop",
+            "vbSizeAll",
+            "vbSizeNESW",
+            "vbSizeNS",
+            "vbSizeNWSE",
+            "vbSizePointer",
+            "vbSizeWE",
+            "vbUpArrow",
+        ],
+    },
+    ConstantGroup {
+        type_name: "QueryUnloadConstants",
+        names: &[
+            "vbAppTaskManager",
+            "vbAppWindows",
+            "vbFormCode",
+            "vbFormControlMenu",
+            "vbFormMDIForm",
+            "vbFormOwner",
+        ],
+    },
+    ConstantGroup {
+        type_name: "ScaleModeConstants",
+        names: &[
+            "vbCentimeters",
+            "vbCharacters",
+            "vbContainerPosition",
+            "vbContainerSize",
+            "vbHimetric",
+            "vbInches",
+            "vbMillimeters",
+            "vbPixels",
+            "vbPoints",
+            "vbTwips",
+            "vbUser",
+        ],
+    },
+    ConstantGroup {
+        type_name: "ShiftConstants",
+        names: &["vbAltMask", "vbCtrlMask", "vbShiftMask"],
+    },
+    ConstantGroup {
+        type_name: "SystemColorConstants",
+        names: &[
+            "vb3DDKShadow",
+            "vb3DFace",
+            "vb3DHighlight",
+            "vb3DLight",
+            "vb3DShadow",
+            "vbActiveBorder",
+            "vbActiveTitleBar",
+            "vbActiveTitleBarText",
+            "vbApplicationWorkspace",
+            "vbButtonFace",
+            "vbButtonShadow",
+            "vbButtonText",
+            "vbDesktop",
+            "vbGrayText",
+            "vbHighlight",
+            "vbHighlightText",
+            "vbInactiveBorder",
+            "vbInactiveCaptionText",
+            "vbInactiveTitleBar",
+            "vbInactiveTitleBarText",
+            "vbInfoBackground",
+            "vbInfoText",
+            "vbMenuBar",
+            "vbMenuText",
+            "vbScrollBars",
+            "vbTitleBarText",
+            "vbWindowBackground",
+            "vbWindowFrame",
+            "vbWindowText",
+        ],
+    },
+];
+
+const VBRUN_CLASSES: &[Class] = &[
+    Class {
+        name: "DataObject",
+        members: &[
+            method("Clear", ""),
+            property("Files", "DataObjectFiles"),
+            method_returning("GetData", "[Format]", "Variant"),
+            method_returning("GetFormat", "Format As Integer", "Boolean"),
+            method("SetData", "[Value], [Format]"),
+        ],
+    },
+    Class {
+        name: "PropertyBag",
+        members: &[
+            property("Contents", "Variant"),
+            method_returning("ReadProperty", "Name As String, [DefaultValue]", "Variant"),
+            method("WriteProperty", "Name As String, Value, [DefaultValue]"),
+        ],
+    },
+];
+
+const STDOLE_CLASSES: &[Class] = &[
+    Class {
+        name: "IDispatch",
+        members: &[],
+    },
+    Class {
+        name: "IUnknown",
+        members: &[],
+    },
+    Class {
+        name: "StdFont",
+        members: &[
+            property("Bold", "Boolean"),
+            property("Charset", "Integer"),
+            property("Italic", "Boolean"),
+            property("Name", "String"),
+            property("Size", "Currency"),
+            property("Strikethrough", "Boolean"),
+            property("Underline", "Boolean"),
+            property("Weight", "Integer"),
+        ],
+    },
+    Class {
+        name: "StdPicture",
+        members: &[
+            property("Handle", "Long"),
+            property("Height", "Long"),
+            property("hPal", "Long"),
+            method(
+                "Render",
+                "hdc As Long, x As Long, y As Long, cx As Long, cy As Long, xSrc As Long, \
+                 ySrc As Long, cxSrc As Long, cySrc As Long, prcWBounds",
+            ),
+            property("Type", "Integer"),
+            property("Width", "Long"),
+        ],
+    },
+];
+
+/// The libraries every project references, in the order VB6 searches them.
+pub const LIBRARIES: &[Library] = &[
+    Library {
+        name: "VBA",
+        procedures: VBA_PROCEDURES,
+        constants: VBA_CONSTANTS,
+        classes: VBA_CLASSES,
+        objects: &[("Err", "ErrObject")],
+    },
+    Library {
+        name: "VB",
+        procedures: VB_PROCEDURES,
+        constants: &[],
+        classes: VB_CLASSES,
+        objects: &[
+            ("App", "App"),
+            ("Clipboard", "Clipboard"),
+            ("Debug", "Debug"),
+            ("Forms", "Forms"),
+            ("Licenses", "Licenses"),
+            ("Printer", "Printer"),
+            ("Printers", "Printers"),
+            ("Screen", "Screen"),
+        ],
+    },
+    Library {
+        name: "VBRUN",
+        procedures: &[],
+        constants: VBRUN_CONSTANTS,
+        classes: VBRUN_CLASSES,
+        objects: &[],
+    },
+    Library {
+        name: "stdole",
+        procedures: &[],
+        constants: &[],
+        classes: STDOLE_CLASSES,
+        objects: &[],
+    },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Keyword,
+    Library,
+    Function,
+    Statement,
+    Constant,
+    Class,
+    Object,
+    Property,
+    Method,
+}
+
+impl SymbolKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            SymbolKind::Keyword => "keyword",
+            SymbolKind::Library => "library",
+            SymbolKind::Function => "function",
+            SymbolKind::Statement => "statement",
+            SymbolKind::Constant => "constant",
+            SymbolKind::Class => "class",
+            SymbolKind::Object => "object",
+            SymbolKind::Property => "property",
+            SymbolKind::Method => "method",
+        }
+    }
+}
+
+/// An entry of the catalogue, as it is looked up by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    /// The library the symbol belongs to, or `None` for keywords.
+    pub library: Option<&'static str>,
+    /// The name, qualified with the class for members, such as `App.Path`.
+    pub name: String,
+    pub kind: SymbolKind,
+    /// How the symbol would be declared in VB6, such as
+    /// `Function Left$(String, Length As Long) As String`.
+    pub signature: String,
+    /// The type of a constant, object or property, or what a function or
+    /// method returns.
+    pub type_name: Option<&'static str>,
+}
+
+impl Symbol {
+    fn procedure(library: &Library, procedure: &Procedure, string_form: bool) -> Symbol {
+        let name = if string_form {
+            format!("{}$", procedure.name)
+        } else {
+            procedure.name.to_owned()
+        };
+        let type_name = if string_form {
+            Some("String")
+        } else {
+            procedure.return_type
+        };
+
+        let (kind, signature) = match type_name {
+            Some(type_name) => (
+                SymbolKind::Function,
+                format!(
+                    "Function {}({}) As {}",
+                    name, procedure.parameters, type_name
+                ),
+            ),
+            None => (
+                SymbolKind::Statement,
+                format!("Sub {}({})", name, procedure.parameters),
+            ),
+        };
+
+        Symbol {
+            library: Some(library.name),
+            name,
+            kind,
+            signature,
+            type_name,
+        }
+    }
+
+    fn member(library: &Library, class: &Class, member: &Member) -> Symbol {
+        let name = format!("{}.{}", class.name, member.name);
+
+        let (kind, signature) = match (member.kind, member.return_type) {
+            (MemberKind::Property, type_name) => (
+                SymbolKind::Property,
+                format!("Property {} As {}", name, type_name.unwrap_or("Variant")),
+            ),
+            (MemberKind::Method, Some(type_name)) => (
+                SymbolKind::Method,
+                format!("Function {}({}) As {}", name, member.parameters, type_name),
+            ),
+            (MemberKind::Method, None) => (
+                SymbolKind::Method,
+                format!("Sub {}({})", name, member.parameters),
+            ),
+        };
+
+        Symbol {
+            library: Some(library.name),
+            name,
+            kind,
+            signature,
+            type_name: member.return_type,
+        }
+    }
+}
+
+/// Every entry in the catalogue: keywords first, then each library's.
+pub fn all_symbols() -> Vec<Symbol> {
+    let mut symbols: Vec<Symbol> = KEYWORDS
+        .iter()
+        .map(|keyword| Symbol {
+            library: None,
+            name: keyword.to_string(),
+            kind: SymbolKind::Keyword,
+            signature: keyword.to_string(),
+            type_name: None,
+        })
+        .collect();
+
+    for library in LIBRARIES {
+        symbols.push(Symbol {
+            library: Some(library.name),
+            name: library.name.to_owned(),
+            kind: SymbolKind::Library,
+            signature: format!("Library {}", library.name),
+            type_name: None,
+        });
+
+        for procedure in library.procedures {
+            symbols.push(Symbol::procedure(library, procedure, false));
+            if procedure.has_string_form {
+                symbols.push(Symbol::procedure(library, procedure, true));
+            }
+        }
+
+        for group in library.constants {
+            for name in group.names {
+                symbols.push(Symbol {
+                    library: Some(library.name),
+                    name: name.to_string(),
+                    kind: SymbolKind::Constant,
+                    signature: format!("Const {} As {}", name, group.type_name),
+                    type_name: Some(group.type_name),
+                });
+            }
+        }
+
+        for (name, class) in library.objects {
+            symbols.push(Symbol {
+                library: Some(library.name),
+                name: name.to_string(),
+                kind: SymbolKind::Object,
+                signature: format!("Global {} As {}.{}", name, library.name, class),
+                type_name: Some(class),
+            });
+        }
+
+        for class in library.classes {
+            symbols.push(Symbol {
+                library: Some(library.name),
+                name: class.name.to_owned(),
+                kind: SymbolKind::Class,
+                signature: format!("Class {}.{}", library.name, class.name),
+                type_name: None,
+            });
+            for member in class.members {
+                symbols.push(Symbol::member(library, class, member));
+            }
+        }
+    }
+
+    symbols
+}
+
+/// Every entry in the catalogue by the lower case name it is looked up by,
+/// without the class of members, so `App.Path` is under `path`.
+fn symbol_index() -> &'static HashMap<String, Vec<Symbol>> {
+    static SYMBOL_INDEX: OnceLock<HashMap<String, Vec<Symbol>>> = OnceLock::new();
+
+    SYMBOL_INDEX.get_or_init(|| {
+        let mut index: HashMap<String, Vec<Symbol>> = HashMap::new();
+        for symbol in all_symbols() {
+            let unqualified = symbol.name.rsplit('.').next().unwrap_or(&symbol.name);
+            index
+                .entry(unqualified.to_ascii_lowercase())
+                .or_default()
+                .push(symbol);
+        }
+        index
+    })
+}
+
+/// Finds the entries for a name, which may be qualified with a library, such
+/// as `VBA.Left`, or be a member of an object or class, such as `App.Path`.
+///
+/// Names are matched ignoring case, and a `$` suffix only matches functions
+/// with a `String` form.
+pub fn lookup(name: &str) -> Vec<Symbol> {
+    let (qualifier, unqualified) = match name.rsplit_once('.') {
+        Some((qualifier, unqualified)) => (Some(qualifier), unqualified),
+        None => (None, name),
+    };
+
+    let Some(symbols) = symbol_index().get(&unqualified.to_ascii_lowercase()) else {
+        return Vec::new();
+    };
+
+    symbols
+        .iter()
+        .filter(|symbol| {
+            let symbol_class = symbol.name.rsplit_once('.').map(|(class, _)| class);
+
+            let is_qualified_by = |qualifier: &str| match symbol_class {
+                // Members can be qualified with their class, or with an
+                // object of that class.
+                Some(class) => {
+                    class.eq_ignore_ascii_case(qualifier)
+                        || LIBRARIES.iter().any(|library| {
+                            library.objects.iter().any(|(object, object_class)| {
+                                object.eq_ignore_ascii_case(qualifier)
+                                    && object_class.eq_ignore_ascii_case(class)
+                            })
+                        })
+                }
+                None => symbol
+                    .library
+                    .is_some_and(|library| library.eq_ignore_ascii_case(qualifier)),
+            };
+
+            match qualifier {
+                Some(qualifier) => is_qualified_by(qualifier),
+                None => symbol_class.is_none(),
+            }
+        })
+        .cloned()
+        .collect()
+}
+
+/// Checks if a name is a keyword, or a function, statement, object, class or
+/// constant of the runtime, which code can use without declaring it. As with
+/// [`lookup`], `Left$` is built in but `Beep$` isn't.
+pub fn is_built_in(name: &str) -> bool {
+    symbol_index()
+        .get(&name.to_ascii_lowercase())
+        .is_some_and(|symbols| symbols.iter().any(|symbol| !symbol.name.contains('.')))
+}
+
+/// The properties and methods of a runtime class, such as `Form`.
+pub fn class_members(class_name: &str) -> &'static [Member] {
+    LIBRARIES
+        .iter()
+        .flat_map(|library| library.classes)
+        .find(|class| class.name.eq_ignore_ascii_case(class_name))
+        .map_or(&[], |class| class.members)
+}
+
+pub fn contains(names: &[&str], name: &str) -> bool {
+    names.iter().any(|known| known.eq_ignore_ascii_case(name))
+}
+
+/// Shows what the catalogue says about the given names, or everything in it
+/// when no names are given. Returns `false` if a name isn't in the catalogue.
+pub fn builtins_subcommand(names: &[String], format: InfoFormat) -> Result<bool> {
+    let mut found = true;
+    let symbols = if names.is_empty() {
+        all_symbols()
+    } else {
+        let mut symbols = Vec::new();
+        for name in names {
+            let matches = lookup(name);
+            if matches.is_empty() {
+                eprintln!("'{}' is not part of the VB6 runtime", name);
+                found = false;
+            }
+            symbols.extend(matches);
+        }
+        symbols
+    };
+
+    match format {
+        InfoFormat::Text => {
+            for symbol in &symbols {
+                match symbol.library {
+                    Some(library) => println!("{}: {}", library, symbol.signature),
+                    None => println!("{}", symbol.signature),
+                }
+            }
+        }
+        InfoFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&builtins_json(&symbols))?
+        ),
+        InfoFormat::Yaml => print!("{}", serde_yaml::to_string(&builtins_json(&symbols))?),
+    }
+
+    Ok(found)
+}
+
+#[derive(Serialize)]
+struct Catalogue<'a> {
+    version: u32,
+    symbols: Vec<SymbolJson<'a>>,
+}
+
+#[derive(Serialize)]
+struct SymbolJson<'a> {
+    library: Option<&'static str>,
+    name: &'a str,
+    kind: &'static str,
+    signature: &'a str,
+    #[serde(rename = "type")]
+    type_name: Option<&'static str>,
+}
+
+fn builtins_json(symbols: &[Symbol]) -> Catalogue<'_> {
+    Catalogue {
+        version: CATALOGUE_VERSION,
+        symbols: symbols
+            .iter()
+            .map(|symbol| SymbolJson {
+                library: symbol.library,
+                name: &symbol.name,
+                kind: symbol.kind.name(),
+                signature: &symbol.signature,
+                type_name: symbol.type_name,
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_looked_up_with_their_qualifiers() {
+        let signatures = |name| -> Vec<String> {
+            lookup(name)
+                .into_iter()
+                .map(|symbol| symbol.signature)
+                .collect()
+        };
+
+        assert_eq!(
+            signatures("left$"),
+            ["Function Left$(String, Length As Long) As String"]
+        );
+        assert_eq!(
+            signatures("VBA.Left"),
+            ["Function Left(String, Length As Long) As Variant"]
+        );
+        assert_eq!(signatures("App.Path"), ["Property App.Path As String"]);
+        assert_eq!(signatures("vbCrLf"), ["Const vbCrLf As String"]);
+        assert_eq!(signatures("Err"), ["Global Err As VBA.ErrObject"]);
+        assert!(signatures("Beep$").is_empty());
+        assert!(signatures("Path").is_empty());
+
+        assert!(is_built_in("vbKeyF12") && is_built_in("Mid$") && !is_built_in("Mesage"));
+    }
+
+    #[test]
+    fn constants_objects_classes_and_libraries_are_built_in() {
+        let kinds = |name| -> Vec<(Option<&str>, SymbolKind)> {
+            lookup(name)
+                .into_iter()
+                .map(|symbol| (symbol.library, symbol.kind))
+                .collect()
+        };
+
+        assert_eq!(kinds("VBMODAL"), [(Some("VBRUN"), SymbolKind::Constant)]);
+        assert_eq!(
+            kinds("Screen"),
+            [
+                (Some("VB"), SymbolKind::Object),
+                (Some("VB"), SymbolKind::Class)
+            ]
+        );
+        assert_eq!(kinds("collection"), [(Some("VBA"), SymbolKind::Class)]);
+        assert_eq!(kinds("stdole"), [(Some("stdole"), SymbolKind::Library)]);
+        assert_eq!(kinds("Then"), [(None, SymbolKind::Keyword)]);
+
+        // Library qualifiers only find what is in that library.
+        assert_eq!(kinds("vba.Collection"), [(Some("VBA"), SymbolKind::Class)]);
+        assert_eq!(
+            kinds("VBRUN.vbModal"),
+            [(Some("VBRUN"), SymbolKind::Constant)]
+        );
+        assert!(kinds("VB.vbModal").is_empty());
+        assert!(kinds("VBA.Screen").is_empty());
+
+        // Members are only found through their class or an object of it.
+        assert_eq!(kinds("Err.Raise"), [(Some("VBA"), SymbolKind::Method)]);
+        assert_eq!(
+            kinds("ErrObject.Raise"),
+            [(Some("VBA"), SymbolKind::Method)]
+        );
+        assert!(!is_built_in("Raise"));
+
+        for name in [
+            "vbModal",
+            "SCREEN",
+            "Collection",
+            "VBA",
+            "Now",
+            "Left$",
+            "Beep$",
+            "Now$",
+        ] {
+            assert_eq!(is_built_in(name), !lookup(name).is_empty(), "{}", name);
+        }
+        assert!(!is_built_in("Beep$") && !is_built_in("Now$") && is_built_in("Beep"));
+    }
+}
@@ -0,0 +1,188 @@
+use std::path::{Component, Path};
+
+use serde_json::{json, Value};
+
+use crate::diagnostic::{Diagnostic, DiagnosticKind};
+use crate::walk::absolute_path;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SOURCE_ROOT: &str = "%SRCROOT%";
+
+/// Builds a SARIF 2.1.0 log containing a single run of aspen.
+///
+/// Artifact locations are made relative to `repository_root` so code scanning
+/// tools can map them onto the files in the repository.
+pub fn sarif_log<'a>(
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic>,
+    repository_root: &Path,
+) -> Value {
+    let rules: Vec<Value> = DiagnosticKind::ALL
+        .iter()
+        .map(|kind| {
+            json!({
+                "id": kind.id(),
+                "shortDescription": { "text": kind.description() },
+                "defaultConfiguration": { "level": level(*kind) },
+            })
+        })
+        .collect();
+
+    let results: Vec<Value> = diagnostics
+        .into_iter()
+        .map(|diagnostic| sarif_result(diagnostic, repository_root))
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                },
+            },
+            "originalUriBaseIds": {
+                SOURCE_ROOT: { "uri": file_uri(repository_root, true) },
+            },
+            "results": results,
+        }],
+    })
+}
+
+fn level(kind: DiagnosticKind) -> &'static str {
+    match kind {
+        DiagnosticKind::NonEnglishEncoding
+        | DiagnosticKind::UnusedSuppression
+        | DiagnosticKind::InvalidOutputPath
+        | DiagnosticKind::InvalidVersion
+        | DiagnosticKind::ControlOutsideContainer
+        | DiagnosticKind::OverlappingControls
+        | DiagnosticKind::InvalidTabOrder
+        | DiagnosticKind::DuplicateAccessKey
+        | DiagnosticKind::ZeroSizeControl
+        | DiagnosticKind::OrphanedEventHandler
+        | DiagnosticKind::ControlArrayGap
+        | DiagnosticKind::UnusedProcedure
+        | DiagnosticKind::UnusedVariable
+        | DiagnosticKind::UnusedConstant
+        | DiagnosticKind::UnusedDeclare
+        | DiagnosticKind::UnreachableProcedure
+        | DiagnosticKind::ImplicitVariant
+        | DiagnosticKind::UndeclaredIdentifier
+        | DiagnosticKind::UnknownMember
+        | DiagnosticKind::ErrorHandlerFallThrough
+        | DiagnosticKind::SwallowedErrors
+        | DiagnosticKind::UnhandledEntryPoint => "warning",
+        DiagnosticKind::UndescribedReference => "note",
+        _ => "error",
+    }
+}
+
+fn sarif_result(diagnostic: &Diagnostic, repository_root: &Path) -> Value {
+    let absolute_path = absolute_path(&diagnostic.path);
+
+    let artifact_location = match absolute_path.strip_prefix(repository_root) {
+        Ok(relative_path) => json!({
+            "uri": relative_uri(relative_path),
+            "uriBaseId": SOURCE_ROOT,
+        }),
+        Err(_) => json!({ "uri": file_uri(&absolute_path, false) }),
+    };
+
+    let mut physical_location = json!({ "artifactLocation": artifact_location });
+    if let Some(region) = diagnostic.region {
+        physical_location["region"] = json!({
+            "startLine": region.line,
+            "startColumn": region.column,
+        });
+    }
+
+    let rule_index = DiagnosticKind::ALL
+        .iter()
+        .position(|kind| *kind == diagnostic.kind)
+        .unwrap_or_default();
+
+    json!({
+        "ruleId": diagnostic.kind.id(),
+        "ruleIndex": rule_index,
+        "level": level(diagnostic.kind),
+        "message": { "text": diagnostic.message },
+        "locations": [{ "physicalLocation": physical_location }],
+    })
+}
+
+fn relative_uri(relative_path: &Path) -> String {
+    relative_path
+        .components()
+        .map(|component| percent_encode(&component.as_os_str().to_string_lossy()))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn file_uri(path: &Path, is_dir: bool) -> String {
+    let mut uri = String::from("file://");
+
+    for component in path.components() {
+        match component {
+            Component::RootDir => {}
+            Component::Prefix(prefix) => {
+                uri.push('/');
+                uri.push_str(&prefix.as_os_str().to_string_lossy().replace('\\', "/"));
+            }
+            component => {
+                uri.push('/');
+                uri.push_str(&percent_encode(&component.as_os_str().to_string_lossy()));
+            }
+        }
+    }
+
+    if is_dir {
+        uri.push('/');
+    }
+
+    uri
+}
+
+fn percent_encode(segment: &str) -> String {
+    let mut encoded = String::new();
+
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            byte => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::Region;
+
+    #[test]
+    fn results_are_relative_to_the_repository_root() {
+        let repository_root = Path::new("/repository");
+        let diagnostic = Diagnostic::new(
+            DiagnosticKind::MissingFile,
+            Path::new("/repository/My Projects/Project1.vbp"),
+            "Module not found: /repository/My Projects/Module1.bas",
+        )
+        .with_region(Some(Region { line: 3, column: 8 }));
+
+        let log = serde_json::to_string_pretty(&sarif_log([&diagnostic], repository_root)).unwrap();
+
+        assert!(log.contains(r#""uri": "My%20Projects/Project1.vbp""#));
+        assert!(log.contains(r#""uriBaseId": "%SRCROOT%""#));
+        assert!(log.contains(r#""uri": "file:///repository/""#));
+        assert!(log.contains(r#""ruleId": "missing-file""#));
+        assert!(log.contains(r#""startLine": 3"#));
+        assert!(log.contains(r#""startColumn": 8"#));
+    }
+}
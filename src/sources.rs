@@ -0,0 +1,377 @@
+//! The code of every class, module, and form in a project, for analyses that
+//! look across files rather than at one file at a time.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+use crate::check::{join_parent_project_path, project_directory, SourceFileKind};
+use crate::code::CodeModule;
+use crate::designer::{parse_designer, Control};
+use crate::info::{format_guid, parse_project, read_project};
+use crate::project_settings::attribute_value;
+use crate::typelib::LibraryReference;
+use vb6parse::parsers::{CompileTargetType, VB6ObjectReference, VB6Project, VB6ProjectReference};
+
+/// The objects whose events are handled by `Object_Event` procedures in
+/// every form and class, besides its controls and `WithEvents` variables.
+const BUILT_IN_EVENT_SOURCES: [&str; 5] =
+    ["form", "mdiform", "class", "usercontrol", "userdocument"];
+
+/// A class, module, or form and the declarations in its code.
+pub struct SourceFile {
+    pub path: PathBuf,
+    pub kind: SourceFileKind,
+    /// The `VB_Name` the code refers to the file by.
+    pub name: String,
+    pub contents: Vec<u8>,
+    pub code: CodeModule,
+}
+
+impl SourceFile {
+    pub fn read(kind: SourceFileKind, path: &Path) -> Result<SourceFile> {
+        let contents = std::fs::read(path)
+            .map_err(|e| anyhow!("Unable to read '{}': {}", path.display(), e))?;
+
+        Ok(SourceFile::new(kind, path, contents))
+    }
+
+    pub fn new(kind: SourceFileKind, path: &Path, contents: Vec<u8>) -> SourceFile {
+        // VB6 falls back to the file name when VB_Name is missing.
+        let name = attribute_value(&contents, "VB_Name").unwrap_or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+        let code = CodeModule::parse(&contents);
+
+        SourceFile {
+            path: path.to_path_buf(),
+            kind,
+            name,
+            contents,
+            code,
+        }
+    }
+
+    /// The lower case names of the objects whose events the file can handle.
+    pub fn event_sources(&self) -> HashSet<String> {
+        let mut sources: HashSet<String> = BUILT_IN_EVENT_SOURCES
+            .iter()
+            .map(|name| name.to_string())
+            .chain(
+                self.code
+                    .variables
+                    .iter()
+                    .map(|variable| variable.name.to_ascii_lowercase()),
+            )
+            .chain(
+                self.code
+                    .interfaces
+                    .iter()
+                    .map(|interface| interface.to_ascii_lowercase()),
+            )
+            .collect();
+
+        if self.kind == SourceFileKind::Form {
+            if let Ok(form) = parse_designer(&self.contents) {
+                add_control_names(&form, &mut sources);
+            }
+        }
+
+        sources
+    }
+}
+
+fn add_control_names(container: &Control, names: &mut HashSet<String>) {
+    for control in &container.children {
+        names.insert(control.name.to_ascii_lowercase());
+        add_control_names(control, names);
+    }
+}
+
+/// Checks if a procedure is named like a handler for an event of one of
+/// `event_sources`, or an implementation of an interface's member, both of
+/// which VB6 calls by name.
+pub fn is_event_handler(name: &str, event_sources: &HashSet<String>) -> bool {
+    name.rsplit_once('_')
+        .is_some_and(|(source, _)| event_sources.contains(&source.to_ascii_lowercase()))
+}
+
+/// The source files of a project, or of files given on their own.
+pub struct Sources {
+    /// The `.vbp` the files are listed in, or `None` for files given on the
+    /// command line.
+    pub project_path: Option<PathBuf>,
+    /// The project's startup form or `Sub Main`, if it has one.
+    pub startup: Option<String>,
+    /// Whether other programs can use the project's public classes, as with
+    /// ActiveX DLLs, EXEs and controls.
+    pub is_activex: bool,
+    /// The COM libraries and controls the project references.
+    pub references: Vec<LibraryReference>,
+    pub files: Vec<SourceFile>,
+}
+
+impl Sources {
+    /// Reads the classes, modules and forms `paths` name, which may be `.vbp`
+    /// projects or the files themselves. Files given on their own are
+    /// grouped together after the projects.
+    ///
+    /// Projects and files that can't be read are left out, and their errors
+    /// returned alongside those that could.
+    pub fn load(paths: &[PathBuf]) -> (Vec<Sources>, Vec<anyhow::Error>) {
+        let mut all_sources = Vec::new();
+        let mut loose_files = Vec::new();
+        let mut errors = Vec::new();
+
+        for path in paths {
+            let is_project = path
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("vbp"));
+
+            if is_project {
+                let project_contents = match read_project(path) {
+                    Ok(project_contents) => project_contents,
+                    Err(e) => {
+                        errors.push(e);
+                        continue;
+                    }
+                };
+                let project = match parse_project(path, &project_contents) {
+                    Ok(project) => project,
+                    Err(e) => {
+                        errors.push(e);
+                        continue;
+                    }
+                };
+
+                let (sources, project_errors) = Sources::from_project(path, &project);
+                all_sources.push(sources);
+                errors.extend(project_errors);
+                continue;
+            }
+
+            let Some(kind) = SourceFileKind::from_path(path) else {
+                errors.push(anyhow!(
+                    "'{}' is not a .vbp project, or a .bas, .cls or .frm file",
+                    path.display()
+                ));
+                continue;
+            };
+            match SourceFile::read(kind, path) {
+                Ok(file) => loose_files.push(file),
+                Err(e) => errors.push(e),
+            }
+        }
+        if !loose_files.is_empty() {
+            all_sources.push(Sources {
+                project_path: None,
+                startup: None,
+                is_activex: false,
+                references: Vec::new(),
+                files: loose_files,
+            });
+        }
+
+        (all_sources, errors)
+    }
+
+    /// Reads the classes, modules and forms listed in a project, returning
+    /// the errors of those that couldn't be read alongside the rest.
+    pub fn from_project(
+        project_path: &Path,
+        project: &VB6Project,
+    ) -> (Sources, Vec<anyhow::Error>) {
+        let directory = project_directory(project_path);
+
+        let members = project
+            .modules
+            .iter()
+            .map(|module| (SourceFileKind::Module, module.path.to_string()))
+            .chain(
+                project
+                    .classes
+                    .iter()
+                    .map(|class| (SourceFileKind::Class, class.path.to_string())),
+            )
+            .chain(
+                project
+                    .forms
+                    .iter()
+                    .map(|form| (SourceFileKind::Form, form.to_string())),
+            );
+
+        let mut files = Vec::new();
+        let mut errors = Vec::new();
+        for (kind, member_path) in members {
+            match SourceFile::read(kind, &join_parent_project_path(directory, &member_path)) {
+                Ok(file) => files.push(file),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        let startup = project
+            .startup
+            .map(|startup| startup.to_string())
+            .filter(|startup| !startup.is_empty() && !startup.eq_ignore_ascii_case("(None)"));
+
+        let references = project
+            .references
+            .iter()
+            .filter_map(|reference| match reference {
+                VB6ProjectReference::Compiled {
+                    uuid,
+                    unknown1,
+                    description,
+                    ..
+                } => Some(LibraryReference {
+                    guid: format_guid(uuid),
+                    version: unknown1.to_string(),
+                    name: description.to_string(),
+                }),
+                VB6ProjectReference::SubProject { .. } => None,
+            })
+            .chain(project.objects.iter().filter_map(|object| match object {
+                VB6ObjectReference::Compiled {
+                    uuid,
+                    version,
+                    file_name,
+                    ..
+                } => Some(LibraryReference {
+                    guid: format_guid(uuid),
+                    version: version.to_string(),
+                    name: file_name.to_string(),
+                }),
+                VB6ObjectReference::Project { .. } => None,
+            }))
+            .collect();
+
+        let sources = Sources {
+            project_path: Some(project_path.to_path_buf()),
+            startup,
+            is_activex: project.project_type != CompileTargetType::Exe,
+            references,
+            files,
+        };
+
+        (sources, errors)
+    }
+
+    /// The project path for output, or `None` for files given on their own.
+    pub fn display_project(&self) -> Option<String> {
+        self.project_path
+            .as_ref()
+            .map(|project_path| project_path.display().to_string())
+    }
+}
+
+/// Builds the sources the analyses' tests run against.
+#[cfg(test)]
+pub mod testing {
+    use super::*;
+    use crate::diagnostic::Diagnostic;
+
+    /// The sources of `App.vbp`, an EXE listing `files` as `(kind, path,
+    /// contents)`.
+    pub fn sources(files: &[(SourceFileKind, &str, &str)]) -> Sources {
+        Sources {
+            project_path: Some(PathBuf::from("App.vbp")),
+            startup: None,
+            is_activex: false,
+            references: Vec::new(),
+            files: files
+                .iter()
+                .map(|(kind, path, contents)| {
+                    SourceFile::new(*kind, Path::new(path), contents.as_bytes().to_vec())
+                })
+                .collect(),
+        }
+    }
+
+    /// Each diagnostic as its line followed by its message.
+    pub fn messages(diagnostics: impl IntoIterator<Item = Diagnostic>) -> Vec<String> {
+        diagnostics
+            .into_iter()
+            .map(|diagnostic| format!("{} {}", diagnostic.region.unwrap().line, diagnostic.message))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unreadable_paths_are_reported_without_stopping_the_load() {
+        let directory = std::env::temp_dir()
+            .join(format!("aspen-sources-{}", std::process::id()))
+            .join("unreadable_paths_are_reported_without_stopping_the_load");
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+
+        std::fs::write(
+            directory.join("App.vbp"),
+            "Type=Exe\r\nModule=Main; Main.bas\r\nClass=Gone; Gone.cls\r\n",
+        )
+        .unwrap();
+        std::fs::write(
+            directory.join("Main.bas"),
+            "Attribute VB_Name = \"Main\"\r\nSub Main()\r\nEnd Sub\r\n",
+        )
+        .unwrap();
+        std::fs::write(
+            directory.join("Helpers.bas"),
+            "Attribute VB_Name = \"Helpers\"\r\n",
+        )
+        .unwrap();
+        std::fs::write(directory.join("Notes.txt"), "").unwrap();
+
+        let paths: Vec<PathBuf> = [
+            "Missing.vbp",
+            "App.vbp",
+            "Notes.txt",
+            "Absent.bas",
+            "Helpers.bas",
+        ]
+        .iter()
+        .map(|name| directory.join(name))
+        .collect();
+
+        let (all_sources, errors) = Sources::load(&paths);
+
+        let loaded: Vec<(Option<PathBuf>, Vec<&str>)> = all_sources
+            .iter()
+            .map(|sources| {
+                (
+                    sources.project_path.clone(),
+                    sources
+                        .files
+                        .iter()
+                        .map(|file| file.name.as_str())
+                        .collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            loaded,
+            [
+                (Some(directory.join("App.vbp")), vec!["Main"]),
+                (None, vec!["Helpers"]),
+            ]
+        );
+
+        let messages: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+        assert_eq!(messages.len(), 4, "{:?}", messages);
+        assert!(messages[0].starts_with("Unable to read project '"));
+        assert!(messages[0].contains("Missing.vbp"));
+        assert!(messages[1].contains("Gone.cls"));
+        assert!(
+            messages[2].ends_with("Notes.txt' is not a .vbp project, or a .bas, .cls or .frm file")
+        );
+        assert!(messages[3].contains("Absent.bas"));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
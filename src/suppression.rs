@@ -0,0 +1,361 @@
+use std::path::{Path, PathBuf};
+
+use vb6parse::VB6Token;
+
+use crate::diagnostic::{Diagnostic, DiagnosticKind, Region};
+use crate::tokens::{token_text, SourceMap};
+
+const IGNORE_FILE_DIRECTIVE: &str = "aspen:ignore-file";
+const IGNORE_DIRECTIVE: &str = "aspen:ignore";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SuppressionScope {
+    File,
+    /// An inclusive range of 1-based lines.
+    Lines(usize, usize),
+}
+
+#[derive(Debug)]
+struct Suppression {
+    /// Lowercased rule ids. Empty suppresses every rule.
+    rules: Vec<String>,
+    scope: SuppressionScope,
+    region: Region,
+    used: bool,
+}
+
+/// The `aspen:ignore` comments found in a single file.
+///
+/// In code, `' aspen:ignore <rule>` suppresses findings on the line it is on,
+/// or on the next line when the comment is on a line of its own, and
+/// `' aspen:ignore-file <rule>` suppresses them throughout the file. Rules may
+/// be separated by commas or spaces, and anything after `--` is treated as
+/// the reason for the suppression. A `.vbp` file may contain the same
+/// comments on lines of their own to suppress project-level findings.
+#[derive(Debug)]
+pub struct Suppressions {
+    path: PathBuf,
+    suppressions: Vec<Suppression>,
+}
+
+impl Suppressions {
+    /// Collects the suppressions from the comment tokens of a parsed module,
+    /// class, or form.
+    pub fn from_tokens(path: &Path, source: &[u8], tokens: &[VB6Token]) -> Self {
+        let source_map = SourceMap::new(source);
+
+        let suppressions = tokens
+            .iter()
+            .filter(|token| matches!(token, VB6Token::Comment(_)))
+            .filter_map(|token| {
+                let text = token_text(token);
+                let offset = source_map.offset_of(text)?;
+                let region = source_map.region_of_offset(offset);
+
+                let comment = String::from_utf8_lossy(text);
+                let comment = comment.strip_prefix('\'').unwrap_or(&comment);
+
+                parse_directive(comment, region, source_map.starts_line(offset))
+            })
+            .collect();
+
+        Suppressions {
+            path: path.to_path_buf(),
+            suppressions,
+        }
+    }
+
+    /// Collects the suppressions from the comment lines of a `.vbp` file.
+    pub fn from_project(path: &Path, contents: &[u8]) -> Self {
+        let suppressions = contents
+            .split(|&byte| byte == b'\n')
+            .enumerate()
+            .filter_map(|(line_index, line)| {
+                let line = String::from_utf8_lossy(line);
+                let indent = line.len() - line.trim_start().len();
+                let comment = line.trim_start().strip_prefix('\'')?;
+
+                let region = Region {
+                    line: line_index + 1,
+                    column: indent + 1,
+                };
+
+                parse_directive(comment, region, true)
+            })
+            .collect();
+
+        Suppressions {
+            path: path.to_path_buf(),
+            suppressions,
+        }
+    }
+
+    /// Checks if `diagnostic` is suppressed, marking the suppression used.
+    pub fn suppresses(&mut self, diagnostic: &Diagnostic) -> bool {
+        if diagnostic.path != self.path {
+            return false;
+        }
+
+        let rule_id = diagnostic.kind.id();
+        let line = diagnostic.region.map(|region| region.line);
+
+        let suppression = self.suppressions.iter_mut().find(|suppression| {
+            let in_scope = match (suppression.scope, line) {
+                (SuppressionScope::File, _) => true,
+                (SuppressionScope::Lines(first, last), Some(line)) => {
+                    (first..=last).contains(&line)
+                }
+                (SuppressionScope::Lines(..), None) => false,
+            };
+
+            in_scope
+                && (suppression.rules.is_empty()
+                    || suppression.rules.iter().any(|rule| rule == rule_id))
+        });
+
+        match suppression {
+            Some(suppression) => {
+                suppression.used = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Reports every suppression that didn't suppress anything, including
+    /// those naming rules that don't exist.
+    pub fn unused(&self) -> Vec<Diagnostic> {
+        self.suppressions
+            .iter()
+            .filter(|suppression| !suppression.used)
+            .map(|suppression| {
+                let unknown_rules: Vec<&str> = suppression
+                    .rules
+                    .iter()
+                    .filter(|rule| !DiagnosticKind::ALL.iter().any(|kind| kind.id() == *rule))
+                    .map(String::as_str)
+                    .collect();
+
+                let message = match (unknown_rules.is_empty(), suppression.rules.is_empty()) {
+                    (false, _) => format!(
+                        "Suppression of unknown rule '{}'",
+                        unknown_rules.join("', '")
+                    ),
+                    (true, true) => "Suppression does not suppress any findings".to_owned(),
+                    (true, false) => format!(
+                        "Suppression of '{}' does not suppress any findings",
+                        suppression.rules.join("', '")
+                    ),
+                };
+
+                Diagnostic::new(DiagnosticKind::UnusedSuppression, &self.path, message)
+                    .with_region(Some(suppression.region))
+            })
+            .collect()
+    }
+}
+
+/// Blanks out the comment lines of a `.vbp` file, which vb6parse doesn't
+/// accept, keeping their line endings so line numbers are unchanged.
+pub fn strip_project_comments(contents: &[u8]) -> Vec<u8> {
+    let mut stripped = Vec::with_capacity(contents.len());
+
+    for line in contents.split_inclusive(|&byte| byte == b'\n') {
+        if line.trim_ascii_start().starts_with(b"'") {
+            let line_ending_start = line
+                .iter()
+                .position(|&byte| byte == b'\r' || byte == b'\n')
+                .unwrap_or(line.len());
+            stripped.extend_from_slice(&line[line_ending_start..]);
+        } else {
+            stripped.extend_from_slice(line);
+        }
+    }
+
+    stripped
+}
+
+/// Parses the text of a comment, after its `'`, into a suppression.
+fn parse_directive(comment: &str, region: Region, starts_line: bool) -> Option<Suppression> {
+    let comment = comment.trim_start();
+
+    let (scope, arguments) = if let Some(arguments) = comment.strip_prefix(IGNORE_FILE_DIRECTIVE) {
+        (SuppressionScope::File, arguments)
+    } else if let Some(arguments) = comment.strip_prefix(IGNORE_DIRECTIVE) {
+        // A comment on its own line applies to the line after it as well.
+        let last_line = if starts_line {
+            region.line + 1
+        } else {
+            region.line
+        };
+
+        (SuppressionScope::Lines(region.line, last_line), arguments)
+    } else {
+        return None;
+    };
+
+    // 'aspen:ignored' or similar isn't a directive.
+    if !arguments.is_empty() && !arguments.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let arguments = match arguments.find("--") {
+        Some(reason_start) => &arguments[..reason_start],
+        None => arguments,
+    };
+
+    let rules = arguments
+        .split(|character: char| character == ',' || character.is_whitespace())
+        .filter(|rule| !rule.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect();
+
+    Some(Suppression {
+        rules,
+        scope,
+        region,
+        used: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vb6parse::parsers::{VB6ModuleFile, VB6Project};
+
+    fn module_suppressions(path: &Path, source: &str) -> Suppressions {
+        let module = VB6ModuleFile::parse("M.bas".to_owned(), source.as_bytes()).unwrap();
+        Suppressions::from_tokens(path, source.as_bytes(), &module.tokens)
+    }
+
+    fn finding(kind: DiagnosticKind, path: &Path, line: Option<usize>) -> Diagnostic {
+        Diagnostic::new(kind, path, "finding")
+            .with_region(line.map(|line| Region { line, column: 1 }))
+    }
+
+    #[test]
+    fn line_suppressions_cover_their_own_line_or_the_next() {
+        let path = Path::new("M.bas");
+        let mut suppressions = module_suppressions(
+            path,
+            concat!(
+                "Attribute VB_Name = \"M\"\r\n",
+                "Public X ' aspen:ignore implicit-variant\r\n",
+                "' aspen:ignore implicit-variant -- kept for old callers\r\n",
+                "Public Y\r\n",
+                "Public Z\r\n",
+            ),
+        );
+
+        let implicit_variant = |line| finding(DiagnosticKind::ImplicitVariant, path, Some(line));
+
+        assert!(suppressions.suppresses(&implicit_variant(2)));
+        assert!(suppressions.suppresses(&implicit_variant(4)));
+        assert!(!suppressions.suppresses(&implicit_variant(5)));
+        assert!(!suppressions.suppresses(&finding(
+            DiagnosticKind::UndeclaredIdentifier,
+            path,
+            Some(2)
+        )));
+        assert!(!suppressions.suppresses(&finding(
+            DiagnosticKind::ImplicitVariant,
+            Path::new("Other.bas"),
+            Some(2)
+        )));
+        assert!(!suppressions.suppresses(&finding(DiagnosticKind::ImplicitVariant, path, None)));
+
+        assert!(suppressions.unused().is_empty());
+    }
+
+    #[test]
+    fn file_suppressions_cover_the_whole_file() {
+        let path = Path::new("M.bas");
+        let mut suppressions = module_suppressions(
+            path,
+            concat!(
+                "Attribute VB_Name = \"M\"\r\n",
+                "' aspen:ignore-file implicit-variant, undeclared-identifier\r\n",
+                "Public X\r\n",
+            ),
+        );
+
+        assert!(suppressions.suppresses(&finding(DiagnosticKind::ImplicitVariant, path, Some(40))));
+        assert!(suppressions.suppresses(&finding(
+            DiagnosticKind::UndeclaredIdentifier,
+            path,
+            None
+        )));
+        assert!(!suppressions.suppresses(&finding(DiagnosticKind::UnusedVariable, path, Some(3))));
+
+        let mut suppressions =
+            module_suppressions(path, "Attribute VB_Name = \"M\"\r\n' aspen:ignore-file\r\n");
+        assert!(suppressions.suppresses(&finding(DiagnosticKind::UnusedVariable, path, Some(3))));
+    }
+
+    #[test]
+    fn project_comments_suppress_project_findings() {
+        let path = Path::new("Project1.vbp");
+        let contents = concat!(
+            "Type=Exe\r\n",
+            "  ' aspen:ignore invalid-startup -- the form is added by the build\r\n",
+            "Startup=\"frmMissing\"\r\n",
+        );
+
+        let stripped = strip_project_comments(contents.as_bytes());
+        assert_eq!(
+            String::from_utf8(stripped.clone()).unwrap(),
+            "Type=Exe\r\n\r\nStartup=\"frmMissing\"\r\n"
+        );
+        assert!(VB6Project::parse("Project1.vbp".to_owned(), &stripped).is_ok());
+
+        let mut suppressions = Suppressions::from_project(path, contents.as_bytes());
+        assert!(suppressions.suppresses(&finding(DiagnosticKind::InvalidStartup, path, Some(3))));
+        assert!(!suppressions.suppresses(&finding(DiagnosticKind::InvalidStartup, path, Some(1))));
+
+        let unused = Suppressions::from_project(path, contents.as_bytes()).unused();
+        assert_eq!(unused.len(), 1);
+        assert_eq!(unused[0].region, Some(Region { line: 2, column: 3 }));
+    }
+
+    #[test]
+    fn unused_and_unknown_suppressions_are_reported() {
+        let path = Path::new("M.bas");
+        let mut suppressions = module_suppressions(
+            path,
+            concat!(
+                "Attribute VB_Name = \"M\"\r\n",
+                "' aspen:ignore implicit-variant\r\n",
+                "Public X\r\n",
+                "' aspen:ignore unused-variable\r\n",
+                "Public Y\r\n",
+                "' aspen:ignore no-such-rule\r\n",
+                "Public Z\r\n",
+                "' aspen:ignore\r\n",
+                "' aspen:ignored implicit-variant\r\n",
+            ),
+        );
+
+        assert!(suppressions.suppresses(&finding(DiagnosticKind::ImplicitVariant, path, Some(3))));
+
+        let unused: Vec<(usize, String)> = suppressions
+            .unused()
+            .into_iter()
+            .map(|diagnostic| {
+                assert_eq!(diagnostic.kind, DiagnosticKind::UnusedSuppression);
+                (diagnostic.region.unwrap().line, diagnostic.message)
+            })
+            .collect();
+
+        assert_eq!(
+            unused,
+            [
+                (
+                    4,
+                    "Suppression of 'unused-variable' does not suppress any findings".to_owned()
+                ),
+                (6, "Suppression of unknown rule 'no-such-rule'".to_owned()),
+                (8, "Suppression does not suppress any findings".to_owned()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use vb6parse::VB6Token;
+
+use crate::diagnostic::Region;
+
+macro_rules! token_text {
+    ($token:expr, $($variant:ident),* $(,)?) => {
+        match $token {
+            $(VB6Token::$variant(text) => text,)*
+        }
+    };
+}
+
+/// Returns the source text a token was parsed from.
+pub fn token_text<'a>(token: &VB6Token<'a>) -> &'a [u8] {
+    token_text!(
+        token,
+        Whitespace,
+        Newline,
+        Comment,
+        ReDimKeyword,
+        DimKeyword,
+        DeclareKeyword,
+        LibKeyword,
+        WithKeyword,
+        OptionKeyword,
+        ExplicitKeyword,
+        PrivateKeyword,
+        PublicKeyword,
+        ConstKeyword,
+        AsKeyword,
+        ByValKeyword,
+        ByRefKeyword,
+        OptionalKeyword,
+        FunctionKeyword,
+        SubKeyword,
+        EndKeyword,
+        TrueKeyword,
+        FalseKeyword,
+        EnumKeyword,
+        TypeKeyword,
+        BooleanKeyword,
+        ByteKeyword,
+        LongKeyword,
+        SingleKeyword,
+        StringKeyword,
+        IntegerKeyword,
+        StringLiteral,
+        IfKeyword,
+        ElseKeyword,
+        AndKeyword,
+        OrKeyword,
+        NotKeyword,
+        ThenKeyword,
+        GotoKeyword,
+        ExitKeyword,
+        ForKeyword,
+        ToKeyword,
+        StepKeyword,
+        NextKeyword,
+        DollarSign,
+        Underscore,
+        Ampersand,
+        Percent,
+        Octothorpe,
+        LeftParanthesis,
+        RightParanthesis,
+        LeftSquareBracket,
+        RightSquareBracket,
+        Comma,
+        Semicolon,
+        AtSign,
+        ExclamationMark,
+        EqualityOperator,
+        LessThanOperator,
+        GreaterThanOperator,
+        MultiplicationOperator,
+        SubtractionOperator,
+        AdditionOperator,
+        DivisionOperator,
+        ForwardSlashOperator,
+        PeriodOperator,
+        ColonOperator,
+        ExponentiationOperator,
+        VariableName,
+        Number,
+    )
+}
+
+/// Maps the tokens parsed from a file back to lines and columns within it.
+///
+/// vb6parse tokens borrow directly from the source they were parsed from, so
+/// a token's position is its offset within that source.
+pub struct SourceMap<'a> {
+    source: &'a [u8],
+    line_starts: Vec<usize>,
+}
+
+impl<'a> SourceMap<'a> {
+    pub fn new(source: &'a [u8]) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(
+                source
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &byte)| byte == b'\n')
+                    .map(|(offset, _)| offset + 1),
+            )
+            .collect();
+
+        SourceMap {
+            source,
+            line_starts,
+        }
+    }
+
+    /// The byte offset of `text` within the source, if it was taken from it.
+    pub fn offset_of(&self, text: &[u8]) -> Option<usize> {
+        let start = self.source.as_ptr() as usize;
+        let position = text.as_ptr() as usize;
+
+        if position < start || position + text.len() > start + self.source.len() {
+            return None;
+        }
+
+        Some(position - start)
+    }
+
+    /// The 1-based line and column of a byte offset.
+    pub fn region_of_offset(&self, offset: usize) -> Region {
+        let line_index = match self.line_starts.binary_search(&offset) {
+            Ok(line_index) => line_index,
+            Err(next_line_index) => next_line_index - 1,
+        };
+
+        Region {
+            line: line_index + 1,
+            column: offset - self.line_starts[line_index] + 1,
+        }
+    }
+
+    /// Checks if only whitespace precedes `offset` on its line.
+    pub fn starts_line(&self, offset: usize) -> bool {
+        let line_start = self.line_starts[self.region_of_offset(offset).line - 1];
+
+        self.source[line_start..offset]
+            .iter()
+            .all(|byte| byte.is_ascii_whitespace())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vb6parse::parsers::VB6ModuleFile;
+
+    #[test]
+    fn tokens_map_back_to_lines_and_columns() {
+        let source = "Attribute VB_Name = \"M\"\r\nPublic X ' note\r\n  ' own line\r\n";
+        let module = VB6ModuleFile::parse("M.bas".to_owned(), source.as_bytes()).unwrap();
+        let source_map = SourceMap::new(source.as_bytes());
+
+        let comments: Vec<(Region, bool, &[u8])> = module
+            .tokens
+            .iter()
+            .filter(|token| matches!(token, VB6Token::Comment(_)))
+            .map(|token| {
+                let text = token_text(token);
+                let offset = source_map.offset_of(text).unwrap();
+                (
+                    source_map.region_of_offset(offset),
+                    source_map.starts_line(offset),
+                    text,
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            comments,
+            [
+                (
+                    Region {
+                        line: 2,
+                        column: 10
+                    },
+                    false,
+                    &b"' note"[..]
+                ),
+                (Region { line: 3, column: 3 }, true, &b"' own line"[..]),
+            ]
+        );
+
+        assert_eq!(source_map.offset_of(b"not from the source"), None);
+        assert_eq!(
+            source_map.region_of_offset(source.len()),
+            Region { line: 4, column: 1 }
+        );
+    }
+}
@@ -0,0 +1,680 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+use crate::code::{CodeModule, Enumeration, Parameter, ProcedureKind, Visibility};
+
+/// Descriptions of common libraries that ship with aspen, by file name.
+const BUILT_IN_LIBRARIES: [(&str, &str); 4] = [
+    ("adodb.json", include_str!("typelibs/adodb.json")),
+    ("mscomctl.json", include_str!("typelibs/mscomctl.json")),
+    ("mscomdlg.json", include_str!("typelibs/mscomdlg.json")),
+    ("scripting.json", include_str!("typelibs/scripting.json")),
+];
+
+/// The kind of a member of a class's public interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemberKind {
+    Sub,
+    Function,
+    PropertyGet,
+    PropertyLet,
+    PropertySet,
+    Event,
+    /// A public variable, which VB6 exposes as a property.
+    Variable,
+}
+
+impl MemberKind {
+    const ALL: [MemberKind; 7] = [
+        MemberKind::Sub,
+        MemberKind::Function,
+        MemberKind::PropertyGet,
+        MemberKind::PropertyLet,
+        MemberKind::PropertySet,
+        MemberKind::Event,
+        MemberKind::Variable,
+    ];
+
+    /// The name of the kind in type library dumps.
+    pub fn id(self) -> &'static str {
+        match self {
+            MemberKind::Sub => "sub",
+            MemberKind::Function => "function",
+            MemberKind::PropertyGet => "property-get",
+            MemberKind::PropertyLet => "property-let",
+            MemberKind::PropertySet => "property-set",
+            MemberKind::Event => "event",
+            MemberKind::Variable => "variable",
+        }
+    }
+
+    /// How the kind is declared in code.
+    pub fn keyword(self) -> &'static str {
+        match self {
+            MemberKind::Sub => "Sub",
+            MemberKind::Function => "Function",
+            MemberKind::PropertyGet => "Property Get",
+            MemberKind::PropertyLet => "Property Let",
+            MemberKind::PropertySet => "Property Set",
+            MemberKind::Event => "Event",
+            MemberKind::Variable => "Public",
+        }
+    }
+}
+
+impl From<ProcedureKind> for MemberKind {
+    fn from(kind: ProcedureKind) -> Self {
+        match kind {
+            ProcedureKind::Sub => MemberKind::Sub,
+            ProcedureKind::Function => MemberKind::Function,
+            ProcedureKind::PropertyGet => MemberKind::PropertyGet,
+            ProcedureKind::PropertyLet => MemberKind::PropertyLet,
+            ProcedureKind::PropertySet => MemberKind::PropertySet,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemberParameter {
+    pub name: String,
+    /// The parameter's type, with implicit Variants spelled out.
+    pub type_name: String,
+    pub by_val: bool,
+    pub optional: bool,
+    pub param_array: bool,
+    pub is_array: bool,
+}
+
+impl From<&Parameter> for MemberParameter {
+    fn from(parameter: &Parameter) -> Self {
+        MemberParameter {
+            name: parameter.name.clone(),
+            type_name: type_or_variant(parameter.type_name.as_deref()),
+            by_val: parameter.by_val,
+            optional: parameter.optional,
+            param_array: parameter.param_array,
+            is_array: parameter.is_array,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub name: String,
+    pub kind: MemberKind,
+    pub parameters: Vec<MemberParameter>,
+    /// The return type of functions and property gets, or a variable's type.
+    pub return_type: Option<String>,
+    /// The 1-based line the member is declared on, when read from code.
+    pub line: Option<usize>,
+}
+
+impl Member {
+    /// The member's signature as VB6 would declare it, without parameter
+    /// names since they don't affect compatibility.
+    pub fn signature(&self) -> String {
+        let parameters: Vec<String> = self
+            .parameters
+            .iter()
+            .map(|parameter| {
+                let mut words = Vec::new();
+                if parameter.optional {
+                    words.push("Optional");
+                }
+                if parameter.param_array {
+                    words.push("ParamArray");
+                } else {
+                    words.push(if parameter.by_val { "ByVal" } else { "ByRef" });
+                }
+                let array = if parameter.is_array { "()" } else { "" };
+
+                format!("{}{} As {}", words.join(" "), array, parameter.type_name)
+            })
+            .collect();
+
+        let mut signature = format!(
+            "{} {}({})",
+            self.kind.keyword(),
+            self.name,
+            parameters.join(", ")
+        );
+        if let Some(return_type) = &self.return_type {
+            signature.push_str(" As ");
+            signature.push_str(return_type);
+        }
+
+        signature
+    }
+
+    /// Checks if code compiled against `self` still works against `other`.
+    pub fn is_compatible_with(&self, other: &Member) -> bool {
+        let same_type = |left: &str, right: &str| left.eq_ignore_ascii_case(right);
+
+        self.kind == other.kind
+            && self.parameters.len() == other.parameters.len()
+            && self
+                .parameters
+                .iter()
+                .zip(&other.parameters)
+                .all(|(left, right)| {
+                    same_type(&left.type_name, &right.type_name)
+                        && left.by_val == right.by_val
+                        && left.optional == right.optional
+                        && left.param_array == right.param_array
+                        && left.is_array == right.is_array
+                })
+            && match (&self.return_type, &other.return_type) {
+                (Some(left), Some(right)) => same_type(left, right),
+                (left, right) => left.is_none() && right.is_none(),
+            }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interface {
+    pub name: String,
+    pub members: Vec<Member>,
+}
+
+impl Interface {
+    /// Reads the public interface of a class from its code.
+    pub fn from_class(name: &str, code_module: &CodeModule) -> Self {
+        let procedures = code_module
+            .procedures
+            .iter()
+            .filter(|procedure| procedure.visibility == Visibility::Public)
+            .map(|procedure| Member {
+                name: procedure.name.clone(),
+                kind: procedure.kind.into(),
+                parameters: procedure.parameters.iter().map(Into::into).collect(),
+                return_type: match procedure.kind {
+                    ProcedureKind::Function | ProcedureKind::PropertyGet => {
+                        Some(type_or_variant(procedure.return_type.as_deref()))
+                    }
+                    _ => None,
+                },
+                line: Some(procedure.line),
+            });
+
+        let events = code_module.events.iter().map(|event| Member {
+            name: event.name.clone(),
+            kind: MemberKind::Event,
+            parameters: event.parameters.iter().map(Into::into).collect(),
+            return_type: None,
+            line: Some(event.line),
+        });
+
+        let variables = code_module
+            .variables
+            .iter()
+            .filter(|variable| variable.visibility == Visibility::Public)
+            .map(|variable| Member {
+                name: variable.name.clone(),
+                kind: MemberKind::Variable,
+                parameters: Vec::new(),
+                return_type: Some(format!(
+                    "{}{}",
+                    type_or_variant(variable.type_name.as_deref()),
+                    if variable.is_array { "()" } else { "" }
+                )),
+                line: Some(variable.line),
+            });
+
+        Interface {
+            name: name.to_owned(),
+            members: procedures.chain(events).chain(variables).collect(),
+        }
+    }
+
+    /// Finds a member by name and kind, ignoring case as VB6 does.
+    pub fn member(&self, name: &str, kind: MemberKind) -> Option<&Member> {
+        self.members
+            .iter()
+            .find(|member| member.kind == kind && member.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// A dump of the public classes in a compiled type library, or the
+/// description of a COM library a project references.
+///
+/// Dumps are JSON documents of the form:
+///
+/// ```json
+/// {
+///   "classes": [
+///     {
+///       "name": "Widget",
+///       "members": [
+///         {
+///           "name": "Render",
+///           "kind": "function",
+///           "parameters": [{ "name": "Scale", "type": "Single", "byval": true }],
+///           "returns": "String"
+///         }
+///       ]
+///     }
+///   ]
+/// }
+/// ```
+///
+/// `kind` is one of `sub`, `function`, `property-get`, `property-let`,
+/// `property-set`, `event`, or `variable`. Parameters may also set
+/// `optional`, `paramarray`, and `array`, which default to `false`, and a
+/// missing `type` is a Variant.
+///
+/// Descriptions of referenced libraries also give the library's `name`, as
+/// code qualifies its types with, and the `guid` and `version` the `.vbp`
+/// refers to it by. They may list `enums`, each with a `name` and the names
+/// of its `members`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeLibrary {
+    pub name: Option<String>,
+    pub guid: Option<String>,
+    pub version: Option<String>,
+    pub enums: Vec<Enumeration>,
+    pub classes: Vec<Interface>,
+}
+
+impl TypeLibrary {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Unable to read type library '{}': {}", path.display(), e))?;
+
+        TypeLibrary::parse(&contents, &path.display().to_string())
+    }
+
+    /// Reads a type library from the contents of `file_name`.
+    fn parse(contents: &str, file_name: &str) -> Result<Self> {
+        let invalid =
+            |reason: String| anyhow!("Type library '{}' is not valid: {}", file_name, reason);
+
+        let document: LibraryDocument =
+            serde_json::from_str(contents).map_err(|e| invalid(e.to_string()))?;
+
+        let classes = document
+            .classes
+            .into_iter()
+            .map(|class| read_interface(class).map_err(invalid))
+            .collect::<Result<_>>()?;
+
+        let enums = document
+            .enums
+            .into_iter()
+            .map(|enumeration| Enumeration {
+                name: enumeration.name,
+                members: enumeration.members,
+            })
+            .collect();
+
+        Ok(TypeLibrary {
+            name: document.name,
+            guid: document.guid,
+            version: document.version,
+            enums,
+            classes,
+        })
+    }
+
+    /// Finds a class by name, ignoring case as VB6 does.
+    pub fn class(&self, name: &str) -> Option<&Interface> {
+        self.classes
+            .iter()
+            .find(|class| class.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// A COM library a project references with a `Reference=` or `Object=`
+/// line, by its GUID, such as `{00000000-0000-0000-0000-000000000000}`, and
+/// its `major.minor` version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryReference {
+    pub guid: String,
+    pub version: String,
+    /// The description of a `Reference=`, or the file name of an `Object=`.
+    pub name: String,
+}
+
+/// The descriptions of COM libraries analyses can use: those that ship with
+/// aspen, and those of in-house libraries given on the command line.
+#[derive(Debug, Clone, Default)]
+pub struct TypeLibraries {
+    libraries: Vec<TypeLibrary>,
+}
+
+impl TypeLibraries {
+    /// Loads the built-in descriptions and those at `paths`, which may be
+    /// `.json` files or directories of them. Descriptions given by the user
+    /// take precedence over built-in ones for the same library.
+    pub fn load(paths: &[PathBuf]) -> Result<Self> {
+        let mut libraries = Vec::new();
+
+        for path in paths {
+            let mut files = vec![path.clone()];
+            if path.is_dir() {
+                files = std::fs::read_dir(path)
+                    .map_err(|e| anyhow!("Unable to read '{}': {}", path.display(), e))?
+                    .filter_map(|entry| Some(entry.ok()?.path()))
+                    .filter(|file| {
+                        file.extension()
+                            .is_some_and(|extension| extension.eq_ignore_ascii_case("json"))
+                    })
+                    .collect();
+                files.sort();
+            }
+
+            for file in files {
+                let library = TypeLibrary::load(&file)?;
+                if library.guid.is_none() || library.version.is_none() {
+                    return Err(anyhow!(
+                        "Type library '{}' is not valid: missing 'guid' or 'version'",
+                        file.display()
+                    ));
+                }
+                libraries.push(library);
+            }
+        }
+
+        for (file_name, contents) in BUILT_IN_LIBRARIES {
+            libraries.push(TypeLibrary::parse(contents, file_name)?);
+        }
+
+        Ok(TypeLibraries { libraries })
+    }
+
+    /// The descriptions of the libraries a project references, in the order
+    /// it references them. Libraries without a description are left out.
+    pub fn referenced(&self, references: &[LibraryReference]) -> Vec<&TypeLibrary> {
+        references
+            .iter()
+            .filter_map(|reference| self.describing(reference))
+            .collect()
+    }
+
+    /// The description of the library a `Reference=` or `Object=` line
+    /// refers to, with the same GUID and major version.
+    ///
+    /// Minor versions only add to a library, so the closest one that is the
+    /// same or newer is preferred, then the closest older one.
+    pub fn describing(&self, reference: &LibraryReference) -> Option<&TypeLibrary> {
+        let (major, minor) = parse_version(&reference.version)?;
+
+        self.libraries
+            .iter()
+            .filter(|library| {
+                library
+                    .guid
+                    .as_deref()
+                    .is_some_and(|guid| guid.eq_ignore_ascii_case(&reference.guid))
+            })
+            .filter_map(|library| {
+                let (library_major, library_minor) = parse_version(library.version.as_deref()?)?;
+                (library_major == major).then_some((library, library_minor))
+            })
+            .min_by_key(|(_, library_minor)| {
+                (*library_minor < minor, library_minor.abs_diff(minor))
+            })
+            .map(|(library, _)| library)
+    }
+}
+
+/// The major and minor parts of a `major.minor` version, which projects
+/// write in hexadecimal.
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let (major, minor) = version.split_once('.')?;
+
+    Some((
+        u32::from_str_radix(major, 16).ok()?,
+        u32::from_str_radix(minor, 16).ok()?,
+    ))
+}
+
+/// Finds the class a type name refers to in `libraries`, which may be
+/// qualified with the library's name as in `ADODB.Recordset`. Unqualified
+/// names are looked up in the order the libraries are referenced, as VB6
+/// does.
+pub fn find_class<'a>(
+    libraries: &[&'a TypeLibrary],
+    type_name: &str,
+) -> Option<(&'a TypeLibrary, &'a Interface)> {
+    let (library_name, class_name) = match type_name.split_once('.') {
+        Some((library_name, class_name)) => (Some(library_name), class_name),
+        None => (None, type_name),
+    };
+
+    libraries
+        .iter()
+        .filter(|library| {
+            library_name.is_none_or(|library_name| {
+                library
+                    .name
+                    .as_deref()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(library_name))
+            })
+        })
+        .find_map(|library| Some((*library, library.class(class_name)?)))
+}
+
+/// A type library description as it is written, before its member kinds
+/// are checked.
+#[derive(Deserialize)]
+struct LibraryDocument {
+    name: Option<String>,
+    guid: Option<String>,
+    version: Option<String>,
+    #[serde(default)]
+    enums: Vec<EnumDocument>,
+    classes: Vec<ClassDocument>,
+}
+
+#[derive(Deserialize)]
+struct EnumDocument {
+    name: String,
+    members: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ClassDocument {
+    name: String,
+    members: Vec<MemberDocument>,
+}
+
+#[derive(Deserialize)]
+struct MemberDocument {
+    name: String,
+    kind: String,
+    #[serde(default)]
+    parameters: Vec<ParameterDocument>,
+    returns: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ParameterDocument {
+    #[serde(default)]
+    name: String,
+    #[serde(rename = "type")]
+    type_name: Option<String>,
+    #[serde(default)]
+    byval: bool,
+    #[serde(default)]
+    optional: bool,
+    #[serde(default)]
+    paramarray: bool,
+    #[serde(default)]
+    array: bool,
+}
+
+fn read_interface(class: ClassDocument) -> Result<Interface, String> {
+    let members = class
+        .members
+        .into_iter()
+        .map(read_member)
+        .collect::<Result<_, _>>()?;
+
+    Ok(Interface {
+        name: class.name,
+        members,
+    })
+}
+
+fn read_member(member: MemberDocument) -> Result<Member, String> {
+    let kind = MemberKind::ALL
+        .into_iter()
+        .find(|kind| kind.id() == member.kind)
+        .ok_or_else(|| {
+            format!(
+                "member '{}' has unknown kind '{}'",
+                member.name, member.kind
+            )
+        })?;
+
+    let parameters = member
+        .parameters
+        .into_iter()
+        .map(|parameter| MemberParameter {
+            name: parameter.name,
+            type_name: type_or_variant(parameter.type_name.as_deref()),
+            by_val: parameter.byval,
+            optional: parameter.optional,
+            param_array: parameter.paramarray,
+            is_array: parameter.array,
+        })
+        .collect();
+
+    Ok(Member {
+        name: member.name,
+        kind,
+        parameters,
+        return_type: member.returns,
+        line: None,
+    })
+}
+
+fn type_or_variant(type_name: Option<&str>) -> String {
+    type_name.unwrap_or("Variant").to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn descriptions_are_read_with_their_defaults() {
+        let library = TypeLibrary::parse(
+            r#"{
+                "name": "Widgets",
+                "classes": [
+                    {
+                        "name": "Widget",
+                        "members": [
+                            {
+                                "name": "Resize",
+                                "kind": "sub",
+                                "parameters": [
+                                    { "name": "Width", "type": "Long", "byval": true },
+                                    { "name": "Height", "optional": true }
+                                ]
+                            },
+                            { "name": "Caption", "kind": "property-get", "returns": "String" }
+                        ]
+                    }
+                ]
+            }"#,
+            "widgets.json",
+        )
+        .unwrap();
+
+        assert_eq!(library.name.as_deref(), Some("Widgets"));
+        assert_eq!(library.guid, None);
+        assert!(library.enums.is_empty());
+
+        let members = &library.classes[0].members;
+        assert_eq!(members[0].kind, MemberKind::Sub);
+        assert_eq!(members[0].parameters[0].type_name, "Long");
+        assert!(members[0].parameters[0].by_val);
+        assert_eq!(members[0].parameters[1].type_name, "Variant");
+        assert!(members[0].parameters[1].optional);
+        assert!(!members[0].parameters[1].by_val);
+        assert_eq!(members[1].kind, MemberKind::PropertyGet);
+        assert_eq!(members[1].return_type.as_deref(), Some("String"));
+
+        for (file_name, contents) in BUILT_IN_LIBRARIES {
+            assert!(
+                TypeLibrary::parse(contents, file_name).is_ok(),
+                "{}",
+                file_name
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_descriptions_are_rejected() {
+        let error = |contents: &str| {
+            TypeLibrary::parse(contents, "bad.json")
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert!(error(r#"{ "enums": [] }"#).starts_with("Type library 'bad.json' is not valid: "));
+        assert_eq!(
+            error(
+                r#"{ "classes": [{ "name": "C", "members": [{ "name": "M", "kind": "macro" }] }] }"#
+            ),
+            "Type library 'bad.json' is not valid: member 'M' has unknown kind 'macro'"
+        );
+        let lone_surrogate = error(r#"{ "classes": [{ "name": "\ud800\u0041", "members": [] }] }"#);
+        assert!(lone_surrogate.contains("surrogate"), "{}", lone_surrogate);
+    }
+
+    #[test]
+    fn references_match_the_closest_minor_version() {
+        let reference = |guid: &str, version: &str| LibraryReference {
+            guid: guid.to_owned(),
+            version: version.to_owned(),
+            name: String::new(),
+        };
+        let type_libraries = TypeLibraries::load(&[]).unwrap();
+        let described = |guid: &str, version: &str| {
+            type_libraries
+                .describing(&reference(guid, version))
+                .and_then(|library| library.name.as_deref())
+        };
+
+        let mscomctl = "{831fdd16-0c5c-11d2-a9fc-0000f8754da1}";
+        assert_eq!(described(mscomctl, "2.0"), Some("MSComctlLib"));
+        assert_eq!(described(mscomctl, "2.2"), Some("MSComctlLib"));
+        assert_eq!(described(mscomctl, "1.3"), None);
+
+        let adodb = "{2A75196C-D9EB-4129-B803-931327F72D5C}";
+        assert_eq!(described(adodb, "2.5"), Some("ADODB"));
+        assert_eq!(described(adodb, "2.6"), Some("ADODB"));
+        assert_eq!(described(adodb, "2.x"), None);
+
+        let library = |version: &str| TypeLibrary {
+            name: Some(version.to_owned()),
+            guid: Some("{00000000-0000-0000-0000-000000000001}".to_owned()),
+            version: Some(version.to_owned()),
+            enums: Vec::new(),
+            classes: Vec::new(),
+        };
+        let type_libraries = TypeLibraries {
+            libraries: vec![library("1.2"), library("1.9"), library("1.5")],
+        };
+        let described = |version: &str| {
+            type_libraries
+                .describing(&reference(
+                    "{00000000-0000-0000-0000-000000000001}",
+                    version,
+                ))
+                .and_then(|library| library.name.as_deref())
+        };
+
+        assert_eq!(described("1.0"), Some("1.2"));
+        assert_eq!(described("1.4"), Some("1.5"));
+        assert_eq!(described("1.5"), Some("1.5"));
+        // Minor versions are hexadecimal, so 1.a is newer than 1.9.
+        assert_eq!(described("1.a"), Some("1.9"));
+        assert_eq!(described("2.0"), None);
+    }
+}
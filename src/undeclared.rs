@@ -0,0 +1,362 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::check::SourceFileKind;
+use crate::code::{identifier_key, CodeModule, Procedure, Visibility};
+use crate::designer::{parse_designer, Control};
+use crate::diagnostic::{Diagnostic, DiagnosticKind, Region};
+use crate::runtime::{class_members, contains, is_built_in};
+use crate::sources::{SourceFile, Sources};
+use crate::typelib::TypeLibrary;
+
+/// Names by their key, as they were declared.
+type Names = BTreeMap<String, String>;
+
+/// Keywords followed by a type, label, or event rather than something the
+/// code declares.
+const NAME_KEYWORDS: [&str; 7] = ["As", "New", "Is", "GoTo", "GoSub", "Resume", "RaiseEvent"];
+
+/// Finds identifiers used in files without `Option Explicit` that nothing
+/// declares, which VB6 silently turns into new `Variant` variables.
+///
+/// A name is declared if it is a local variable, constant or parameter of
+/// the procedure, is declared in its file or a public member of a module, is
+/// a control on its form, is part of the VB6 runtime, or is a class, enum or
+/// constant of a COM library the project references.
+pub fn find_undeclared_identifiers(
+    sources: &Sources,
+    libraries: &[&TypeLibrary],
+) -> Vec<Diagnostic> {
+    let project_names = project_names(sources, libraries);
+    let mut diagnostics = Vec::new();
+
+    for file in &sources.files {
+        let has_option_explicit = file
+            .code
+            .module_statements
+            .iter()
+            .any(|statement| statement.starts_with(&["Option", "Explicit"]));
+        if has_option_explicit {
+            continue;
+        }
+
+        let file_names = file_names(file);
+
+        for procedure in &file.code.procedures {
+            let local_names = local_names(procedure);
+            let mut reported = HashSet::new();
+
+            for statement in &procedure.body {
+                // Declarations, and conditional compilation.
+                if statement.starts_with(&["Dim"])
+                    || statement.starts_with(&["Static"])
+                    || statement.starts_with(&["Const"])
+                    || statement.starts_with(&["#"])
+                {
+                    continue;
+                }
+
+                let tokens = &statement.tokens;
+                for (position, token) in tokens.iter().enumerate() {
+                    if !token.starts_with(|character: char| character.is_alphabetic()) {
+                        continue;
+                    }
+
+                    let previous = position.checked_sub(1).map(|position| &tokens[position]);
+                    let is_member =
+                        previous.is_some_and(|previous| previous == "." || previous == "!");
+                    let is_named =
+                        previous.is_some_and(|previous| contains(&NAME_KEYWORDS, previous));
+                    let is_named_argument =
+                        tokens.get(position + 1).is_some_and(|next| next == ":=");
+                    if is_member || is_named || is_named_argument || is_built_in(token) {
+                        continue;
+                    }
+
+                    let key = identifier_key(token);
+                    let is_declared = [&local_names, &file_names, &project_names]
+                        .iter()
+                        .any(|names| names.contains_key(&key));
+                    if is_declared || !reported.insert(key.clone()) {
+                        continue;
+                    }
+
+                    let suggestion = [&local_names, &file_names, &project_names]
+                        .iter()
+                        .flat_map(|names| names.iter())
+                        .map(|(name_key, name)| (edit_distance(&key, name_key), name))
+                        .filter(|(distance, _)| *distance <= key.len().min(8) / 4)
+                        .min_by_key(|(distance, _)| *distance);
+
+                    let message = match suggestion {
+                        Some((_, name)) => format!(
+                            "'{}' is not declared in '{}', did you mean '{}'?",
+                            token, procedure.name, name
+                        ),
+                        None => format!("'{}' is not declared in '{}'", token, procedure.name),
+                    };
+                    diagnostics.push(
+                        Diagnostic::new(DiagnosticKind::UndeclaredIdentifier, &file.path, message)
+                            .with_region(Some(Region {
+                                line: statement.line,
+                                column: 1,
+                            })),
+                    );
+                }
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// The names every file in the project can use: the files themselves, the
+/// public members of modules, enums and their members, and what referenced
+/// libraries provide.
+fn project_names(sources: &Sources, libraries: &[&TypeLibrary]) -> Names {
+    let mut names = Names::new();
+
+    for library in libraries {
+        if let Some(name) = &library.name {
+            insert(&mut names, name);
+        }
+        for class in &library.classes {
+            insert(&mut names, &class.name);
+        }
+        for enumeration in &library.enums {
+            insert(&mut names, &enumeration.name);
+            for member in &enumeration.members {
+                insert(&mut names, member);
+            }
+        }
+    }
+
+    for file in &sources.files {
+        insert(&mut names, &file.name);
+
+        for enumeration in &file.code.enums {
+            insert(&mut names, &enumeration.name);
+            for member in &enumeration.members {
+                insert(&mut names, member);
+            }
+        }
+
+        if file.kind == SourceFileKind::Module {
+            insert_members(&mut names, &file.code, |visibility| {
+                visibility != Visibility::Private
+            });
+        }
+    }
+
+    names
+}
+
+/// The names a file declares for itself, and for forms their controls and
+/// the properties and methods of the form.
+fn file_names(file: &SourceFile) -> Names {
+    let mut names = Names::new();
+    insert_members(&mut names, &file.code, |_| true);
+
+    if file.kind == SourceFileKind::Form {
+        if let Ok(form) = parse_designer(&file.contents) {
+            insert_controls(&mut names, &form);
+        }
+        for member in class_members("Form") {
+            insert(&mut names, member.name);
+        }
+    }
+
+    names
+}
+
+/// The parameters, local variables and constants of a procedure, its labels,
+/// and its own name, which functions assign their return value to.
+fn local_names(procedure: &Procedure) -> Names {
+    let mut names = Names::new();
+    insert(&mut names, &procedure.name);
+
+    for parameter in &procedure.parameters {
+        insert(&mut names, &parameter.name);
+    }
+    for variable in procedure.local_variables() {
+        insert(&mut names, &variable.name);
+    }
+    for constant in procedure.local_constants() {
+        insert(&mut names, &constant.name);
+    }
+
+    // Labels, whether or not anything jumps to them.
+    for statement in &procedure.body {
+        if let Some(label) = statement.label() {
+            insert(&mut names, label);
+        }
+        for pair in statement.tokens.windows(2) {
+            if contains(&["GoTo", "GoSub", "Resume"], &pair[0]) {
+                insert(&mut names, &pair[1]);
+            }
+        }
+    }
+
+    names
+}
+
+fn insert_members(
+    names: &mut Names,
+    code_module: &CodeModule,
+    is_visible: impl Fn(Visibility) -> bool,
+) {
+    let members = code_module
+        .procedures
+        .iter()
+        .map(|procedure| (&procedure.name, procedure.visibility))
+        .chain(
+            code_module
+                .variables
+                .iter()
+                .map(|variable| (&variable.name, variable.visibility)),
+        )
+        .chain(
+            code_module
+                .constants
+                .iter()
+                .map(|constant| (&constant.name, constant.visibility)),
+        )
+        .chain(
+            code_module
+                .declares
+                .iter()
+                .map(|declare| (&declare.name, declare.visibility)),
+        );
+
+    for (name, visibility) in members {
+        if is_visible(visibility) {
+            insert(names, name);
+        }
+    }
+}
+
+fn insert_controls(names: &mut Names, container: &Control) {
+    for control in &container.children {
+        insert(names, &control.name);
+        insert_controls(names, control);
+    }
+}
+
+fn insert(names: &mut Names, name: &str) {
+    names
+        .entry(identifier_key(name))
+        .or_insert_with(|| name.to_owned());
+}
+
+/// The number of single character insertions, deletions and substitutions
+/// that turn one name into another.
+fn edit_distance(first: &str, second: &str) -> usize {
+    let second: Vec<char> = second.chars().collect();
+    let mut previous: Vec<usize> = (0..=second.len()).collect();
+
+    for (row, first_character) in first.chars().enumerate() {
+        let mut current = vec![row + 1];
+        for (column, second_character) in second.iter().enumerate() {
+            let substitution = previous[column] + usize::from(first_character != *second_character);
+            current.push(
+                substitution
+                    .min(previous[column + 1] + 1)
+                    .min(current[column] + 1),
+            );
+        }
+        previous = current;
+    }
+
+    previous[second.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::testing::{messages, sources};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn typos_without_option_explicit_are_found() {
+        let module = SourceFile::new(
+            SourceFileKind::Module,
+            Path::new("Module1.bas"),
+            concat!(
+                "Attribute VB_Name = \"Module1\"\r\n",
+                "Public Enum Colour\r\n",
+                "  Red = 1\r\n",
+                "End Enum\r\n",
+                "Public Total As Long\r\n",
+                "Public Function Twice(ByVal Value As Long) As Long\r\n",
+                "  Twice = Value * 2\r\n",
+                "End Function\r\n",
+            )
+            .as_bytes()
+            .to_vec(),
+        );
+        let form = SourceFile::new(
+            SourceFileKind::Form,
+            Path::new("frmMain.frm"),
+            concat!(
+                "VERSION 5.00\r\n",
+                "Begin VB.Form frmMain \r\n",
+                "   Begin VB.TextBox txtName \r\n",
+                "   End\r\n",
+                "End\r\n",
+                "Attribute VB_Name = \"frmMain\"\r\n",
+                "Private Sub Form_Load()\r\n",
+                "  Const Limit = 10\r\n",
+                "  Dim Count As Integer\r\n",
+                "  On Error GoTo Failed\r\n",
+                "  Caption = Left$(txtName.Text, Limit) & vbCrLf & Module1.Twice(Red)\r\n",
+                "  Count = Totl + Twice(Count)\r\n",
+                "  MsgBox Prompt:=Mesage, Buttons:=vbOKOnly\r\n",
+                "  Mesage = \"\"\r\n",
+                "Failed:\r\n",
+                "End Sub\r\n",
+            )
+            .as_bytes()
+            .to_vec(),
+        );
+
+        let sources = Sources {
+            project_path: Some(PathBuf::from("App.vbp")),
+            startup: None,
+            is_activex: false,
+            references: Vec::new(),
+            files: vec![module, form],
+        };
+
+        assert_eq!(
+            messages(find_undeclared_identifiers(&sources, &[])),
+            [
+                "12 'Totl' is not declared in 'Form_Load', did you mean 'Total'?",
+                "13 'Mesage' is not declared in 'Form_Load'",
+            ]
+        );
+    }
+
+    #[test]
+    fn labels_are_declared_without_a_goto() {
+        let sources = sources(&[(
+            SourceFileKind::Module,
+            "Module1.bas",
+            concat!(
+                "Attribute VB_Name = \"Module1\"\r\n",
+                "Public Sub Main()\r\n",
+                "Start:\r\n",
+                "  DoEvents: Beep\r\n",
+                "Retry :\r\n",
+                "10 Beep\r\n",
+                "  GoSub 20\r\n",
+                "Cleanup: Beep\r\n",
+                "  Retried = True\r\n",
+                "End Sub\r\n",
+            ),
+        )]);
+
+        assert_eq!(
+            messages(find_undeclared_identifiers(&sources, &[])),
+            ["9 'Retried' is not declared in 'Main'"]
+        );
+    }
+}
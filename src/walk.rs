@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use walkdir::{DirEntry, WalkDir};
+
+use crate::glob::Glob;
+
+/// The ignore files honoured in every directory beneath the search path.
+pub const IGNORE_FILE_NAMES: [&str; 2] = [".gitignore", ".aspenignore"];
+
+/// Decides which directories are descended into and which `.vbp` files are
+/// found while searching a directory for projects.
+#[derive(Debug, Clone)]
+pub struct WalkFilter {
+    /// When non-empty, only project files matching one of these are found.
+    pub include: Vec<Glob>,
+    /// Files and directories matching any of these are skipped entirely.
+    pub exclude: Vec<Glob>,
+    /// Whether `.gitignore` and `.aspenignore` files are honoured.
+    pub use_ignore_files: bool,
+}
+
+impl Default for WalkFilter {
+    /// Finds every project, honouring ignore files as `aspen check` does
+    /// unless `--no-ignore` is given.
+    fn default() -> Self {
+        WalkFilter {
+            include: Vec::new(),
+            exclude: Vec::new(),
+            use_ignore_files: true,
+        }
+    }
+}
+
+impl WalkFilter {
+    /// Walks `root`, pruning excluded and ignored directories as it goes.
+    ///
+    /// Walk errors are passed through so they can be reported.
+    pub fn walk<'a>(
+        &'a self,
+        root: &'a Path,
+    ) -> impl Iterator<Item = walkdir::Result<DirEntry>> + 'a {
+        let mut ignore_rules: HashMap<PathBuf, Gitignore> = HashMap::new();
+
+        WalkDir::new(root).into_iter().filter_entry(move |entry| {
+            entry.depth() == 0 || !self.is_skipped(root, entry, &mut ignore_rules)
+        })
+    }
+
+    /// Checks if a found project file passes the `include` globs.
+    pub fn is_included(&self, root: &Path, path: &Path) -> bool {
+        if self.include.is_empty() {
+            return true;
+        }
+
+        let relative_path = relative_path(root, path);
+
+        self.include
+            .iter()
+            .any(|glob| glob.matches(&relative_path, false))
+    }
+
+    fn is_skipped(
+        &self,
+        root: &Path,
+        entry: &DirEntry,
+        ignore_rules: &mut HashMap<PathBuf, Gitignore>,
+    ) -> bool {
+        let is_dir = entry.file_type().is_dir();
+        let relative = relative_path(root, entry.path());
+
+        if self
+            .exclude
+            .iter()
+            .any(|glob| glob.matches(&relative, is_dir))
+        {
+            return true;
+        }
+
+        if !self.use_ignore_files {
+            return false;
+        }
+
+        // Rules in deeper directories override the rules of their parents.
+        for directory in entry.path().ancestors().skip(1) {
+            if !directory.starts_with(root) {
+                break;
+            }
+
+            let rules = ignore_rules
+                .entry(directory.to_path_buf())
+                .or_insert_with(|| load_ignore_rules(directory));
+
+            match rules.matched(entry.path(), is_dir) {
+                Match::None => continue,
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+            }
+        }
+
+        false
+    }
+}
+
+/// Returns `path` relative to `root` with `/` separators, for glob matching.
+pub fn relative_path(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Makes `path` absolute against the current directory, resolving `.` and
+/// `..` components without touching the file system.
+pub fn absolute_path(path: &Path) -> PathBuf {
+    let path = match std::env::current_dir() {
+        Ok(current_dir) if path.is_relative() => current_dir.join(path),
+        _ => path.to_path_buf(),
+    };
+
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+fn load_ignore_rules(directory: &Path) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(directory);
+    let _ = builder.case_insensitive(true);
+
+    for ignore_file_name in IGNORE_FILE_NAMES {
+        let ignore_file = directory.join(ignore_file_name);
+
+        // A missing or unreadable ignore file simply contributes no rules,
+        // and invalid lines are skipped the same way git skips them.
+        if ignore_file.is_file() {
+            builder.add(ignore_file);
+        }
+    }
+
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found_projects(filter: &WalkFilter, root: &Path) -> Vec<String> {
+        let mut found: Vec<_> = filter
+            .walk(root)
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension() == Some("vbp".as_ref()))
+            .filter(|entry| filter.is_included(root, entry.path()))
+            .map(|entry| relative_path(root, entry.path()))
+            .collect();
+
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn excluded_and_ignored_projects_are_skipped() {
+        let root = std::env::temp_dir()
+            .join(format!("aspen-walk-{}", std::process::id()))
+            .join("excluded_and_ignored_projects_are_skipped");
+        let _ = std::fs::remove_dir_all(&root);
+
+        for directory in ["src/Old", "bin", "legacy/keep"] {
+            std::fs::create_dir_all(root.join(directory)).unwrap();
+        }
+        for project in [
+            "src/Project1.vbp",
+            "src/Old/Project1.vbp",
+            "bin/Project1.vbp",
+            "legacy/Legacy.vbp",
+            "legacy/keep/Keep.vbp",
+        ] {
+            std::fs::write(root.join(project), "Type=Exe\r\n").unwrap();
+        }
+        std::fs::write(root.join(".gitignore"), "bin/\n").unwrap();
+        std::fs::write(root.join("legacy/.aspenignore"), "*.vbp\n!Keep.vbp\n").unwrap();
+
+        // Ignore files are honoured by default, as they are by the CLI.
+        let mut filter = WalkFilter {
+            exclude: vec![Glob::new("Old").unwrap()],
+            ..WalkFilter::default()
+        };
+
+        assert_eq!(
+            found_projects(&filter, &root),
+            vec!["legacy/keep/Keep.vbp", "src/Project1.vbp"]
+        );
+
+        filter.use_ignore_files = false;
+        filter.include = vec![Glob::new("bin/*.vbp").unwrap()];
+
+        assert_eq!(found_projects(&filter, &root), vec!["bin/Project1.vbp"]);
+    }
+}
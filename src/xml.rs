@@ -0,0 +1,40 @@
+//! Escaping shared by the XML and SVG reports.
+
+/// Escapes text for use in XML content or a quoted attribute.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Other control characters aren't allowed in XML 1.0 at all.
+            '\t' | '\n' | '\r' => escaped.push(character),
+            character if (character as u32) < 0x20 => escaped.push('\u{FFFD}'),
+            character => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markup_and_control_characters_are_escaped() {
+        assert_eq!(
+            escape("<a href=\"x\">Tom & Jerry's</a>"),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&apos;s&lt;/a&gt;"
+        );
+        assert_eq!(escape("Line 1\r\n\tLine 2"), "Line 1\r\n\tLine 2");
+        assert_eq!(
+            escape("Bell\u{7}Escape\u{1B}"),
+            "Bell\u{FFFD}Escape\u{FFFD}"
+        );
+    }
+}